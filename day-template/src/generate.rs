@@ -0,0 +1,7 @@
+use rand::Rng;
+use std::io::Write;
+
+/// Generate a random puzzle input.
+pub fn generate(_rng: &mut impl Rng, _scale: usize, _out: &mut dyn Write) -> std::io::Result<()> \{
+    unimplemented!()
+}
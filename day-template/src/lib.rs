@@ -1,8 +1,11 @@
-use aoc2020::parse;
+use aoc2020::\{input::parse_str, parse};
 
 use std::path::Path;
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 pub fn part1(input: &Path) -> Result<(), Error> \{
     unimplemented!()
 }
@@ -11,6 +14,12 @@ pub fn part2(_input: &Path) -> Result<(), Error> \{
     unimplemented!()
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) \{
+    parse_str::<String>(input).for_each(drop);
+}
+
 #[derive(Debug, Error)]
 pub enum Error \{
     #[error(transparent)]
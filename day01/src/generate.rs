@@ -0,0 +1,64 @@
+use rand::{seq::SliceRandom, Rng};
+use std::{collections::HashSet, io::Write};
+
+const TARGET: i64 = 2020;
+const BASE_LEN: usize = 200;
+
+/// Exactly one pair and exactly one triple sum to the target.
+fn has_unique_solutions(data: &[i64]) -> bool {
+    let set: HashSet<_> = data.iter().copied().collect();
+    let pairs = data
+        .iter()
+        .filter(|&&a| a * 2 != TARGET && set.contains(&(TARGET - a)))
+        .count();
+    let mut triples = 0;
+    for (i, &a) in data.iter().enumerate() {
+        for &b in &data[i + 1..] {
+            let c = TARGET - a - b;
+            if c > b.max(a) && set.contains(&c) {
+                triples += 1;
+            }
+        }
+    }
+    // each pair is counted once from each side
+    pairs == 2 && triples == 1
+}
+
+/// Generate a list of expense report entries.
+///
+/// Most entries are greater than half the target, so that they cannot participate
+/// in any solution. Entries are distinct, so `scale` is capped at about 5.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let len = (BASE_LEN * scale).min(900);
+    let mut data = loop {
+        let a = rng.gen_range(100..TARGET / 2);
+        let x = rng.gen_range(100..TARGET / 3);
+        let y = rng.gen_range(100..TARGET - x - 100);
+        let fixed = [a, TARGET - a, x, y, TARGET - x - y];
+
+        // fillers must not complete any pair or triple with the fixed entries
+        let mut forbidden: HashSet<_> = fixed.iter().copied().collect();
+        for &s in &fixed {
+            forbidden.insert(TARGET - s);
+            for &t in &fixed {
+                forbidden.insert(TARGET - s - t);
+            }
+        }
+        let mut fillers: Vec<_> = (TARGET / 2 + 1..TARGET)
+            .filter(|filler| !forbidden.contains(filler))
+            .collect();
+        fillers.shuffle(rng);
+
+        let mut data = fixed.to_vec();
+        data.extend(fillers.into_iter().take(len - fixed.len()));
+        if has_unique_solutions(&data) {
+            break data;
+        }
+    };
+
+    data.shuffle(rng);
+    for datum in data {
+        writeln!(out, "{}", datum)?;
+    }
+    Ok(())
+}
@@ -1,9 +1,12 @@
-use aoc2020::parse;
+use aoc2020::{input::parse_str, parse};
 
 use std::collections::HashSet;
 use std::path::Path;
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 fn find_pair_summing_to(data: &HashSet<i64>, sum: i64) -> Option<(i64, i64)> {
     for datum in data {
         let want = sum - *datum;
@@ -48,6 +51,12 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    parse_str::<i64>(input).for_each(drop);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
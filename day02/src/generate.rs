@@ -0,0 +1,32 @@
+use rand::Rng;
+use std::io::Write;
+
+const BASE_LEN: usize = 1000;
+
+/// Generate a list of password policies and example passwords.
+///
+/// Both positions named by each policy are always within the password.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    for _ in 0..BASE_LEN * scale {
+        let len = rng.gen_range(2..=20);
+        let min_count = rng.gen_range(1..len);
+        let max_count = rng.gen_range(min_count + 1..=len);
+        let char_counted = rng.gen_range(b'a'..=b'z');
+        // bias the password towards the counted character so that both parts find some valid ones
+        let example: String = (0..len)
+            .map(|_| {
+                if rng.gen_bool(0.4) {
+                    char_counted as char
+                } else {
+                    rng.gen_range(b'a'..=b'z') as char
+                }
+            })
+            .collect();
+        writeln!(
+            out,
+            "{}-{} {}: {}",
+            min_count, max_count, char_counted as char, example
+        )?;
+    }
+    Ok(())
+}
@@ -1,8 +1,11 @@
-use aoc2020::parse;
+use aoc2020::{input::parse_str, parse};
 
 use std::path::Path;
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 #[derive(parse_display::Display, parse_display::FromStr)]
 #[display("{min_count}-{max_count} {char_counted}: {example}")]
 struct PasswordPolicy {
@@ -22,10 +25,16 @@ impl PasswordPolicy {
         ch_count >= self.min_count as usize && ch_count <= self.max_count as usize
     }
 
-    fn is_valid_part2(&self) -> bool {
-        let example = self.example.as_bytes();
-        (example[self.min_count as usize - 1] == self.char_counted as u8)
-            ^ (example[self.max_count as usize - 1] == self.char_counted as u8)
+    /// In part 2, `min_count` and `max_count` are 1-based positions within the example.
+    fn is_valid_part2(&self) -> Result<bool, Error> {
+        let matches_at = |position: u32| {
+            (position as usize)
+                .checked_sub(1)
+                .and_then(|idx| self.example.chars().nth(idx))
+                .map(|ch| ch == self.char_counted)
+                .ok_or_else(|| Error::PositionOutOfRange(position, self.example.clone()))
+        };
+        Ok(matches_at(self.min_count)? ^ matches_at(self.max_count)?)
     }
 }
 
@@ -38,15 +47,45 @@ pub fn part1(input: &Path) -> Result<(), Error> {
 }
 
 pub fn part2(input: &Path) -> Result<(), Error> {
-    let n_valid = parse::<PasswordPolicy>(input)?
-        .filter(|example| example.is_valid_part2())
-        .count();
+    let mut n_valid = 0;
+    for policy in parse::<PasswordPolicy>(input)? {
+        if policy.is_valid_part2()? {
+            n_valid += 1;
+        }
+    }
     println!("{} valid passwords (part 2)", n_valid);
     Ok(())
 }
 
+/// Fuzzing entry point: parse and check `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    for policy in parse_str::<PasswordPolicy>(input).flatten() {
+        policy.is_valid();
+        let _ = policy.is_valid_part2();
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    #[error("position {0} is not within password {1:?}")]
+    PositionOutOfRange(u32, String),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_part2_positions_out_of_range() {
+        for policy in &["0-1 a: abc", "1-4 a: abc", "1-2 a: a"] {
+            let policy: PasswordPolicy = policy.parse().unwrap();
+            assert!(matches!(
+                policy.is_valid_part2(),
+                Err(Error::PositionOutOfRange(..))
+            ));
+        }
+    }
 }
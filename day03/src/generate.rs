@@ -0,0 +1,16 @@
+use rand::Rng;
+use std::io::Write;
+
+const WIDTH: usize = 31;
+const BASE_HEIGHT: usize = 323;
+
+/// Generate a map of trees, growing taller with `scale`.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    for _ in 0..BASE_HEIGHT * scale {
+        let row: String = (0..WIDTH)
+            .map(|_| if rng.gen_bool(0.25) { '#' } else { '.' })
+            .collect();
+        writeln!(out, "{}", row)?;
+    }
+    Ok(())
+}
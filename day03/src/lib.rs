@@ -1,9 +1,12 @@
-use aoc2020::geometry::{tile::DisplayWidth, Map, Point};
+use aoc2020::geometry::{tile::DisplayWidth, Map, Point, Wrap};
 
-use std::convert::{TryFrom, TryInto};
+use std::convert::TryFrom;
 use std::path::Path;
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug, parse_display::FromStr, parse_display::Display)]
 enum Tile {
     #[display("#")]
@@ -16,33 +19,15 @@ impl DisplayWidth for Tile {
     const DISPLAY_WIDTH: usize = 1;
 }
 
-struct XWrapMap(Map<Tile>);
-
-impl std::ops::Index<Point> for XWrapMap {
-    type Output = Tile;
-
-    fn index(&self, mut point: Point) -> &Tile {
-        point.x %= self.0.width().try_into().unwrap_or(i32::MAX);
-        self.0.index(point)
-    }
-}
-
-fn count_trees(map: &XWrapMap, slope: Point) -> u64 {
-    let mut check = map.0.top_left();
-
-    let mut n_trees = 0;
-    while check.y >= 0 {
-        if map[check] == Tile::Tree {
-            n_trees += 1;
-        }
-        check += slope;
-    }
-
-    n_trees
+fn count_trees(map: &Map<Tile>, slope: Point) -> u64 {
+    map.wrapping(Wrap::X)
+        .project(map.top_left(), slope.x, slope.y)
+        .filter(|&point| map[point] == Tile::Tree)
+        .count() as u64
 }
 
 pub fn part1(input: &Path) -> Result<(), Error> {
-    let map = XWrapMap(Map::try_from(input)?);
+    let map = Map::try_from(input)?;
     let slope = Point::new(3, -1);
     let n_trees = count_trees(&map, slope);
 
@@ -52,7 +37,7 @@ pub fn part1(input: &Path) -> Result<(), Error> {
 }
 
 pub fn part2(input: &Path) -> Result<(), Error> {
-    let map = XWrapMap(Map::try_from(input)?);
+    let map = Map::try_from(input)?;
 
     let slopes = [
         Point::new(1, -1),
@@ -72,8 +57,40 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    let _ = Map::<Tile>::try_from(input);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
     Io(#[from] std::io::Error),
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#
+";
+
+    #[test]
+    fn example() {
+        let map = Map::try_from(EXAMPLE.trim()).unwrap();
+        assert_eq!(count_trees(&map, Point::new(3, -1)), 7);
+        assert_eq!(count_trees(&map, Point::new(1, -2)), 2);
+    }
+}
@@ -0,0 +1,80 @@
+use rand::{seq::SliceRandom, Rng};
+use std::io::Write;
+
+const BASE_LEN: usize = 300;
+const EYE_COLORS: &[&str] = &["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+fn hex_digits(rng: &mut impl Rng, n: usize) -> String {
+    (0..n)
+        .map(|_| std::char::from_digit(rng.gen_range(0..16), 16).unwrap())
+        .collect()
+}
+
+fn decimal_digits(rng: &mut impl Rng, n: usize) -> String {
+    (0..n)
+        .map(|_| std::char::from_digit(rng.gen_range(0..10), 10).unwrap())
+        .collect()
+}
+
+/// Produce a value for `key`, which is valid if `valid` is set.
+///
+/// Years are always numeric, because the parser rejects non-numeric years outright.
+fn field(rng: &mut impl Rng, key: &str, valid: bool) -> String {
+    match (key, valid) {
+        ("byr", true) => rng.gen_range(1920..=2002).to_string(),
+        ("byr", false) => rng.gen_range(1900..1920).to_string(),
+        ("iyr", true) => rng.gen_range(2010..=2020).to_string(),
+        ("iyr", false) => rng.gen_range(2021..2030).to_string(),
+        ("eyr", true) => rng.gen_range(2020..=2030).to_string(),
+        ("eyr", false) => rng.gen_range(2000..2020).to_string(),
+        ("hgt", true) if rng.gen() => format!("{}cm", rng.gen_range(150..=193)),
+        ("hgt", true) => format!("{}in", rng.gen_range(59..=76)),
+        ("hgt", false) => match rng.gen_range(0..3) {
+            0 => format!("{}cm", rng.gen_range(194..250)),
+            1 => format!("{}in", rng.gen_range(20..59)),
+            _ => rng.gen_range(50..200).to_string(),
+        },
+        ("hcl", true) => format!("#{}", hex_digits(rng, 6)),
+        ("hcl", false) => hex_digits(rng, 6),
+        ("ecl", true) => EYE_COLORS.choose(rng).unwrap().to_string(),
+        ("ecl", false) => "xry".to_string(),
+        ("pid", true) => decimal_digits(rng, 9),
+        ("pid", false) => decimal_digits(rng, 10),
+        ("cid", _) => rng.gen_range(100..350).to_string(),
+        _ => unreachable!("unknown passport field {}", key),
+    }
+}
+
+/// Generate a batch of passports.
+///
+/// Some passports are missing fields, and some have fields with invalid values.
+/// Fields appear in random order, wrapped across random lines.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let mut keys = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
+    for idx in 0..BASE_LEN * scale {
+        keys.shuffle(rng);
+        let n_fields = if rng.gen_bool(0.7) {
+            keys.len()
+        } else {
+            rng.gen_range(4..keys.len())
+        };
+        let valid_rate = if rng.gen_bool(0.6) { 1.0 } else { 0.8 };
+
+        if idx > 0 {
+            writeln!(out)?;
+        }
+        for (field_idx, key) in keys[..n_fields].iter().enumerate() {
+            if field_idx > 0 {
+                if rng.gen_bool(0.3) {
+                    writeln!(out)?;
+                } else {
+                    write!(out, " ")?;
+                }
+            }
+            let valid = rng.gen_bool(valid_rate);
+            write!(out, "{}:{}", key, field(rng, key, valid))?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
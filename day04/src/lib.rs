@@ -1,4 +1,4 @@
-use aoc2020::input::parse_newline_sep;
+use aoc2020::input::{parse_newline_sep, parse_newline_sep_str};
 
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -6,6 +6,9 @@ use std::path::Path;
 use std::str::FromStr;
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 #[cfg(feature = "emit_json")]
 use serde::{Deserialize, Serialize};
 
@@ -150,6 +153,15 @@ pub fn emit_json(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse and check `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    for passport in parse_newline_sep_str::<Passport>(input).flatten() {
+        passport.has_northpole_fields();
+        passport.is_valid();
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -0,0 +1,36 @@
+use rand::{seq::SliceRandom, Rng};
+use std::io::Write;
+
+const N_SEATS: u16 = 128 * 8;
+const BASE_LEN: usize = 800;
+
+fn encode(seat_id: u16) -> String {
+    (0..10)
+        .rev()
+        .map(|bit| {
+            let set = seat_id & (1 << bit) != 0;
+            match (bit >= 3, set) {
+                (true, false) => 'F',
+                (true, true) => 'B',
+                (false, false) => 'L',
+                (false, true) => 'R',
+            }
+        })
+        .collect()
+}
+
+/// Generate boarding passes for a contiguous block of seats, except for exactly one.
+///
+/// There are only 1024 seats on the plane, so `scale` is capped at 1.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let len = (BASE_LEN * scale).min(N_SEATS as usize - 24) as u16;
+    let low = rng.gen_range(1..N_SEATS - len);
+    let missing = rng.gen_range(low + 1..low + len - 1);
+
+    let mut seats: Vec<_> = (low..low + len).filter(|&seat| seat != missing).collect();
+    seats.shuffle(rng);
+    for seat in seats {
+        writeln!(out, "{}", encode(seat))?;
+    }
+    Ok(())
+}
@@ -1,8 +1,11 @@
-use aoc2020::parse;
+use aoc2020::{input::parse_str, parse};
 
 use std::path::Path;
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 #[derive(Debug, parse_display::Display, parse_display::FromStr)]
 #[from_str(regex = "^(?P<0>[FB]{7}[LR]{3})$")]
 pub struct BoardingPass(String);
@@ -59,6 +62,14 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse and check `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    for boarding_pass in parse_str::<BoardingPass>(input).flatten() {
+        boarding_pass.seat_id();
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
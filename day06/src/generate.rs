@@ -0,0 +1,32 @@
+use rand::{seq::SliceRandom, Rng};
+use std::io::Write;
+
+const BASE_LEN: usize = 500;
+
+/// Generate groups of customs declaration answers.
+///
+/// Group members tend to share some answers, so that intersections are not always empty.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let mut alphabet: Vec<char> = ('a'..='z').collect();
+    for idx in 0..BASE_LEN * scale {
+        if idx > 0 {
+            writeln!(out)?;
+        }
+        alphabet.shuffle(rng);
+        let n_shared = rng.gen_range(0..6);
+        for _ in 0..rng.gen_range(1..=5) {
+            let n_own = rng.gen_range(0..8);
+            let mut answers: Vec<_> = alphabet[..n_shared]
+                .iter()
+                .chain(alphabet[n_shared..].choose_multiple(rng, n_own))
+                .copied()
+                .collect();
+            if answers.is_empty() {
+                answers.push(alphabet[n_shared]);
+            }
+            answers.shuffle(rng);
+            writeln!(out, "{}", answers.into_iter().collect::<String>())?;
+        }
+    }
+    Ok(())
+}
@@ -1,9 +1,12 @@
-use aoc2020::input::parse_newline_sep;
+use aoc2020::input::{parse_newline_sep, parse_newline_sep_str};
 
 use std::collections::HashSet;
 use std::path::Path;
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 struct CustomsDeclarationForm(Vec<HashSet<char>>);
 
 impl std::str::FromStr for CustomsDeclarationForm {
@@ -53,6 +56,15 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse and check `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    for form in parse_newline_sep_str::<CustomsDeclarationForm>(input).flatten() {
+        form.union();
+        form.intersection();
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -0,0 +1,193 @@
+use crate::MY_BAG;
+use rand::{seq::SliceRandom, Rng};
+use std::{collections::HashSet, io::Write};
+
+const BASE_LEN: usize = 594;
+const MAX_LEVEL: usize = 8;
+const MY_LEVEL: usize = 4;
+
+const ADJECTIVES: &[&str] = &[
+    "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "striped", "light",
+    "mirrored", "muted", "pale", "plaid", "posh", "vibrant", "wavy", "dusty", "shiny", "bold",
+];
+
+const COLORS: &[&str] = &[
+    "aqua",
+    "beige",
+    "black",
+    "blue",
+    "bronze",
+    "brown",
+    "chartreuse",
+    "coral",
+    "crimson",
+    "cyan",
+    "fuchsia",
+    "gold",
+    "gray",
+    "green",
+    "indigo",
+    "lavender",
+    "lime",
+    "magenta",
+    "maroon",
+    "olive",
+    "orange",
+    "plum",
+    "purple",
+    "red",
+    "salmon",
+    "silver",
+    "tan",
+    "teal",
+    "tomato",
+    "turquoise",
+    "violet",
+    "white",
+    "yellow",
+];
+
+/// Generate `n` distinct bag colors, not including `MY_BAG`.
+///
+/// Once two-word names start to run out, names gain a second adjective.
+fn bag_names(rng: &mut impl Rng, n: usize) -> Vec<String> {
+    let two_word_limit = ADJECTIVES.len() * COLORS.len() * 3 / 4;
+    let mut names = HashSet::with_capacity(n + 1);
+    names.insert(MY_BAG.to_string());
+    while names.len() <= n {
+        let adjective = ADJECTIVES.choose(rng).unwrap();
+        let color = COLORS.choose(rng).unwrap();
+        let name = if names.len() < two_word_limit {
+            format!("{} {}", adjective, color)
+        } else {
+            format!(
+                "{} {} {}",
+                ADJECTIVES.choose(rng).unwrap(),
+                adjective,
+                color
+            )
+        };
+        names.insert(name);
+    }
+    names.remove(MY_BAG);
+    let mut names: Vec<_> = names.into_iter().collect();
+    // hash set iteration order is not determined by the seed
+    names.sort_unstable();
+    names.shuffle(rng);
+    names
+}
+
+/// Generate an acyclic set of luggage rules.
+///
+/// Every bag is assigned a level, and may contain only bags of strictly lower levels.
+/// `MY_BAG` sits in the middle, so it both contains and is contained by other bags.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let mut bags: Vec<(String, usize)> = bag_names(rng, BASE_LEN * scale - 1)
+        .into_iter()
+        .enumerate()
+        .map(|(idx, name)| {
+            // ensure that there is always at least one bag at the extreme levels
+            let level = match idx {
+                0 => 0,
+                1 => MAX_LEVEL,
+                _ => rng.gen_range(0..=MAX_LEVEL),
+            };
+            (name, level)
+        })
+        .collect();
+    bags.push((MY_BAG.to_string(), MY_LEVEL));
+
+    let mut lines = Vec::with_capacity(bags.len());
+    for (name, level) in &bags {
+        let candidates: Vec<_> = bags
+            .iter()
+            .filter(|(_, candidate_level)| candidate_level < level)
+            .map(|(candidate, _)| candidate)
+            .collect();
+        let n_children = if *level == 0 {
+            0
+        } else {
+            rng.gen_range(1..=4.min(candidates.len()))
+        };
+        let mut children: Vec<_> = candidates
+            .choose_multiple(rng, n_children)
+            .copied()
+            .collect();
+        // the topmost bag always contains ours, so we always have some containers
+        if *level == MAX_LEVEL && !children.iter().any(|child| *child == MY_BAG) {
+            children[0] = &bags.last().unwrap().0;
+        }
+
+        let contents = if children.is_empty() {
+            "no other bags".to_string()
+        } else {
+            children
+                .iter()
+                .map(|child| {
+                    let qty = rng.gen_range(1..=5);
+                    format!("{} {} bag{}", qty, child, if qty == 1 { "" } else { "s" })
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        lines.push(format!("{} bags contain {}.", name, contents));
+    }
+
+    lines.shuffle(rng);
+    for line in lines {
+        writeln!(out, "{}", line)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::LuggageRule;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_generated_rules_are_acyclic() {
+        for seed in 0..8 {
+            let mut buf = Vec::new();
+            generate(&mut StdRng::seed_from_u64(seed), 1, &mut buf).unwrap();
+            let rules: HashMap<_, _> = String::from_utf8(buf)
+                .unwrap()
+                .lines()
+                .map(|line| line.parse::<LuggageRule>().unwrap())
+                .map(|rule| (rule.outer_color.clone(), rule.contents))
+                .collect();
+            assert_eq!(rules.len(), BASE_LEN);
+            assert!(!rules[MY_BAG].is_empty());
+            assert!(rules
+                .values()
+                .any(|contents| contents.iter().any(|(_, color)| color == MY_BAG)));
+
+            // Kahn's algorithm consumes every bag iff there are no cycles
+            let mut n_containers: HashMap<&str, usize> = HashMap::new();
+            for contents in rules.values() {
+                for (_, color) in contents {
+                    assert!(rules.contains_key(color));
+                    *n_containers.entry(color).or_default() += 1;
+                }
+            }
+            let mut queue: Vec<_> = rules
+                .keys()
+                .filter(|color| !n_containers.contains_key(color.as_str()))
+                .collect();
+            let mut n_visited = 0;
+            while let Some(color) = queue.pop() {
+                n_visited += 1;
+                for (_, child) in &rules[color] {
+                    let count = n_containers.get_mut(child.as_str()).unwrap();
+                    *count -= 1;
+                    if *count == 0 {
+                        queue.push(child);
+                    }
+                }
+            }
+            assert_eq!(n_visited, rules.len());
+        }
+    }
+}
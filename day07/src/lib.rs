@@ -1,4 +1,4 @@
-use aoc2020::parse;
+use aoc2020::{input::parse_str, parse};
 
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -6,6 +6,9 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::Path;
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 lazy_static! {
     static ref LUGGAGE_OUTER_RE: Regex = Regex::new(r"^(?P<outer_color>.*) bags contain").unwrap();
     static ref LUGGAGE_INNER_RE: Regex =
@@ -31,14 +34,12 @@ impl std::str::FromStr for LuggageRule {
         let contents = LUGGAGE_INNER_RE
             .captures_iter(s)
             .map(|capture| {
-                (
-                    capture["qty"]
-                        .parse::<u32>()
-                        .expect("regex guarantees positive integers"),
-                    capture["color"].to_string(),
-                )
+                let qty = capture["qty"]
+                    .parse::<u32>()
+                    .map_err(|err| format!("parsing quantity {:?}: {}", &capture["qty"], err))?;
+                Ok((qty, capture["color"].to_string()))
             })
-            .collect();
+            .collect::<Result<_, String>>()?;
 
         Ok(LuggageRule {
             outer_color,
@@ -155,6 +156,12 @@ pub fn exhaustive_quantize(input: &Path, n: usize) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    parse_str::<LuggageRule>(input).for_each(drop);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -0,0 +1,104 @@
+use crate::{Error, HandheldGameConsole, Instruction, Operation};
+use rand::{seq::SliceRandom, Rng};
+use std::io::Write;
+
+const BASE_LEN: usize = 600;
+
+fn terminates(instructions: Vec<Instruction>) -> bool {
+    let len = instructions.len() as i64;
+    matches!(
+        HandheldGameConsole::new(instructions).run(),
+        Err(Error::InstructionPointerOutOfRange(ip, _)) if ip == len
+    )
+}
+
+/// Count the single jmp/nop inversions which cause the program to terminate.
+fn count_repairs(instructions: &[Instruction]) -> usize {
+    (0..instructions.len())
+        .filter(|&idx| instructions[idx].operation.is_jmp_nop())
+        .filter(|&idx| {
+            let mut modified = instructions.to_vec();
+            modified[idx].operation.invert_jmp_nop();
+            terminates(modified)
+        })
+        .count()
+}
+
+/// Build a terminating program, returning it along with the indices of the executed path.
+///
+/// Instructions off the executed path are traps which mostly jump backwards into loops.
+fn terminating_program(rng: &mut impl Rng, len: usize) -> (Vec<Instruction>, Vec<usize>) {
+    let mut instructions: Vec<_> = (0..len)
+        .map(|idx| {
+            if rng.gen_bool(0.6) {
+                Instruction {
+                    operation: Operation::Jmp,
+                    argument: -rng.gen_range(0..=idx.min(20) as i64),
+                }
+            } else {
+                Instruction {
+                    operation: Operation::Acc,
+                    argument: rng.gen_range(-50..=50),
+                }
+            }
+        })
+        .collect();
+
+    let mut path = Vec::new();
+    let mut idx = 0;
+    while idx < len {
+        let (operation, argument, next) = match rng.gen_range(0..20) {
+            0..=8 => (Operation::Acc, rng.gen_range(-50..=50), idx + 1),
+            9..=14 => {
+                let argument = rng.gen_range(1..=6.min(len - idx));
+                (Operation::Jmp, argument as i64, idx + argument)
+            }
+            _ => {
+                // inverting this would jump backwards into a loop
+                let argument = match path.choose(rng) {
+                    Some(&target) => target as i64 - idx as i64,
+                    None => rng.gen_range(1..=6),
+                };
+                (Operation::Nop, argument, idx + 1)
+            }
+        };
+        instructions[idx] = Instruction {
+            operation,
+            argument,
+        };
+        path.push(idx);
+        idx = next;
+    }
+
+    (instructions, path)
+}
+
+/// Generate a boot program which loops, but which can be repaired by inverting exactly one
+/// jmp or nop instruction.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let len = BASE_LEN * scale;
+    let instructions = loop {
+        let (mut instructions, path) = terminating_program(rng, len);
+        let corruptible: Vec<_> = path
+            .iter()
+            .copied()
+            .filter(|&idx| {
+                instructions[idx].operation == Operation::Nop && instructions[idx].argument < 0
+            })
+            .collect();
+        let corrupt = match corruptible.choose(rng) {
+            Some(&idx) => idx,
+            None => continue,
+        };
+        instructions[corrupt].operation = Operation::Jmp;
+
+        if !terminates(instructions.clone()) && count_repairs(&instructions) == 1 {
+            break instructions;
+        }
+    };
+
+    for instruction in instructions {
+        writeln!(out, "{} {:+}", instruction.operation, instruction.argument)?;
+    }
+    Ok(())
+}
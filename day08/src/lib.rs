@@ -1,9 +1,12 @@
-use aoc2020::parse;
+use aoc2020::{input::parse_str, parse};
 
 use bitvec::{bitvec, order::Lsb0, vec::BitVec};
 use std::path::Path;
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::Display, parse_display::FromStr)]
 #[display(style = "snake_case")]
 pub enum Operation {
@@ -14,10 +17,7 @@ pub enum Operation {
 
 impl Operation {
     fn is_jmp_nop(&self) -> bool {
-        match self {
-            Self::Jmp | Self::Nop => true,
-            _ => false,
-        }
+        matches!(self, Self::Jmp | Self::Nop)
     }
 
     fn invert_jmp_nop(&mut self) {
@@ -143,6 +143,12 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    parse_str::<Instruction>(input).for_each(drop);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -0,0 +1,65 @@
+use crate::{xmas_valid, DEFAULT_PREAMBLE_LEN};
+use rand::{seq::SliceRandom, Rng};
+use std::io::Write;
+
+const BASE_LEN: usize = 1000;
+/// Every item is the sum of two of its predecessors, so items grow exponentially.
+/// Generation stops early once they reach this bound, which keeps all sums well within `u64`.
+const MAX_ITEM: u64 = 1_000_000_000_000_000;
+
+/// Pick the sum of two distinct entries from among the smallest few in the window.
+///
+/// Always choosing small pairs keeps the growth rate down: the items roughly double every
+/// preamble length.
+fn next_valid(rng: &mut impl Rng, window: &[u64]) -> u64 {
+    let mut window = window.to_vec();
+    window.sort_unstable();
+    let mut pair = window[..5].choose_multiple(rng, 2);
+    pair.next().unwrap() + pair.next().unwrap()
+}
+
+/// Generate a XMAS-encoded stream with exactly one invalid item, which is also the sum of
+/// a contiguous run of earlier items.
+///
+/// Items are capped in magnitude, so very large scales produce truncated streams.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let len = BASE_LEN * scale;
+    let invalid_at = rng.gen_range(BASE_LEN / 5..BASE_LEN / 2);
+
+    let mut items: Vec<u64> = (1..=DEFAULT_PREAMBLE_LEN as u64 * 2).collect();
+    items.shuffle(rng);
+    items.truncate(DEFAULT_PREAMBLE_LEN);
+
+    while items.len() < len {
+        let idx = items.len();
+        let item = if idx == invalid_at {
+            // small runs of early items sum to less than any pair in the current window
+            loop {
+                let run_len = rng.gen_range(2..=10);
+                let low = rng.gen_range(0..idx / 2);
+                items.push(items[low..low + run_len].iter().sum());
+                let valid = xmas_valid(&items, DEFAULT_PREAMBLE_LEN);
+                let item = items.pop().unwrap();
+                if !valid {
+                    break item;
+                }
+            }
+        } else {
+            let window_start = idx - DEFAULT_PREAMBLE_LEN;
+            let window: Vec<_> = (window_start..idx)
+                .filter(|&window_idx| window_idx != invalid_at)
+                .map(|window_idx| items[window_idx])
+                .collect();
+            next_valid(rng, &window)
+        };
+        if item > MAX_ITEM {
+            break;
+        }
+        items.push(item);
+    }
+
+    for item in items {
+        writeln!(out, "{}", item)?;
+    }
+    Ok(())
+}
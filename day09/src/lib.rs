@@ -1,8 +1,11 @@
-use aoc2020::parse;
+use aoc2020::{input::parse_str, parse};
 
 use std::path::Path;
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 pub const DEFAULT_PREAMBLE_LEN: usize = 25;
 
 /// True if the tail is the sum of any two of the previous `preamble_len` numbers
@@ -34,7 +37,7 @@ pub fn find_first_invalid(items: &[u64], preamble_len: usize) -> Option<u64> {
 }
 
 // never returns an empty slice
-fn find_slice_with_sum<'a>(items: &'a [u64], target_sum: u64) -> Option<&'a [u64]> {
+fn find_slice_with_sum(items: &[u64], target_sum: u64) -> Option<&[u64]> {
     for low in 0..items.len() {
         let mut running_sum = items[low];
         for high in low + 1..items.len() {
@@ -78,6 +81,12 @@ pub fn part2(input: &Path, mut carryover: Option<Carryover>) -> Result<(), Error
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    parse_str::<u64>(input).for_each(drop);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -132,11 +141,11 @@ mod test {
 
     #[test]
     fn test_find_first_invalid() {
-        assert_eq!(find_first_invalid(&SAMPLE_LIST, 5), Some(127));
+        assert_eq!(find_first_invalid(SAMPLE_LIST, 5), Some(127));
     }
 
     #[test]
     fn test_find_weakness() {
-        assert_eq!(find_weakness(&SAMPLE_LIST, 127), Some(62));
+        assert_eq!(find_weakness(SAMPLE_LIST, 127), Some(62));
     }
 }
@@ -0,0 +1,50 @@
+use rand::{seq::SliceRandom, Rng};
+use std::io::Write;
+
+const BASE_LEN: usize = 100;
+/// Arrangement counts multiply across runs; keep the product comfortably within `usize`.
+const MAX_ARRANGEMENTS: u64 = 1_000_000_000_000_000;
+
+/// The number of arrangements of a run of `n` consecutive 1-jolt gaps.
+fn arrangements(n: usize) -> u64 {
+    match n {
+        0 | 1 => 1,
+        2 => 2,
+        3 => 4,
+        4 => 7,
+        _ => unreachable!("runs are never longer than 4"),
+    }
+}
+
+/// Generate a set of adapters forming a single chain, joined by gaps of 1 and 3 jolts.
+///
+/// Once the number of arrangements would grow too large, runs of 1-jolt gaps are kept short.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let len = BASE_LEN * scale;
+    let mut n_arrangements = 1;
+    let mut joltage = 0_u32;
+    let mut adapters = Vec::with_capacity(len);
+
+    while adapters.len() < len {
+        if !adapters.is_empty() {
+            joltage += 3;
+            adapters.push(joltage);
+        }
+        let mut run_len = rng.gen_range(0..=4);
+        while n_arrangements * arrangements(run_len) > MAX_ARRANGEMENTS {
+            run_len -= 1;
+        }
+        n_arrangements *= arrangements(run_len);
+        for _ in 0..run_len {
+            joltage += 1;
+            adapters.push(joltage);
+        }
+    }
+
+    adapters.truncate(len);
+    adapters.shuffle(rng);
+    for adapter in adapters {
+        writeln!(out, "{}", adapter)?;
+    }
+    Ok(())
+}
@@ -1,9 +1,15 @@
-use aoc2020::parse;
+use aoc2020::{input::parse_str, parse};
 
 use counter::Counter;
 use std::path::Path;
 use thiserror::Error;
 
+mod generate;
+#[cfg(test)]
+mod reference;
+
+pub use generate::generate;
+
 const CHARGING_OUTLET: u32 = 0;
 
 fn make_adapter_chain(adapters: &[u32]) -> Option<Vec<u32>> {
@@ -82,7 +88,7 @@ fn count_legal_adapter_arrangements_inner(
     mut unused_adapters: &[u32],
     memoize: &mut Vec<usize>,
 ) -> usize {
-    if unused_adapters.len() == 0 {
+    if unused_adapters.is_empty() {
         return 1;
     }
 
@@ -113,6 +119,12 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    parse_str::<u32>(input).for_each(drop);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -0,0 +1,81 @@
+//! Naive reference implementations, against which the optimized solutions are checked.
+
+use crate::{adapter_chain_stats, count_legal_adapter_arrangements, CHARGING_OUTLET};
+use proptest::prelude::*;
+
+/// Sort the adapters and count the differences between consecutive joltages, including the
+/// charging outlet and the device. Returns `(1-diffs, 3-diffs)`, or `None` if any gap is too wide.
+fn chain_diffs(adapters: &[u32]) -> Option<(usize, usize)> {
+    let mut chain = adapters.to_vec();
+    chain.sort_unstable();
+    chain.insert(0, CHARGING_OUTLET);
+    chain.push(chain[chain.len() - 1] + 3);
+
+    let mut ones = 0;
+    let mut threes = 0;
+    for window in chain.windows(2) {
+        match window[1] - window[0] {
+            1 => ones += 1,
+            2 => {}
+            3 => threes += 1,
+            _ => return None,
+        }
+    }
+    Some((ones, threes))
+}
+
+/// Try every subset of the adapters, counting those which form a legal chain.
+///
+/// The device is always rated 3 jolts above the highest adapter, so that adapter is mandatory.
+fn count_arrangements(adapters: &[u32]) -> usize {
+    let mut adapters = adapters.to_vec();
+    adapters.sort_unstable();
+    let device = adapters[adapters.len() - 1] + 3;
+
+    (0..1_u32 << adapters.len())
+        .filter(|subset| {
+            let mut previous = CHARGING_OUTLET;
+            for (idx, &adapter) in adapters.iter().enumerate() {
+                if subset & (1 << idx) != 0 {
+                    if adapter > previous + 3 {
+                        return false;
+                    }
+                    previous = adapter;
+                }
+            }
+            device == previous + 3
+        })
+        .count()
+}
+
+/// Distinct adapters, in no particular order, which always form a complete chain.
+fn chainable_adapters() -> impl Strategy<Value = Vec<u32>> {
+    prop::collection::vec(1..=3_u32, 1..14)
+        .prop_map(|gaps| {
+            gaps.into_iter()
+                .scan(CHARGING_OUTLET, |joltage, gap| {
+                    *joltage += gap;
+                    Some(*joltage)
+                })
+                .collect::<Vec<_>>()
+        })
+        .prop_shuffle()
+}
+
+proptest! {
+    #[test]
+    fn stats_match_reference(adapters in prop::collection::btree_set(1..40_u32, 1..14)) {
+        let adapters: Vec<_> = adapters.into_iter().collect();
+        let stats = adapter_chain_stats(&adapters)
+            .map(|stats| (stats[&1], stats[&3]));
+        prop_assert_eq!(stats, chain_diffs(&adapters));
+    }
+
+    #[test]
+    fn arrangements_match_reference(adapters in chainable_adapters()) {
+        prop_assert_eq!(
+            count_legal_adapter_arrangements(&adapters),
+            count_arrangements(&adapters)
+        );
+    }
+}
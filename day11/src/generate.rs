@@ -0,0 +1,37 @@
+use crate::{seating_automaton, Rules, SeatingSystem};
+use aoc2020::geometry::Termination;
+use rand::Rng;
+use std::{convert::TryFrom, io::Write};
+
+const WIDTH: usize = 92;
+const BASE_HEIGHT: usize = 98;
+/// Denser seating tends to oscillate forever rather than stabilizing.
+const FLOOR_DENSITY: f64 = 0.3;
+/// Some layouts oscillate forever instead of stabilizing; give up on them after this many steps.
+const MAX_STEPS: usize = 1000;
+
+fn stabilizes(seats: &SeatingSystem, rules: Rules) -> bool {
+    matches!(
+        seating_automaton(seats.clone(), rules).run(MAX_STEPS),
+        Termination::FixedPoint { .. }
+    )
+}
+
+/// Generate a seating area which stabilizes under both sets of rules.
+///
+/// The area grows taller with `scale`.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let mut layout = String::with_capacity((WIDTH + 1) * BASE_HEIGHT * scale);
+    loop {
+        layout.clear();
+        for _ in 0..BASE_HEIGHT * scale {
+            layout.extend((0..WIDTH).map(|_| if rng.gen_bool(FLOOR_DENSITY) { '.' } else { 'L' }));
+            layout.push('\n');
+        }
+        let seats = SeatingSystem::try_from(layout.as_str()).expect("generated layout is valid");
+        if stabilizes(&seats, Rules::Adjacent) && stabilizes(&seats, Rules::Projected) {
+            break;
+        }
+    }
+    out.write_all(layout.as_bytes())
+}
@@ -1,8 +1,16 @@
-use aoc2020::geometry::{tile::DisplayWidth, Map, Point};
+use aoc2020::geometry::{
+    automaton::Neighbors,
+    render::{self, Animation, Color, Image, Rgb},
+    tile::DisplayWidth,
+    Automaton, Map, Point, Termination,
+};
 
 use std::{convert::TryFrom, path::Path};
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 #[derive(
     Debug, Clone, Copy, PartialEq, Eq, Hash, parse_display::Display, parse_display::FromStr,
 )]
@@ -19,73 +27,90 @@ impl DisplayWidth for Tile {
     const DISPLAY_WIDTH: usize = 1;
 }
 
-type SeatingSystem = Map<Tile>;
-
-fn count_occupied_adjacencies(seats: &SeatingSystem, position: Point) -> usize {
-    seats
-        .adjacencies(position)
-        .filter(|&seat_position| seats[seat_position] == Tile::OccupiedSeat)
-        .count()
+impl Color for Tile {
+    fn color(&self) -> Rgb {
+        match self {
+            Tile::Floor => Rgb::new(40, 40, 40),
+            Tile::EmptySeat => Rgb::new(80, 160, 80),
+            Tile::OccupiedSeat => Rgb::new(200, 60, 60),
+        }
+    }
 }
 
-fn count_occupied_projected(seats: &SeatingSystem, position: Point) -> usize {
-    seats
-        .adjacencies(position)
-        .filter(|&adj| {
-            let deltas = adj - position;
-            assert!(!(deltas.x == 0 && deltas.y == 0));
-            for visible_position in seats.project(position, deltas.x, deltas.y).skip(1) {
-                match seats[visible_position] {
-                    Tile::EmptySeat => return false,
-                    Tile::OccupiedSeat => return true,
-                    Tile::Floor => {}
-                }
-            }
-            false
-        })
-        .count()
+type SeatingSystem = Map<Tile>;
+
+/// The two sets of rules by which passengers choose seats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rules {
+    /// Passengers consider the eight adjacent tiles.
+    Adjacent,
+    /// Passengers consider the first seat visible in each of the eight directions.
+    Projected,
 }
 
-fn state_transition(
-    seats: &SeatingSystem,
-    count_occupied: impl Fn(&SeatingSystem, Point) -> usize,
-    max_adjacent: usize,
-) -> SeatingSystem {
-    let mut output = seats.clone();
-    output.for_each_point_mut(|seat, position| {
-        let n_occupied_adjacencies = count_occupied(seats, position);
-        match (&seat, n_occupied_adjacencies) {
-            (Tile::EmptySeat, 0) => *seat = Tile::OccupiedSeat,
-            (Tile::OccupiedSeat, n) if n >= max_adjacent => *seat = Tile::EmptySeat,
-            _ => {}
+impl Rules {
+    /// An occupied seat is vacated when this many of the seats it considers are occupied.
+    fn max_occupied(self) -> usize {
+        match self {
+            Rules::Adjacent => 4,
+            Rules::Projected => 5,
         }
-    });
-    output
-}
+    }
 
-fn state_transition_adjacent(seats: &SeatingSystem) -> SeatingSystem {
-    state_transition(seats, count_occupied_adjacencies, 4)
+    /// The seats considered by a passenger at `position`.
+    ///
+    /// Floor never changes, so it considers nothing.
+    fn considered(self, seats: &SeatingSystem, position: Point) -> Vec<Point> {
+        if seats[position] == Tile::Floor {
+            return Vec::new();
+        }
+        match self {
+            Rules::Adjacent => seats
+                .adjacencies(position)
+                .filter(|&adj| seats[adj] != Tile::Floor)
+                .collect(),
+            Rules::Projected => seats
+                .adjacencies(position)
+                .filter_map(|adj| {
+                    let deltas = adj - position;
+                    seats
+                        .project(position, deltas.x, deltas.y)
+                        .skip(1)
+                        .find(|&visible| seats[visible] != Tile::Floor)
+                })
+                .collect(),
+        }
+    }
 }
 
-fn state_transition_project(seats: &SeatingSystem) -> SeatingSystem {
-    state_transition(seats, count_occupied_projected, 5)
+fn seating_automaton(
+    seats: SeatingSystem,
+    rules: Rules,
+) -> Automaton<Tile, impl Fn(&Tile, Neighbors<'_, Tile>) -> Tile> {
+    let max_occupied = rules.max_occupied();
+    Automaton::new(
+        seats,
+        move |seats: &SeatingSystem, position| rules.considered(seats, position),
+        move |&seat: &Tile, neighbors: Neighbors<'_, Tile>| {
+            let n_occupied = neighbors
+                .filter(|&&neighbor| neighbor == Tile::OccupiedSeat)
+                .count();
+            match (seat, n_occupied) {
+                (Tile::EmptySeat, 0) => Tile::OccupiedSeat,
+                (Tile::OccupiedSeat, n) if n >= max_occupied => Tile::EmptySeat,
+                _ => seat,
+            }
+        },
+    )
 }
 
-fn transition_until_stable(
-    seats: &SeatingSystem,
-    state_transition: impl Fn(&SeatingSystem) -> SeatingSystem,
-) -> SeatingSystem {
-    let mut current = seats.clone();
-
-    loop {
-        let succ = state_transition(&current);
-        if succ == current {
-            break;
-        }
-        current = succ;
+fn transition_until_stable(seats: SeatingSystem, rules: Rules) -> Result<SeatingSystem, Error> {
+    let mut automaton = seating_automaton(seats, rules);
+    match automaton.run(usize::MAX) {
+        Termination::FixedPoint { .. } => Ok(automaton.into_map()),
+        Termination::Cycle { start, period } => Err(Error::Oscillates { start, period }),
+        Termination::GenerationLimit => unreachable!("a finite map must eventually repeat"),
     }
-
-    current
 }
 
 fn count_occupied(seats: &SeatingSystem) -> usize {
@@ -97,7 +122,7 @@ fn count_occupied(seats: &SeatingSystem) -> usize {
 
 pub fn part1(input: &Path) -> Result<(), Error> {
     let seats = SeatingSystem::try_from(input)?;
-    let seats = transition_until_stable(&seats, state_transition_adjacent);
+    let seats = transition_until_stable(seats, Rules::Adjacent)?;
     let occupied_when_stable = count_occupied(&seats);
     println!(
         "seats occupied in steady state (adjacent):  {}",
@@ -108,7 +133,7 @@ pub fn part1(input: &Path) -> Result<(), Error> {
 
 pub fn part2(input: &Path) -> Result<(), Error> {
     let seats = SeatingSystem::try_from(input)?;
-    let seats = transition_until_stable(&seats, state_transition_project);
+    let seats = transition_until_stable(seats, Rules::Projected)?;
     let occupied_when_stable = count_occupied(&seats);
     println!(
         "seats occupied in steady state (projected): {}",
@@ -117,10 +142,47 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Write an animated GIF of the seating system evolving until it stabilizes.
+pub fn render(input: &Path, output: &Path, projected: bool) -> Result<(), Error> {
+    const SCALE: usize = 4;
+
+    let rules = if projected {
+        Rules::Projected
+    } else {
+        Rules::Adjacent
+    };
+    let mut automaton = seating_automaton(SeatingSystem::try_from(input)?, rules);
+
+    let first = Image::from_map(automaton.map(), SCALE);
+    let mut animation = Animation::create(output, first.width(), first.height(), 20)?;
+    animation.push(&first)?;
+
+    // the callback can't fail, so hold on to the first error
+    let mut result = Ok(());
+    automaton.run_with(usize::MAX, |automaton| {
+        if result.is_ok() {
+            result = animation.push(&Image::from_map(automaton.map(), SCALE));
+        }
+    });
+    result?;
+    animation.finish()?;
+    Ok(())
+}
+
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    let _ = SeatingSystem::try_from(input);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Render(#[from] render::Error),
+    #[error("seating oscillates with period {period} from generation {start}")]
+    Oscillates { start: usize, period: usize },
 }
 
 #[cfg(test)]
@@ -146,12 +208,17 @@ L.LLLLL.LL
 
     #[test]
     fn transitions() {
-        let mut current = example();
-        let mut n = 0;
-        while n < 7 {
-            n += 1;
-            println!("{}", current);
-            current = state_transition_project(&current);
+        let mut automaton = seating_automaton(example(), Rules::Projected);
+        println!("{}", automaton.map());
+        let termination = automaton.run_with(10, |automaton| println!("{}", automaton.map()));
+        assert_eq!(termination, Termination::FixedPoint { generation: 6 });
+    }
+
+    #[test]
+    fn stable_occupancy() {
+        for &(rules, expect) in &[(Rules::Adjacent, 37), (Rules::Projected, 26)] {
+            let seats = transition_until_stable(example(), rules).unwrap();
+            assert_eq!(count_occupied(&seats), expect);
         }
     }
 }
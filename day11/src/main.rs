@@ -1,9 +1,9 @@
 use aoc2020::{config::Config, website::get_input};
-use day11::{part1, part2};
+use day11::{part1, part2, render};
 
 use color_eyre::eyre::Result;
-use structopt::StructOpt;
 use std::path::PathBuf;
+use structopt::StructOpt;
 
 const DAY: u8 = 11;
 
@@ -20,6 +20,10 @@ struct RunArgs {
     /// run part 2
     #[structopt(long)]
     part2: bool,
+
+    /// write an animated GIF of the seats evolving, under the rules of part 2 if it runs
+    #[structopt(long, parse(from_os_str))]
+    render: Option<PathBuf>,
 }
 
 impl RunArgs {
@@ -48,5 +52,8 @@ fn main() -> Result<()> {
     if args.part2 {
         part2(&input_path)?;
     }
+    if let Some(ref output) = args.render {
+        render(&input_path, output, args.part2)?;
+    }
     Ok(())
 }
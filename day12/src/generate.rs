@@ -0,0 +1,28 @@
+use rand::{seq::SliceRandom, Rng};
+use std::io::Write;
+
+const BASE_LEN: usize = 780;
+
+/// Generate navigation instructions.
+///
+/// Turns are always multiples of 90 degrees.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    for _ in 0..BASE_LEN * scale {
+        match rng.gen_range(0..10) {
+            0..=4 => writeln!(
+                out,
+                "{}{}",
+                ['N', 'S', 'E', 'W'].choose(rng).unwrap(),
+                rng.gen_range(1..=5)
+            )?,
+            5..=6 => writeln!(
+                out,
+                "{}{}",
+                ['L', 'R'].choose(rng).unwrap(),
+                [90, 180, 270].choose(rng).unwrap()
+            )?,
+            _ => writeln!(out, "F{}", rng.gen_range(1..=100))?,
+        }
+    }
+    Ok(())
+}
@@ -1,11 +1,15 @@
 use aoc2020::{
-    geometry::{line_segment::LineSegment as Vector, Direction, Point},
+    geometry::{compass, line_segment::LineSegment as Vector, Compass8, Direction, Point},
+    input::parse_str,
     parse,
 };
 
 use std::path::Path;
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::FromStr, parse_display::Display)]
 enum Action {
     #[display("N")]
@@ -34,7 +38,7 @@ struct Instruction {
 
 #[derive(Clone, PartialEq, Eq)]
 struct Ship {
-    heading: Direction,
+    heading: Compass8,
     position: Point,
     waypoint: Point,
 }
@@ -42,43 +46,37 @@ struct Ship {
 impl Ship {
     fn new() -> Ship {
         Ship {
-            heading: Direction::Right,
+            heading: Compass8::E,
             position: Point::default(),
             waypoint: Point::new(10, 1),
         }
     }
 
-    fn apply(&mut self, instruction: Instruction) {
+    fn apply(&mut self, instruction: Instruction) -> Result<(), Error> {
         let movement_direction = match instruction.action {
-            Action::North => Some(Direction::Up),
-            Action::South => Some(Direction::Down),
-            Action::East => Some(Direction::Right),
-            Action::West => Some(Direction::Left),
+            Action::North => Some(Compass8::N),
+            Action::South => Some(Compass8::S),
+            Action::East => Some(Compass8::E),
+            Action::West => Some(Compass8::W),
             Action::Forward => Some(self.heading),
             Action::Left => {
-                for _ in 0..(instruction.qty / 90) {
-                    self.heading = self.heading.turn_left();
-                }
+                self.heading = self.heading.turn(-instruction.qty)?;
                 None
             }
             Action::Right => {
-                for _ in 0..(instruction.qty / 90) {
-                    self.heading = self.heading.turn_right();
-                }
+                self.heading = self.heading.turn(instruction.qty)?;
                 None
             }
         };
 
         if let Some(direction) = movement_direction {
-            let vector = Vector {
-                direction,
-                distance: instruction.qty,
-            };
-            self.position += vector;
+            let (dx, dy) = direction.deltas();
+            self.position += Point::new(dx, dy) * instruction.qty;
         }
+        Ok(())
     }
 
-    fn apply_waypoint(&mut self, instruction: Instruction) {
+    fn apply_waypoint(&mut self, instruction: Instruction) -> Result<(), Error> {
         match instruction.action {
             Action::North | Action::South | Action::East | Action::West => {
                 let direction = match instruction.action {
@@ -97,24 +95,27 @@ impl Ship {
                 self.waypoint += vector;
             }
             Action::Forward => self.position += self.waypoint * instruction.qty,
-            Action::Left => {
-                for _ in 0..(instruction.qty / 90) {
-                    self.waypoint = self.waypoint.rotate_left();
-                }
-            }
-            Action::Right => {
-                for _ in 0..(instruction.qty / 90) {
-                    self.waypoint = self.waypoint.rotate_right();
+            Action::Left | Action::Right => {
+                // the waypoint is an arbitrary offset, so only quarter turns keep it on the grid
+                if instruction.qty % 90 != 0 {
+                    return Err(compass::Error::Angle(instruction.qty).into());
                 }
+                let quarter_turns = instruction.qty / 90;
+                self.waypoint = self.waypoint.rotate(if instruction.action == Action::Left {
+                    -quarter_turns
+                } else {
+                    quarter_turns
+                });
             }
         }
+        Ok(())
     }
 }
 
 pub fn part1(input: &Path) -> Result<(), Error> {
     let mut ship = Ship::new();
     for instruction in parse::<Instruction>(input)? {
-        ship.apply(instruction);
+        ship.apply(instruction)?;
     }
     let manhattan = ship.position.manhattan();
     println!("ship manhattan distance from origin: {}", manhattan);
@@ -124,7 +125,7 @@ pub fn part1(input: &Path) -> Result<(), Error> {
 pub fn part2(input: &Path) -> Result<(), Error> {
     let mut ship = Ship::new();
     for instruction in parse::<Instruction>(input)? {
-        ship.apply_waypoint(instruction);
+        ship.apply_waypoint(instruction)?;
     }
     let manhattan = ship.position.manhattan();
     println!(
@@ -134,8 +135,52 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    parse_str::<Instruction>(input).for_each(drop);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Turn(#[from] compass::Error),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &[&str] = &["F10", "N3", "F7", "R90", "F11"];
+
+    fn instructions(lines: &[&str]) -> Vec<Instruction> {
+        lines.iter().map(|line| line.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn example_waypoint() {
+        let mut ship = Ship::new();
+        for instruction in instructions(EXAMPLE) {
+            ship.apply_waypoint(instruction).unwrap();
+        }
+        assert_eq!(ship.position, Point::new(214, -72));
+        assert_eq!(ship.waypoint, Point::new(4, -10));
+    }
+
+    #[test]
+    fn waypoint_turns_in_quarters() {
+        let mut ship = Ship::new();
+        ship.apply_waypoint("L450".parse().unwrap()).unwrap();
+        assert_eq!(ship.waypoint, Point::new(-1, 10));
+
+        for bad in ["R45", "L100"] {
+            assert!(matches!(
+                ship.apply_waypoint(bad.parse().unwrap()),
+                Err(Error::Turn(compass::Error::Angle(_)))
+            ));
+        }
+        assert_eq!(ship.waypoint, Point::new(-1, 10));
+    }
 }
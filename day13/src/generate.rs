@@ -0,0 +1,62 @@
+use aoc2020::numbers::number_theory::Sieve;
+use rand::{seq::SliceRandom, Rng};
+use std::io::Write;
+
+const MAX_BUS: i64 = 1000;
+/// The remainder theorem computes terms bounded by `modulus * product(moduli)`; their sum
+/// must fit in an `i64`.
+const MAX_TERMS: i128 = 4_000_000_000_000_000_000;
+
+/// Generate one set of bus notes.
+///
+/// Bus IDs are distinct primes, so the remainder theorem applies, and each bus's position
+/// in the schedule is less than its ID.
+fn notes(rng: &mut impl Rng, primes: &[i64], out: &mut dyn Write) -> std::io::Result<()> {
+    let mut routes = Vec::new();
+    let mut product = 1_i128;
+    let mut sum = 0_i128;
+    loop {
+        let position = routes.len() as i64;
+        let candidates: Vec<_> = primes
+            .iter()
+            .copied()
+            .filter(|&prime| prime > position && !routes.contains(&Some(prime)))
+            .filter(|&prime| (sum + prime as i128) * product * prime as i128 <= MAX_TERMS)
+            .collect();
+        let bus = match candidates.choose(rng) {
+            Some(&bus) => bus,
+            None => break,
+        };
+        routes.push(Some(bus));
+        product *= bus as i128;
+        sum += bus as i128;
+        for _ in 0..rng.gen_range(0..12) {
+            routes.push(None);
+        }
+    }
+    while routes.last() == Some(&None) {
+        routes.pop();
+    }
+
+    writeln!(out, "{}", rng.gen_range(100_000..10_000_000))?;
+    let routes: Vec<_> = routes
+        .into_iter()
+        .map(|bus| bus.map_or_else(|| "x".to_string(), |bus| bus.to_string()))
+        .collect();
+    writeln!(out, "{}", routes.join(","))
+}
+
+/// Generate `scale` independent sets of bus notes, separated by blank lines.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let primes: Vec<_> = Sieve::new(MAX_BUS as usize - 1)
+        .primes()
+        .map(|prime| prime as i64)
+        .collect();
+    for idx in 0..scale {
+        if idx > 0 {
+            writeln!(out)?;
+        }
+        notes(rng, &primes, out)?;
+    }
+    Ok(())
+}
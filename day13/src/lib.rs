@@ -1,11 +1,14 @@
 use aoc2020::{
-    input::parse_newline_sep,
-    numbers::chinese_remainder::{chinese_remainder, Constraint},
+    input::{parse_newline_sep, parse_newline_sep_str},
+    numbers::chinese_remainder::{self, solve, Constraint},
 };
 
-use std::{path::Path, str::FromStr};
+use std::{convert::TryFrom, path::Path, str::FromStr};
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 type Bus = i64;
 type Timestamp = i64;
 
@@ -34,7 +37,13 @@ impl FromStr for BusNotes {
                 0 => notes.earliest_departure_timestamp = line.parse()?,
                 1 => {
                     for bus in line.split(',') {
-                        notes.routes.push(bus.parse()?);
+                        let bus = bus.parse()?;
+                        if let BusId::Number(id) = bus {
+                            if id <= 0 {
+                                return Err(Error::InvalidBus(id));
+                            }
+                        }
+                        notes.routes.push(bus);
                     }
                 }
                 _ => {
@@ -79,30 +88,27 @@ impl BusNotes {
             .min()
     }
 
-    fn is_valid_part2(&self, t: Timestamp) -> bool {
-        self.routes
+    /// Find the earliest timestamp at which each bus departs as many minutes later as its
+    /// position in the list.
+    ///
+    /// Bus ids need not be coprime. The solution is computed in `i128`, as the product of the
+    /// ids can exceed a `Timestamp`.
+    fn search_for_valid_timestamp(&self) -> Result<Timestamp, Error> {
+        let routes: Vec<_> = self.active_routes().collect();
+        let constraints: Vec<_> = routes
             .iter()
-            .copied()
-            .enumerate()
-            .filter_map(|(idx, bus)| match bus {
-                BusId::Number(bus) => Some((idx, bus)),
-                BusId::X => None,
-            })
-            .all(|(idx, bus)| minutes_remaining_after(bus, t) as usize == idx)
-    }
-
-    fn search_for_valid_timestamp(&self) -> Option<Timestamp> {
-        let constraints: Vec<_> = self
-            .active_routes()
-            .map(|(position, bus)| Constraint::new_invert_remainder(bus, position as Bus))
+            .map(|&(position, bus)| Constraint::new_invert_remainder(bus as i128, position as i128))
             .collect();
-        let t = chinese_remainder(&constraints)?;
-        let valid = self.is_valid_part2(t);
-        if !valid {
-            dbg!(t);
-        }
-        assert!(valid, "validity calculation failed");
-        Some(t)
+        let solution = solve(&constraints).map_err(|err| match err {
+            chinese_remainder::Error::Conflict { first, second } => Error::Inconsistent {
+                first: routes[first],
+                second: routes[second],
+            },
+            // bus ids are validated as positive while parsing
+            chinese_remainder::Error::Modulus(_) => Error::NoSolution,
+            chinese_remainder::Error::Overflow(_) => Error::TimestampOverflow,
+        })?;
+        Timestamp::try_from(solution.remainder).map_err(|_| Error::TimestampOverflow)
     }
 }
 
@@ -120,9 +126,7 @@ pub fn part1(input: &Path) -> Result<(), Error> {
 
 pub fn part2(input: &Path) -> Result<(), Error> {
     for (notes_id, notes) in parse_newline_sep::<BusNotes>(input)?.enumerate() {
-        let valid_timestamp = notes
-            .search_for_valid_timestamp()
-            .ok_or(Error::NoSolution)?;
+        let valid_timestamp = notes.search_for_valid_timestamp()?;
         println!(
             "notes {}: first valid timestamp = {}",
             notes_id, valid_timestamp
@@ -131,6 +135,15 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse and check `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    for notes in parse_newline_sep_str::<BusNotes>(input).flatten() {
+        notes.first_departure_after();
+        let _ = notes.search_for_valid_timestamp();
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -139,8 +152,45 @@ pub enum Error {
     Int(#[from] std::num::ParseIntError),
     #[error(transparent)]
     Id(#[from] parse_display::ParseError),
+    #[error("bus ids must be positive; got {0}")]
+    InvalidBus(Bus),
     #[error("too many lines of notes")]
     TooManyLines,
     #[error("no solution found")]
     NoSolution,
+    #[error(
+        "bus {} at offset {} can never depart in step with bus {} at offset {}",
+        .second.1, .second.0, .first.1, .first.0
+    )]
+    Inconsistent {
+        first: (usize, Bus),
+        second: (usize, Bus),
+    },
+    #[error("first valid timestamp does not fit in a timestamp")]
+    TimestampOverflow,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let notes: BusNotes = "939\n7,13,x,x,59,x,31,19".parse().unwrap();
+        assert_eq!(notes.search_for_valid_timestamp().unwrap(), 1068781);
+    }
+
+    #[test]
+    fn test_overflowing_solution() {
+        let primes = [
+            907, 911, 919, 929, 937, 941, 947, 953, 967, 971, 977, 983, 991, 997, 1009, 1013, 1019,
+            1021, 1031, 1033,
+        ];
+        let routes: Vec<_> = primes.iter().map(ToString::to_string).collect();
+        let notes: BusNotes = format!("939\n{}", routes.join(",")).parse().unwrap();
+        assert!(matches!(
+            notes.search_for_valid_timestamp(),
+            Err(Error::TimestampOverflow)
+        ));
+    }
 }
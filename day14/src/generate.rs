@@ -0,0 +1,34 @@
+use rand::{seq::index::sample, Rng};
+use std::io::Write;
+
+const BASE_LEN: usize = 100;
+const MASK_LEN: usize = 36;
+/// Each floating bit doubles the number of writes in the second part.
+const MAX_FLOATING: usize = 9;
+
+fn mask(rng: &mut impl Rng) -> String {
+    let mut mask: Vec<char> = (0..MASK_LEN)
+        .map(|_| if rng.gen() { '1' } else { '0' })
+        .collect();
+    let n_floating = rng.gen_range(1..=MAX_FLOATING);
+    for idx in sample(rng, MASK_LEN, n_floating) {
+        mask[idx] = 'X';
+    }
+    mask.into_iter().collect()
+}
+
+/// Generate a docking program: a series of masks, each followed by a few memory writes.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    for _ in 0..BASE_LEN * scale {
+        writeln!(out, "mask = {}", mask(rng))?;
+        for _ in 0..rng.gen_range(1..=6) {
+            writeln!(
+                out,
+                "mem[{}] = {}",
+                rng.gen_range(0..1 << 16),
+                rng.gen_range(0..1_i64 << 36)
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -1,8 +1,11 @@
-use aoc2020::parse;
+use aoc2020::{input::parse_str, parse};
 
 use std::{collections::HashMap, fmt, iter::FromIterator, path::Path, str::FromStr};
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 const U36_MASK: i64 = 0x0f_ffff_ffff;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -192,6 +195,12 @@ pub fn part2(input: &Path, show_memory: bool) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    parse_str::<Instruction>(input).for_each(drop);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -203,6 +212,7 @@ pub enum Error {
 }
 
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod test {
     use super::*;
 
@@ -0,0 +1,16 @@
+use rand::{seq::index::sample, Rng};
+use std::io::Write;
+
+/// Generate a list of distinct starting numbers.
+///
+/// The number of turns is fixed by the puzzle, so `scale` instead widens the range of the
+/// starting numbers and lengthens the list.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let range = 20 * scale;
+    let len = rng.gen_range(3..=(5 + scale).min(range));
+    let numbers: Vec<_> = sample(rng, range, len)
+        .into_iter()
+        .map(|number| number.to_string())
+        .collect();
+    writeln!(out, "{}", numbers.join(","))
+}
@@ -1,8 +1,14 @@
-use aoc2020::{parse, CommaSep};
+use aoc2020::{input::parse_str, parse, CommaSep};
 
 use std::path::Path;
 use thiserror::Error;
 
+mod generate;
+#[cfg(test)]
+mod reference;
+
+pub use generate::generate;
+
 fn memory_game(initializers: &[u32]) -> impl '_ + Iterator<Item = u32> {
     let mut turn = 0;
     let mut previous = 0;
@@ -61,6 +67,12 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    parse_str::<CommaSep<u32>>(input).for_each(drop);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -85,7 +97,7 @@ mod test {
     fn test_nth_semantics() {
         // nth has these semantics: for Turn `N`, request `nth(N-1)`.
         let initializers = [0, 3, 6];
-        assert_eq!(memory_game(&initializers).nth(0), Some(0));
+        assert_eq!(memory_game(&initializers).next(), Some(0));
         assert_eq!(memory_game(&initializers).nth(8), Some(4));
         assert_eq!(memory_game(&initializers).nth(2020 - 1), Some(436));
     }
@@ -0,0 +1,29 @@
+//! Naive reference implementations, against which the optimized solutions are checked.
+
+use crate::memory_game;
+use proptest::prelude::*;
+
+/// Play the memory game by direct simulation, searching the whole history for each turn.
+fn memory_game_naive(initializers: &[u32], turns: usize) -> Vec<u32> {
+    let mut spoken: Vec<u32> = initializers.iter().copied().take(turns).collect();
+    while spoken.len() < turns {
+        let (previous, history) = spoken.split_last().expect("initializers are not empty");
+        let next = history
+            .iter()
+            .rposition(|number| number == previous)
+            .map_or(0, |idx| (history.len() - idx) as u32);
+        spoken.push(next);
+    }
+    spoken
+}
+
+proptest! {
+    #[test]
+    fn memory_game_matches_reference(
+        initializers in prop::collection::vec(0..30_u32, 1..8),
+        turns in 1..400_usize,
+    ) {
+        let actual: Vec<_> = memory_game(&initializers).take(turns).collect();
+        prop_assert_eq!(actual, memory_game_naive(&initializers, turns));
+    }
+}
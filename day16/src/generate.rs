@@ -0,0 +1,194 @@
+use rand::{seq::SliceRandom, Rng};
+use std::io::Write;
+
+const BASE_NEARBY: usize = 240;
+
+const FIELD_NAMES: &[&str] = &[
+    "departure location",
+    "departure station",
+    "departure platform",
+    "departure track",
+    "departure date",
+    "departure time",
+    "arrival location",
+    "arrival station",
+    "arrival platform",
+    "arrival track",
+    "class",
+    "duration",
+    "price",
+    "route",
+    "row",
+    "seat",
+    "train",
+    "type",
+    "wagon",
+    "zone",
+];
+
+/// Value bands, shared between all fields.
+///
+/// The field of rank `r` accepts exactly the bands `0..=r`, in both its low and its high range.
+/// A column whose values include one from band `r` can therefore only belong to fields of rank
+/// `r` or higher. Every column includes a value from its own field's band, so eliminating fields
+/// in rank order always leaves exactly one candidate column.
+struct Bands {
+    /// `low[r]` is the inclusive upper bound of low band `r`; low band 0 starts at `low_start`.
+    low_start: u32,
+    low: Vec<u32>,
+    /// `high[r]` is the inclusive lower bound of high band `r`; high band 0 ends at `high_end`.
+    high: Vec<u32>,
+    high_end: u32,
+}
+
+impl Bands {
+    fn new(rng: &mut impl Rng, n: usize) -> Bands {
+        let low_start = rng.gen_range(25..50);
+        let mut low = Vec::with_capacity(n);
+        let mut bound = low_start;
+        for _ in 0..n {
+            bound += rng.gen_range(5..=15);
+            low.push(bound);
+        }
+        // values in this gap are invalid for every field
+        bound += rng.gen_range(10..=30);
+        let mut high = vec![0; n];
+        for rank in (0..n).rev() {
+            bound += rng.gen_range(5..=15);
+            high[rank] = bound;
+        }
+        let high_end = bound + rng.gen_range(5..=15);
+        Bands {
+            low_start,
+            low,
+            high,
+            high_end,
+        }
+    }
+
+    fn describe(&self, rank: usize) -> String {
+        format!(
+            "{}-{} or {}-{}",
+            self.low_start, self.low[rank], self.high[rank], self.high_end
+        )
+    }
+
+    /// A value within band `band`, from either the low or the high range.
+    fn value_in(&self, rng: &mut impl Rng, band: usize) -> u32 {
+        if rng.gen() {
+            let start = if band == 0 {
+                self.low_start
+            } else {
+                self.low[band - 1] + 1
+            };
+            rng.gen_range(start..=self.low[band])
+        } else {
+            let end = if band == 0 {
+                self.high_end
+            } else {
+                self.high[band - 1] - 1
+            };
+            rng.gen_range(self.high[band]..=end)
+        }
+    }
+
+    /// A value which no field accepts.
+    fn invalid(&self, rng: &mut impl Rng) -> u32 {
+        match rng.gen_range(0..3) {
+            0 => rng.gen_range(0..self.low_start),
+            1 => rng.gen_range(self.low[self.low.len() - 1] + 1..self.high[self.high.len() - 1]),
+            _ => rng.gen_range(self.high_end + 1..1000),
+        }
+    }
+}
+
+/// A ticket which every field accepts.
+///
+/// When `exact` is set, each value comes from its field's own band.
+fn valid_ticket(
+    rng: &mut impl Rng,
+    bands: &Bands,
+    column_ranks: &[usize],
+    exact: bool,
+) -> Vec<u32> {
+    column_ranks
+        .iter()
+        .map(|&rank| {
+            let band = if exact { rank } else { rng.gen_range(0..=rank) };
+            bands.value_in(rng, band)
+        })
+        .collect()
+}
+
+fn format_ticket(ticket: &[u32]) -> String {
+    ticket
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Generate ticket notes whose field assignment is uniquely solvable by elimination.
+///
+/// `scale` multiplies the number of nearby tickets; the set of fields is fixed.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let n = FIELD_NAMES.len();
+    let bands = Bands::new(rng, n);
+
+    // the rank of the field in each column
+    let mut column_ranks: Vec<_> = (0..n).collect();
+    column_ranks.shuffle(rng);
+    // the name of the field of each rank
+    let mut names = FIELD_NAMES.to_vec();
+    names.shuffle(rng);
+
+    for (rank, name) in names.iter().enumerate() {
+        writeln!(out, "{}: {}", name, bands.describe(rank))?;
+    }
+
+    // our own ticket pins every column to its field's own band
+    writeln!(out)?;
+    writeln!(out, "your ticket:")?;
+    writeln!(
+        out,
+        "{}",
+        format_ticket(&valid_ticket(rng, &bands, &column_ranks, true))
+    )?;
+
+    writeln!(out)?;
+    writeln!(out, "nearby tickets:")?;
+    for _ in 0..BASE_NEARBY * scale {
+        let mut ticket = valid_ticket(rng, &bands, &column_ranks, false);
+        if rng.gen_bool(0.25) {
+            let idx = rng.gen_range(0..n);
+            ticket[idx] = bands.invalid(rng);
+        }
+        writeln!(out, "{}", format_ticket(&ticket))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{analyze_tickets, model::Input, ticket_scanning_error_rate};
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_generated_tickets_are_solvable() {
+        for seed in 0..8 {
+            let mut buf = Vec::new();
+            generate(&mut StdRng::seed_from_u64(seed), 1, &mut buf).unwrap();
+            let input: Input = String::from_utf8(buf).unwrap().parse().unwrap();
+            assert_eq!(input.fields.len(), FIELD_NAMES.len());
+            assert_eq!(input.nearby_tickets.len(), BASE_NEARBY);
+            assert!(ticket_scanning_error_rate(&input) > 0);
+
+            let mapping = analyze_tickets(&input);
+            assert_eq!(mapping.len(), FIELD_NAMES.len());
+            let columns: HashSet<_> = mapping.values().collect();
+            assert_eq!(columns.len(), FIELD_NAMES.len());
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use aoc2020::geometry::IntervalSet;
 use std::{
     collections::{HashMap, HashSet},
     convert::TryFrom,
@@ -5,37 +6,42 @@ use std::{
 };
 use thiserror::Error;
 
+mod generate;
 mod model;
+
+pub use generate::generate;
 use model::{Input, Ticket, TicketField};
 
+/// Every value which is valid for at least one field.
+fn valid_values(fields: &[TicketField]) -> IntervalSet<u32> {
+    fields.iter().flat_map(TicketField::ranges).collect()
+}
+
 fn ticket_scanning_errors<'a>(
-    fields: &'a [TicketField],
+    valid: &'a IntervalSet<u32>,
     check: &'a Ticket,
 ) -> impl 'a + Iterator<Item = u32> {
     check
         .iter()
-        .filter(move |&value| !fields.iter().any(|field| field.contains(*value)))
+        .filter(move |&&value| !valid.contains(value))
         .copied()
 }
 
 fn ticket_scanning_error_rate(input: &Input) -> u32 {
+    let valid = valid_values(&input.fields);
     input
         .nearby_tickets
         .iter()
-        .map(|ticket| ticket_scanning_errors(&input.fields, ticket))
-        .flatten()
+        .flat_map(|ticket| ticket_scanning_errors(&valid, ticket))
         .sum()
 }
 
 fn valid_nearby_tickets(input: &Input) -> impl '_ + Iterator<Item = &Ticket> {
+    let valid = valid_values(&input.fields);
     input
         .nearby_tickets
         .iter()
-        .filter(move |ticket| {
-            ticket_scanning_errors(&input.fields, ticket)
-                .next()
-                .is_none()
-        })
+        .filter(move |ticket| ticket_scanning_errors(&valid, ticket).next().is_none())
         .chain(std::iter::once(&input.my_ticket))
 }
 
@@ -49,7 +55,7 @@ fn valid_indices_for_field<'a>(
 
 fn analyze_tickets(input: &Input) -> HashMap<String, usize> {
     let valid_tickets: Vec<_> = valid_nearby_tickets(input).collect();
-    let ticket_len = valid_tickets.get(0).map(|ticket| ticket.len());
+    let ticket_len = valid_tickets.first().map(|ticket| ticket.len());
     let mut mapping = HashMap::new();
     let mut known_indices = HashSet::new();
 
@@ -103,6 +109,12 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    let _ = input.parse::<Input>();
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -1,22 +1,23 @@
 use super::Error;
-use aoc2020::CommaSep;
+use aoc2020::{geometry::Interval, CommaSep};
 
 use std::{convert::TryFrom, path::Path, str::FromStr};
 
 #[derive(Clone, parse_display::FromStr, parse_display::Display)]
-#[display("{name}: {low_range_low}-{low_range_high} or {high_range_low}-{high_range_high}")]
+#[display("{name}: {low} or {high}")]
 pub struct TicketField {
     pub(crate) name: String,
-    low_range_low: u32,
-    low_range_high: u32,
-    high_range_low: u32,
-    high_range_high: u32,
+    low: Interval<u32>,
+    high: Interval<u32>,
 }
 
 impl TicketField {
     pub fn contains(&self, n: u32) -> bool {
-        (self.low_range_low..=self.low_range_high).contains(&n)
-            || (self.high_range_low..=self.high_range_high).contains(&n)
+        self.low.contains(n) || self.high.contains(n)
+    }
+
+    pub fn ranges(&self) -> impl Iterator<Item = Interval<u32>> {
+        std::iter::once(self.low).chain(std::iter::once(self.high))
     }
 }
 
@@ -0,0 +1,16 @@
+use rand::Rng;
+use std::io::Write;
+
+const BASE_SIDE: f64 = 8.0;
+
+/// Generate an initial square plane of cubes, with area proportional to `scale`.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let side = (BASE_SIDE * (scale as f64).sqrt()).round() as usize;
+    for _ in 0..side {
+        let row: String = (0..side)
+            .map(|_| if rng.gen_bool(0.45) { '#' } else { '.' })
+            .collect();
+        writeln!(out, "{}", row)?;
+    }
+    Ok(())
+}
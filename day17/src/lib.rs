@@ -1,65 +1,69 @@
-use aoc2020::geometry::{
-    point::{Point, PointTrait},
-    tile::Bool,
-    vector3::Vector3,
-    vector4::Vector4,
-    Map,
-};
-
-use std::{collections::HashSet, convert::TryFrom, ops::Sub, path::Path};
+use aoc2020::geometry::{tile::Bool, AaBox, Map, OffsetTable, SparseMap, VectorN};
+
+use std::{convert::TryFrom, path::Path};
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
+/// An infinite grid of cubes in `D` dimensions, each active or inactive.
 #[derive(Default, Debug, Clone)]
-pub struct ConwaySpace<HighDimensionPoint> {
-    // choose a sparse representation instead of extending map because this space is specifically infinite
-    active: HashSet<HighDimensionPoint>,
-    min: HighDimensionPoint,
-    max: HighDimensionPoint,
+pub struct ConwaySpace<const D: usize> {
+    // choose a sparse representation instead of extending map because this space is specifically infinite;
+    // only active cubes are stored
+    active: SparseMap<VectorN<D>, Bool>,
 }
 
-impl<HighDimensionPoint> ConwaySpace<HighDimensionPoint>
-where
-    HighDimensionPoint:
-        'static + PointTrait + std::hash::Hash + Default + Sub<Output = HighDimensionPoint>,
-    i64: From<<HighDimensionPoint as PointTrait>::N>,
-{
-    fn new<T, Projection>(
-        input: T,
-        projection: Projection,
-    ) -> Result<ConwaySpace<HighDimensionPoint>, <Map<Bool> as TryFrom<T>>::Error>
+impl<const D: usize> ConwaySpace<D> {
+    /// Create a space from a 2d plane of cubes.
+    ///
+    /// The plane's axes become the first two axes of the space; all other coordinates are 0.
+    fn new<T>(input: T) -> Result<ConwaySpace<D>, <Map<Bool> as TryFrom<T>>::Error>
     where
         Map<Bool>: TryFrom<T>,
-        Projection: Fn(Point) -> HighDimensionPoint,
     {
+        assert!(D >= 2, "a plane does not fit in fewer than 2 dimensions");
         let plane = Map::try_from(input)?;
         let mut space = ConwaySpace::default();
 
         plane.for_each_point(|&cube, point| {
-            let point = projection(point);
             if cube.into() {
-                space.active.insert(point);
+                let mut position = VectorN::default();
+                position[0] = point.x;
+                position[1] = point.y;
+                space.active.insert(position, cube);
             }
-            space.min = space.min.boundary_min(point);
-            space.max = space.max.boundary_max(point);
         });
 
         Ok(space)
     }
 
-    fn get(&self, point: HighDimensionPoint) -> bool {
-        self.active.contains(&point)
+    fn get(&self, point: VectorN<D>) -> bool {
+        self.active.contains(point)
     }
 
-    fn successor(&self) -> ConwaySpace<HighDimensionPoint> {
-        let mut successor = ConwaySpace::default();
+    #[cfg(test)]
+    fn successor(&self) -> ConwaySpace<D> {
+        self.successor_with(&OffsetTable::new())
+    }
 
-        for point in HighDimensionPoint::inclusive_range(self.min.decr(), self.max.incr()) {
-            let n_adjacent = point.adjacent().filter(|&point| self.get(point)).count();
+    /// Compute the next cycle, reusing a precomputed table of neighbor offsets.
+    fn successor_with(&self, offsets: &OffsetTable<D>) -> ConwaySpace<D> {
+        let mut successor = ConwaySpace::default();
+        let bounds = match self.active.bounds() {
+            Some(bounds) => AaBox::from(bounds),
+            None => return successor,
+        };
+
+        // only cells within one step of an active cell can become active
+        for point in bounds.expand(1).points() {
+            let n_adjacent = offsets
+                .moore_around(point)
+                .filter(|&point| self.get(point))
+                .count();
             match (self.get(point), n_adjacent) {
                 (true, 2) | (true, 3) | (false, 3) => {
-                    successor.active.insert(point);
-                    successor.min = successor.min.boundary_min(point);
-                    successor.max = successor.max.boundary_max(point);
+                    successor.active.insert(point, Bool::True);
                 }
                 _ => {
                     // in all other cases, the successor of this point is inactive
@@ -70,11 +74,12 @@ where
         successor
     }
 
-    fn nth_successor(&self, n: usize) -> ConwaySpace<HighDimensionPoint> {
+    fn nth_successor(&self, n: usize) -> ConwaySpace<D> {
+        let offsets = OffsetTable::new();
         let mut successor = self.clone();
 
         for _ in 0..n {
-            successor = successor.successor();
+            successor = successor.successor_with(&offsets);
         }
 
         successor
@@ -87,20 +92,22 @@ where
     /// `projected` is the 2d projection of the point.
     /// `on_plane` is `true` when this point is on the plane of interest
     #[cfg(test)]
-    fn plane_2d(&self, project: impl Fn(HighDimensionPoint) -> (Point, bool)) -> Map<Bool> {
-        let (max, _) = project(self.max);
-        let (min, _) = project(self.min);
+    fn plane_2d(
+        &self,
+        project: impl Fn(VectorN<D>) -> (aoc2020::geometry::Point, bool),
+    ) -> Map<Bool> {
+        let (low, high) = self.active.bounds().unwrap_or_default();
+        let (max, _) = project(high);
+        let (min, _) = project(low);
         let width = max.x - min.x + 1;
         let height = max.y - min.y + 1;
 
-        let mut plane = Map::new(width.abs() as usize, height.abs() as usize);
+        let mut plane = Map::new(
+            width.unsigned_abs() as usize,
+            height.unsigned_abs() as usize,
+        );
 
-        for (point, on_plane) in self
-            .active
-            .iter()
-            .map(|&point| point - self.min)
-            .map(project)
-        {
+        for (point, on_plane) in self.active.points().map(|point| point - low).map(project) {
             if on_plane {
                 plane[point] = true.into();
             }
@@ -110,24 +117,32 @@ where
     }
 }
 
+/// Count the active cubes after `cycles` cycles in a space of `D` dimensions.
+pub fn count_active<const D: usize>(input: &Path, cycles: usize) -> Result<usize, Error> {
+    let space = ConwaySpace::<D>::new(input)?;
+    Ok(space.nth_successor(cycles).active.len())
+}
+
 pub fn part1(input: &Path) -> Result<(), Error> {
     const N: usize = 6;
-    let mut space = ConwaySpace::new(input, |point| Vector3::new(point.x, point.y, 0))?;
-    space = space.nth_successor(N);
-    let n_active = space.active.len();
+    let n_active = count_active::<3>(input, N)?;
     println!("{} active cubes (3d) after {} cycles", n_active, N);
     Ok(())
 }
 
 pub fn part2(input: &Path) -> Result<(), Error> {
     const N: usize = 6;
-    let mut space = ConwaySpace::new(input, |point| Vector4::new(point.x, point.y, 0, 0))?;
-    space = space.nth_successor(N);
-    let n_active = space.active.len();
+    let n_active = count_active::<4>(input, N)?;
     println!("{} active cubes (4d) after {} cycles", n_active, N);
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    let _ = Map::<Bool>::try_from(input);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -137,6 +152,7 @@ pub enum Error {
 #[cfg(test)]
 mod test {
     use super::*;
+    use aoc2020::geometry::{vector3::Vector3, vector4::Vector4, Point};
 
     const EXAMPLE: &str = "
 .#.
@@ -153,30 +169,56 @@ mod test {
 .##
 .#.";
 
-    fn example<HighDimensionPoint>(
-        projection: impl Fn(Point) -> HighDimensionPoint,
-    ) -> ConwaySpace<HighDimensionPoint>
+    /// The point type with named axes in which the examples for a space are written.
+    trait NamedAxes {
+        type Point: Copy;
+
+        fn plane_2d_named(&self, project: impl Fn(Self::Point) -> (Point, bool)) -> Map<Bool>;
+    }
+
+    impl NamedAxes for ConwaySpace<3> {
+        type Point = Vector3;
+
+        fn plane_2d_named(&self, project: impl Fn(Vector3) -> (Point, bool)) -> Map<Bool> {
+            self.plane_2d(|hd| project(hd.into()))
+        }
+    }
+
+    impl NamedAxes for ConwaySpace<4> {
+        type Point = Vector4;
+
+        fn plane_2d_named(&self, project: impl Fn(Vector4) -> (Point, bool)) -> Map<Bool> {
+            self.plane_2d(|hd| project(hd.into()))
+        }
+    }
+
+    fn example_n<const D: usize>() -> ConwaySpace<D> {
+        ConwaySpace::new(EXAMPLE.trim()).unwrap()
+    }
+
+    fn example<V, const D: usize>(projection: impl Fn(Point) -> V) -> ConwaySpace<D>
     where
-        HighDimensionPoint:
-            'static + PointTrait + std::hash::Hash + Default + Sub<Output = HighDimensionPoint>,
-        i64: From<<HighDimensionPoint as PointTrait>::N>,
+        V: Into<VectorN<D>>,
     {
-        ConwaySpace::new(EXAMPLE.trim(), projection).unwrap()
+        let plane = Map::<Bool>::try_from(EXAMPLE.trim()).unwrap();
+        let mut space = ConwaySpace::default();
+        plane.for_each_point(|&cube, point| {
+            if cube.into() {
+                space.active.insert(projection(point).into(), cube);
+            }
+        });
+        space
     }
 
-    fn check_projection<HighDimensionPoint>(
-        space: &ConwaySpace<HighDimensionPoint>,
-        projection: impl Fn(HighDimensionPoint) -> (Point, bool),
+    fn check_projection<Space: NamedAxes>(
+        space: &Space,
+        projection: impl Fn(Space::Point) -> (Point, bool),
         expect: &str,
-    ) where
-        HighDimensionPoint:
-            'static + PointTrait + std::hash::Hash + Default + Sub<Output = HighDimensionPoint>,
-        i64: From<<HighDimensionPoint as PointTrait>::N>,
-    {
+    ) {
         // we don't want to be faffing around matching indices; we just want to check that our
         // output matches our input. Just render it as a string, for simplicity.
         let expect_str = Map::<Bool>::try_from(expect.trim()).unwrap().to_string();
-        let have = space.plane_2d(projection);
+        let have = space.plane_2d_named(projection);
         let have_str = have.to_string();
 
         if have_str != expect_str {
@@ -187,6 +229,22 @@ mod test {
         assert_eq!(have.to_string(), expect_str);
     }
 
+    #[test]
+    fn any_dimension() {
+        // in 2 dimensions, the example is a glider: it persists, and moves one step diagonally
+        // every four cycles
+        let glider = example_n::<2>().nth_successor(4);
+        assert_eq!(glider.active.len(), 5);
+        assert_eq!(
+            glider.active.bounds(),
+            Some((VectorN([1, -1]), VectorN([3, 1])))
+        );
+
+        assert_eq!(example_n::<3>().nth_successor(6).active.len(), 112);
+        // as in 3 and 4 dimensions, after one cycle the central plane differs from all the others
+        assert_eq!(example_n::<5>().successor().active.len(), 5 + 26 * 3);
+    }
+
     #[test]
     fn test_projection() {
         let space = example(|point| Vector3::new(point.x, point.y, 0));
@@ -0,0 +1,43 @@
+use rand::Rng;
+use std::io::Write;
+
+const BASE_LEN: usize = 373;
+const MAX_DEPTH: usize = 3;
+/// Whatever the operator precedence, an expression's value is less than the product of
+/// `literal + 1` over all its literals. Keeping that product small bounds both parts' sums.
+const MAX_PRODUCT: u64 = 10_000_000_000_000;
+
+/// Write a random expression into `expr`, returning the product of `literal + 1`.
+fn expression(rng: &mut impl Rng, depth: usize, expr: &mut String) -> u64 {
+    let mut product = 1_u64;
+    for idx in 0..rng.gen_range(2..=6) {
+        if idx > 0 {
+            expr.push_str(if rng.gen() { " + " } else { " * " });
+        }
+        if depth < MAX_DEPTH && rng.gen_bool(0.25) {
+            expr.push('(');
+            product = product.saturating_mul(expression(rng, depth + 1, expr));
+            expr.push(')');
+        } else {
+            let literal = rng.gen_range(1..=9);
+            expr.push_str(&literal.to_string());
+            product = product.saturating_mul(literal + 1);
+        }
+    }
+    product
+}
+
+/// Generate a homework sheet of arithmetic expressions with nested parentheses.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let mut expr = String::new();
+    for _ in 0..BASE_LEN * scale {
+        loop {
+            expr.clear();
+            if expression(rng, 0, &mut expr) <= MAX_PRODUCT {
+                break;
+            }
+        }
+        writeln!(out, "{}", expr)?;
+    }
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use aoc2020::parse;
+use aoc2020::{input::parse_str, parse};
 use lalrpop_util::lalrpop_mod;
 
 use std::{
@@ -8,10 +8,20 @@ use std::{
 };
 use thiserror::Error;
 
-lalrpop_mod!(parser);
+mod generate;
+#[cfg(test)]
+mod reference;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub use generate::generate;
+
+lalrpop_mod!(
+    #[allow(clippy::all)]
+    parser
+);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Operation {
+    #[default]
     Add,
     Mul,
 }
@@ -28,12 +38,6 @@ impl Operation {
     }
 }
 
-impl Default for Operation {
-    fn default() -> Self {
-        Operation::Add
-    }
-}
-
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Value {
     Literal(i64),
@@ -152,6 +156,12 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    parse_str::<Expr>(input).for_each(drop);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
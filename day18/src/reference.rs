@@ -0,0 +1,83 @@
+//! Naive reference implementations, against which the optimized solutions are checked.
+
+use crate::Expr;
+use proptest::prelude::*;
+
+/// Evaluate an expression directly from its text with the shunting-yard algorithm.
+///
+/// `precedence` maps each operator to its binding strength; all operators are left-associative.
+fn shunting_yard(expr: &str, precedence: impl Fn(char) -> u8) -> i128 {
+    fn apply(values: &mut Vec<i128>, op: char) {
+        let b = values.pop().expect("operator has a right operand");
+        let a = values.pop().expect("operator has a left operand");
+        values.push(if op == '+' { a + b } else { a * b });
+    }
+
+    let mut values = Vec::new();
+    let mut operators: Vec<char> = Vec::new();
+    for token in expr.chars().filter(|ch| !ch.is_whitespace()) {
+        match token {
+            '0'..='9' => values.push(token.to_digit(10).unwrap() as i128),
+            '(' => operators.push(token),
+            ')' => {
+                while let Some(op) = operators.pop() {
+                    if op == '(' {
+                        break;
+                    }
+                    apply(&mut values, op);
+                }
+            }
+            '+' | '*' => {
+                while let Some(&op) = operators.last() {
+                    if op == '(' || precedence(op) < precedence(token) {
+                        break;
+                    }
+                    operators.pop();
+                    apply(&mut values, op);
+                }
+                operators.push(token);
+            }
+            _ => panic!("unexpected token {:?}", token),
+        }
+    }
+    while let Some(op) = operators.pop() {
+        apply(&mut values, op);
+    }
+    values.pop().expect("expression is not empty")
+}
+
+/// Well-formed expressions over single-digit literals.
+///
+/// The size is limited so that no evaluation order can overflow an `i64`.
+fn expression() -> impl Strategy<Value = String> {
+    let literal = (1..=9_u8).prop_map(|n| n.to_string());
+    literal.prop_recursive(2, 16, 4, |inner| {
+        (
+            inner.clone(),
+            prop::collection::vec((any::<bool>(), inner), 1..4),
+        )
+            .prop_map(|(first, rest)| {
+                let mut expr = first;
+                for (add, value) in rest {
+                    expr.push_str(if add { " + " } else { " * " });
+                    expr.push_str(&value);
+                }
+                format!("({})", expr)
+            })
+    })
+}
+
+proptest! {
+    #[test]
+    fn value_matches_reference(expr in expression()) {
+        let parsed: Expr = expr.parse().unwrap();
+        prop_assert_eq!(parsed.value() as i128, shunting_yard(&expr, |_| 0));
+    }
+
+    #[test]
+    fn advanced_matches_reference(expr in expression()) {
+        let parsed: Expr = expr.parse().unwrap();
+        let precedence = |op| if op == '+' { 1 } else { 0 };
+        prop_assert_eq!(parsed.evaluate_advanced() as i128, shunting_yard(&expr, precedence));
+    }
+}
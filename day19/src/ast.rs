@@ -94,7 +94,7 @@ impl FromStr for Input {
                 }
                 _ => {
                     return Err(Error::Parse(
-                        section[..50].to_string(),
+                        section.chars().take(50).collect(),
                         "more sections than expected".to_string(),
                     ));
                 }
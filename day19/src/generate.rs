@@ -0,0 +1,238 @@
+use rand::{seq::SliceRandom, Rng};
+use std::{
+    collections::{BTreeSet, HashMap},
+    io::Write,
+};
+
+const BASE_MESSAGES: usize = 400;
+/// Rules 42 and 31 each match strings of exactly this length.
+const CHUNK_LEN: usize = 8;
+/// Identifiers which the puzzle's second part refers to explicitly.
+const PINNED: &[usize] = &[0, 8, 11, 42, 31];
+
+enum Term {
+    Literal(u8),
+    Subrules(Vec<Vec<usize>>),
+}
+
+/// Build rules matching exactly the strings of a given set.
+///
+/// Rules are memoized by the set they match, and use sequential internal identifiers
+/// which are later shuffled.
+#[derive(Default)]
+struct Builder {
+    rules: Vec<Term>,
+    memo: HashMap<BTreeSet<Vec<u8>>, usize>,
+}
+
+impl Builder {
+    fn push(&mut self, term: Term) -> usize {
+        self.rules.push(term);
+        self.rules.len() - 1
+    }
+
+    fn literal(&mut self, ch: u8) -> usize {
+        let set: BTreeSet<_> = std::iter::once(vec![ch]).collect();
+        if let Some(&id) = self.memo.get(&set) {
+            return id;
+        }
+        let id = self.push(Term::Literal(ch));
+        self.memo.insert(set, id);
+        id
+    }
+
+    /// Build a rule matching exactly the non-empty, equal-length strings in `set`.
+    ///
+    /// Each alternative begins with a distinct literal, so any match is unique.
+    fn build(&mut self, set: BTreeSet<Vec<u8>>) -> usize {
+        if let Some(&id) = self.memo.get(&set) {
+            return id;
+        }
+        let mut alternatives = Vec::new();
+        for &ch in b"ab" {
+            let suffixes: BTreeSet<_> = set
+                .iter()
+                .filter(|s| s[0] == ch)
+                .map(|s| s[1..].to_vec())
+                .collect();
+            if suffixes.is_empty() {
+                continue;
+            }
+            let mut alternative = vec![self.literal(ch)];
+            if suffixes.iter().any(|suffix| !suffix.is_empty()) {
+                alternative.push(self.build(suffixes));
+            }
+            alternatives.push(alternative);
+        }
+        // a rule with a single alternative of a single literal is just that literal
+        if let [alternative] = alternatives.as_slice() {
+            if let [literal] = alternative.as_slice() {
+                return *literal;
+            }
+        }
+        let id = self.push(Term::Subrules(alternatives));
+        self.memo.insert(set, id);
+        id
+    }
+}
+
+fn all_strings(len: usize) -> Vec<Vec<u8>> {
+    (0..1_usize << len)
+        .map(|n| {
+            (0..len)
+                .map(|bit| if n & (1 << bit) == 0 { b'a' } else { b'b' })
+                .collect()
+        })
+        .collect()
+}
+
+fn message(
+    rng: &mut impl Rng,
+    n_42: usize,
+    n_31: usize,
+    set_42: &[Vec<u8>],
+    set_31: &[Vec<u8>],
+) -> String {
+    let mut message = Vec::with_capacity((n_42 + n_31) * CHUNK_LEN);
+    for _ in 0..n_42 {
+        message.extend(set_42.choose(rng).unwrap());
+    }
+    for _ in 0..n_31 {
+        message.extend(set_31.choose(rng).unwrap());
+    }
+    String::from_utf8(message).expect("only ascii literals; qed")
+}
+
+/// Generate a rule set and messages.
+///
+/// Rules 42 and 31 partition the strings of length 8 between them, so every message has at
+/// most one parse, with or without the looping rules of the second part. `scale` multiplies
+/// the number of messages.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let mut strings = all_strings(CHUNK_LEN);
+    strings.shuffle(rng);
+    let (set_42, set_31) = strings.split_at(strings.len() / 2);
+
+    let mut builder = Builder::default();
+    let rule_42 = builder.build(set_42.iter().cloned().collect());
+    let rule_31 = builder.build(set_31.iter().cloned().collect());
+    let rule_8 = builder.push(Term::Subrules(vec![vec![rule_42]]));
+    let rule_11 = builder.push(Term::Subrules(vec![vec![rule_42, rule_31]]));
+    let rule_0 = builder.push(Term::Subrules(vec![vec![rule_8, rule_11]]));
+
+    // assign final identifiers: pinned rules keep their numbers, and the rest are shuffled
+    let n_rules = builder.rules.len();
+    let mut free: Vec<_> = (0..n_rules.max(PINNED.len() + 1 + 42))
+        .filter(|id| !PINNED.contains(id))
+        .collect();
+    free.shuffle(rng);
+    let mut free = free.into_iter();
+    let idents: Vec<_> = (0..n_rules)
+        .map(|internal| {
+            [rule_0, rule_8, rule_11, rule_42, rule_31]
+                .iter()
+                .position(|&pinned| pinned == internal)
+                .map(|idx| PINNED[idx])
+                .unwrap_or_else(|| free.next().expect("enough identifiers; qed"))
+        })
+        .collect();
+
+    let mut lines: Vec<_> = builder
+        .rules
+        .iter()
+        .enumerate()
+        .map(|(internal, term)| {
+            let body = match term {
+                Term::Literal(ch) => format!("\"{}\"", *ch as char),
+                Term::Subrules(alternatives) => alternatives
+                    .iter()
+                    .map(|alternative| {
+                        alternative
+                            .iter()
+                            .map(|&id| idents[id].to_string())
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+            };
+            format!("{}: {}", idents[internal], body)
+        })
+        .collect();
+    lines.shuffle(rng);
+    for line in lines {
+        writeln!(out, "{}", line)?;
+    }
+
+    writeln!(out)?;
+    let n_messages = BASE_MESSAGES * scale;
+    for idx in 0..n_messages {
+        let msg = match rng.gen_range(0..4) {
+            // matches in both parts
+            0 => message(rng, 2, 1, set_42, set_31),
+            // matches only with the looping rules
+            1 => {
+                let n_31 = rng.gen_range(1..=3);
+                let n_42 = rng.gen_range(n_31 + 1..=6);
+                message(rng, n_42, n_31, set_42, set_31)
+            }
+            // never matches: too many trailing chunks
+            2 => {
+                let n_42 = rng.gen_range(1..=3);
+                let n_31 = rng.gen_range(n_42..=4);
+                message(rng, n_42, n_31, set_42, set_31)
+            }
+            // never matches: length is not a multiple of the chunk length
+            _ => {
+                let mut msg = message(rng, 2, 1, set_42, set_31);
+                msg.truncate(msg.len() - rng.gen_range(1..CHUNK_LEN));
+                msg
+            }
+        };
+        // no trailing newline, which would parse as an extra empty message
+        if idx + 1 < n_messages {
+            writeln!(out, "{}", msg)?;
+        } else {
+            write!(out, "{}", msg)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{matches_rule, Input};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn full_matches(rule: usize, input: &Input, msg: &str) -> usize {
+        matches_rule(rule, &input.rules, msg)
+            .iter()
+            .filter(|remainder| remainder.is_empty())
+            .count()
+    }
+
+    #[test]
+    fn test_42_and_31_partition_chunks() {
+        for seed in 0..8 {
+            let mut buf = Vec::new();
+            generate(&mut StdRng::seed_from_u64(seed), 1, &mut buf).unwrap();
+            let input: Input = String::from_utf8(buf).unwrap().parse().unwrap();
+            assert_eq!(input.messages.len(), BASE_MESSAGES);
+
+            for chunk in all_strings(CHUNK_LEN) {
+                let chunk = String::from_utf8(chunk).unwrap();
+                assert_eq!(
+                    full_matches(42, &input, &chunk) + full_matches(31, &input, &chunk),
+                    1,
+                    "chunk {} must match exactly one of rules 42 and 31",
+                    chunk,
+                );
+            }
+            assert!(input
+                .messages
+                .iter()
+                .any(|msg| full_matches(0, &input, msg) == 1));
+        }
+    }
+}
@@ -2,7 +2,10 @@ use std::{collections::HashMap, convert::TryFrom, path::Path};
 use thiserror::Error;
 
 mod ast;
+mod generate;
+
 use ast::{Ident, Input, Rule, RuleTerm};
+pub use generate::generate;
 
 /// returns a list of portions remaining after successful matches of alternatives.
 ///
@@ -20,7 +23,7 @@ fn matches_rule<'a>(rule: Ident, rules: &HashMap<Ident, Rule>, input: &'a str) -
 
     match &rule.term {
         RuleTerm::Literal(ch) => {
-            if input.chars().next() == Some(*ch) {
+            if input.starts_with(*ch) {
                 return vec![&input[ch.len_utf8()..]];
             }
         }
@@ -81,6 +84,12 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    let _ = input.parse::<Input>();
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -0,0 +1,261 @@
+use crate::sea_monster;
+use aoc2020::geometry::{find_pattern, tile::Bool, Map, Orientation, Point, Rect};
+use rand::{
+    seq::{index::sample, SliceRandom},
+    Rng,
+};
+use std::{collections::HashSet, io::Write};
+
+const BASE_TILES_PER_SIDE: usize = 12;
+const BASE_TILE_WIDTH: usize = 10;
+/// Edges are stored as `u16`, which bounds the tile width, and the number of distinct edges
+/// needed grows with the square of the side length, which bounds the scale.
+const MAX_SCALE: usize = 7;
+const MAX_TILE_WIDTH: usize = 16;
+/// Noise pixels are set with this probability.
+const NOISE_DENSITY: f64 = 0.25;
+/// Roughly one sea monster is planted per this many image pixels.
+const PIXELS_PER_MONSTER: usize = 300;
+
+fn random_image(rng: &mut impl Rng, side: usize) -> Map<Bool> {
+    let mut image = Map::new(side, side);
+    for y in 0..side {
+        for x in 0..side {
+            image[(x, y)] = rng.gen_bool(NOISE_DENSITY).into();
+        }
+    }
+    image
+}
+
+type Pixel = (usize, usize);
+
+/// Plant sea monsters which do not overlap, returning the pixels of each.
+fn plant_sea_monsters(rng: &mut impl Rng, image: &mut Map<Bool>) -> Vec<Vec<Pixel>> {
+    let side = image.width();
+    let target = side * side / PIXELS_PER_MONSTER;
+    let sea_monster = sea_monster();
+    let (monster_width, monster_height) =
+        (sea_monster.cells().width(), sea_monster.cells().height());
+    let shape: Vec<Point> = sea_monster
+        .cells()
+        .points()
+        .filter(|&point| sea_monster.cells()[point].is_some())
+        .collect();
+    let mut occupied: HashSet<Pixel> = HashSet::new();
+    let mut planted = Vec::with_capacity(target);
+    for _ in 0..target * 10 {
+        if planted.len() == target {
+            break;
+        }
+        let x = rng.gen_range(0..=side - monster_width);
+        let y = rng.gen_range(0..=side - monster_height);
+        let footprint: Vec<_> = (x..x + monster_width)
+            .flat_map(|x| (y..y + monster_height).map(move |y| (x, y)))
+            .collect();
+        if footprint.iter().any(|pixel| occupied.contains(pixel)) {
+            continue;
+        }
+        occupied.extend(footprint);
+        let mut pixels: Vec<_> = shape
+            .iter()
+            .map(|point| (x + point.x as usize, y + point.y as usize))
+            .collect();
+        for &pixel in &pixels {
+            image[pixel] = true.into();
+        }
+        pixels.sort_unstable();
+        planted.push(pixels);
+    }
+    planted
+}
+
+/// Noise can complete sea monsters which were never planted, particularly where it
+/// overlaps planted monsters. Clear a noise pixel from each such monster.
+///
+/// Returns `false` if an accidental monster consists entirely of planted pixels.
+fn clear_accidental_sea_monsters(
+    rng: &mut impl Rng,
+    image: &mut Map<Bool>,
+    planted: &[Vec<Pixel>],
+) -> bool {
+    let planted_pixels: HashSet<Pixel> = planted.iter().flatten().copied().collect();
+    let planted: HashSet<&[Pixel]> = planted.iter().map(|pixels| pixels.as_slice()).collect();
+    for found in find_pattern(image, &sea_monster()) {
+        let mut pixels: Vec<Pixel> = found
+            .cells
+            .iter()
+            .map(|point| (point.x as usize, point.y as usize))
+            .collect();
+        // clearing an earlier monster may already have broken this one
+        if !pixels.iter().all(|&pixel| image[pixel].into()) {
+            continue;
+        }
+        pixels.sort_unstable();
+        if planted.contains(pixels.as_slice()) {
+            continue;
+        }
+        let noise: Vec<_> = pixels
+            .into_iter()
+            .filter(|pixel| !planted_pixels.contains(pixel))
+            .collect();
+        match noise.choose(rng) {
+            Some(&pixel) => image[pixel] = false.into(),
+            None => return false,
+        }
+    }
+    true
+}
+
+/// A straight run of pixels along a seam, between two lattice corners.
+fn segment(start: (usize, usize), horizontal: bool, len: usize) -> Vec<(usize, usize)> {
+    (0..len)
+        .map(|offset| {
+            if horizontal {
+                (start.0 + offset, start.1)
+            } else {
+                (start.0, start.1 + offset)
+            }
+        })
+        .collect()
+}
+
+fn edge_value(pixels: &Map<Bool>, segment: &[(usize, usize)]) -> u16 {
+    segment.iter().enumerate().fold(0, |acc, (bit, &pixel)| {
+        if pixels[pixel].into() {
+            acc | 1 << bit
+        } else {
+            acc
+        }
+    })
+}
+
+/// Randomize the seams between tiles so that every edge is distinct from every other edge,
+/// even allowing for reversal, and no edge is palindromic.
+///
+/// This ensures that the tiles have exactly one arrangement, up to symmetry.
+fn randomize_seams(
+    rng: &mut impl Rng,
+    pixels: &mut Map<Bool>,
+    tiles_per_side: usize,
+    tile_width: usize,
+) {
+    let stride = tile_width - 1;
+    let mut seen = HashSet::new();
+    for horizontal in [true, false].iter().copied() {
+        for a in 0..tiles_per_side {
+            for b in 0..=tiles_per_side {
+                let start = if horizontal {
+                    (a * stride, b * stride)
+                } else {
+                    (b * stride, a * stride)
+                };
+                let segment = segment(start, horizontal, tile_width);
+                // corners are shared with other segments; only the interior may change
+                loop {
+                    for &pixel in &segment[1..tile_width - 1] {
+                        pixels[pixel] = rng.gen_bool(0.5).into();
+                    }
+                    let value = edge_value(pixels, &segment);
+                    let reversed = crate::reverse_edge(value, tile_width);
+                    if value != reversed && seen.insert(value.min(reversed)) {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Generate camera tiles cut from a random image in which sea monsters have been planted.
+///
+/// Each tile is independently rotated and flipped. `scale` multiplies the number of tiles
+/// along each side, and widens the tiles so that edges remain distinct; it is capped at 7.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let scale = scale.min(MAX_SCALE);
+    let tiles_per_side = BASE_TILES_PER_SIDE * scale;
+    let tile_width = (BASE_TILE_WIDTH + 2 * (scale - 1)).min(MAX_TILE_WIDTH);
+    let inner_width = tile_width - 2;
+    let stride = tile_width - 1;
+
+    // the final image, containing exactly the planted sea monsters
+    let image = loop {
+        let mut image = random_image(rng, tiles_per_side * inner_width);
+        let planted = plant_sea_monsters(rng, &mut image);
+        if !clear_accidental_sea_monsters(rng, &mut image, &planted) {
+            continue;
+        }
+        if find_pattern(&image, &sea_monster()).len() == planted.len() {
+            break image;
+        }
+    };
+
+    // adjacent tiles overlap by one pixel: each shares its border with its neighbors
+    let pixels_side = tiles_per_side * stride + 1;
+    let mut pixels: Map<Bool> = Map::new(pixels_side, pixels_side);
+    for y in 0..pixels_side {
+        for x in 0..pixels_side {
+            if x % stride == 0 || y % stride == 0 {
+                pixels[(x, y)] = rng.gen_bool(0.5).into();
+            } else {
+                let image_x = (x / stride) * inner_width + x % stride - 1;
+                let image_y = (y / stride) * inner_width + y % stride - 1;
+                pixels[(x, y)] = image[(image_x, image_y)];
+            }
+        }
+    }
+    randomize_seams(rng, &mut pixels, tiles_per_side, tile_width);
+
+    let ids = sample(rng, 9000, tiles_per_side * tiles_per_side);
+    let mut tiles: Vec<_> = ids
+        .into_iter()
+        .map(|id| id as u16 + 1000)
+        .zip(0..)
+        .collect();
+    // the tile order in the input carries no information
+    tiles.shuffle(rng);
+
+    for (idx, (id, position)) in tiles.into_iter().enumerate() {
+        let (tile_x, tile_y) = (position % tiles_per_side, position / tiles_per_side);
+        let corner = Point::from((tile_x * stride, tile_y * stride));
+        let tile = pixels
+            .crop(Rect::new(corner, tile_width, tile_width))
+            .expect("tiles lie within the pixels");
+        let tile = tile.to_oriented(Orientation::new(rng.gen(), rng.gen_range(0..4)));
+
+        if idx > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "Tile {}:", id)?;
+        write!(out, "{}", tile)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{arrange_tiles, convert_to_image, Tile};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_generated_tiles_reassemble() {
+        for seed in 0..2 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut buf = Vec::new();
+            generate(&mut rng, 1, &mut buf).unwrap();
+            let tiles: Vec<Tile> = String::from_utf8(buf)
+                .unwrap()
+                .split("\n\n")
+                .map(|tile| tile.parse().unwrap())
+                .collect();
+            assert_eq!(tiles.len(), BASE_TILES_PER_SIDE * BASE_TILES_PER_SIDE);
+
+            let image = convert_to_image(arrange_tiles(tiles).unwrap());
+            assert_eq!(image.width(), BASE_TILES_PER_SIDE * (BASE_TILE_WIDTH - 2));
+            assert_eq!(
+                find_pattern(&image, &sea_monster()).len(),
+                image.width() * image.height() / PIXELS_PER_MONSTER
+            );
+        }
+    }
+}
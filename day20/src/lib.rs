@@ -1,6 +1,11 @@
 use aoc2020::{
-    geometry::{tile::Bool, Direction, Map, Point},
-    input::parse_newline_sep,
+    geometry::{
+        find_pattern, mark_matches,
+        render::{self, Image, Rgb},
+        tile::Bool,
+        Direction, Map, Orientation, Pattern, Point, Rect,
+    },
+    input::{parse_newline_sep, parse_newline_sep_str},
 };
 
 use std::{
@@ -11,6 +16,9 @@ use std::{
 };
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 #[derive(
     Clone,
     Copy,
@@ -45,22 +53,18 @@ impl FromStr for Tile {
             Some(idx) => s.split_at(idx),
         };
 
-        Ok(Tile {
+        let tile = Tile {
             id: tile_id.parse::<TileId>()?.0,
             data: (&tile_data[1..]).try_into()?,
-        })
-    }
-}
-
-impl Tile {
-    fn flip_vertical(&mut self) {
-        self.data = self.data.flip_vertical();
-    }
+        };
 
-    fn rotate_left(&mut self, times: u8) {
-        for _ in 0..times {
-            self.data = self.data.rotate_left();
+        // edges are stored as bitmaps in a `u16`
+        let width = tile.data.width();
+        if width != tile.data.height() || width == 0 || width > 16 {
+            return Err(Error::TileShape(tile.id));
         }
+
+        Ok(tile)
     }
 }
 
@@ -93,15 +97,15 @@ struct TileRepr {
     edges: [u16; 4],
 
     // keep track of the orientation of this tile, for use in reconstructing the original
-    flipped: bool,
-    left_rotations: u8,
+    orientation: Orientation,
 }
 
 impl From<&Tile> for TileRepr {
     fn from(tile: &Tile) -> Self {
-        let mut repr = TileRepr::default();
-
-        repr.id = tile.id;
+        let mut repr = TileRepr {
+            id: tile.id,
+            ..TileRepr::default()
+        };
 
         // top
         repr.edges[0] =
@@ -170,10 +174,8 @@ impl TileRepr {
         side
     }
 
-    fn rotate_left(mut self, times: usize) -> Self {
-        self.edges.rotate_left(times);
-        self.left_rotations += times as u8;
-        self.left_rotations %= self.edges.len() as u8;
+    fn rotate_left(mut self) -> Self {
+        self.edges.rotate_left(1);
         self
     }
 
@@ -186,15 +188,19 @@ impl TileRepr {
             *edge = reverse_edge(*edge, edge_width);
         }
         self.edges.swap(0, 2);
-        self.flipped = !self.flipped;
         self
     }
 
-    fn all_orientations(self, edge_width: usize) -> impl Iterator<Item = TileRepr> {
-        let flipped = self.flip_vertical(edge_width);
-        (0..4)
-            .map(move |n| self.rotate_left(n))
-            .chain((0..4).map(move |n| flipped.rotate_left(n)))
+    /// This tile, further transformed into the given orientation.
+    fn oriented(mut self, orientation: Orientation, edge_width: usize) -> Self {
+        if orientation.flipped() {
+            self = self.flip_vertical(edge_width);
+        }
+        for _ in 0..orientation.rotations() {
+            self = self.rotate_left();
+        }
+        self.orientation = self.orientation.then(orientation);
+        self
     }
 }
 
@@ -283,8 +289,10 @@ fn arrange_tiles(tiles: impl IntoIterator<Item = Tile>) -> Result<Map<Tile>, Err
 
     let reprs: Vec<TileRepr> = tiles
         .values()
-        .map(|tile| TileRepr::from(tile).all_orientations(edge_width))
-        .flatten()
+        .flat_map(|tile| {
+            let repr = TileRepr::from(tile);
+            Orientation::iter().map(move |orientation| repr.oriented(orientation, edge_width))
+        })
         .collect();
 
     let output_edge = (tiles.len() as f64).sqrt() as usize;
@@ -300,10 +308,7 @@ fn arrange_tiles(tiles: impl IntoIterator<Item = Tile>) -> Result<Map<Tile>, Err
             let mut tile = tiles
                 .remove(&repr.id)
                 .expect("all repr ids correspond to a tile id");
-            if repr.flipped {
-                tile.flip_vertical();
-            }
-            tile.rotate_left(repr.left_rotations);
+            tile.data = tile.data.to_oriented(repr.orientation);
 
             output_map[point] = tile;
         }
@@ -322,87 +327,32 @@ pub fn tiles_map_from_input(input: &Path) -> Result<Map<Tile>, Error> {
 }
 
 fn convert_to_image(tiles: Map<Tile>) -> Map<Bool> {
-    if tiles.width() == 0 || tiles.height() == 0 {
-        return Map::new(tiles.width(), tiles.height());
-    }
-    let tile_width = tiles[(0, 0)].data.width() - 2;
-    let mut image = Map::new(tiles.width() * tile_width, tiles.height() * tile_width);
-
-    for tile_point in tiles.points() {
-        for y in 0..tile_width {
-            let image_y = (tile_point.y as usize * tile_width) + y;
-            for x in 0..tile_width {
-                let image_x = (tile_point.x as usize * tile_width) + x;
-                image[(image_x, image_y)] = tiles[tile_point].data[(x + 1, y + 1)];
-            }
-        }
-    }
-
-    image
+    // strip the border
+    let inner = tiles.map_tiles(|tile| {
+        let inner_width = tile.data.width().saturating_sub(2);
+        tile.data
+            .crop(Rect::new(Point::new(1, 1), inner_width, inner_width))
+            .expect("the inner part of a tile lies within it")
+    });
+    Map::from_grid(&inner).expect("all tiles have the same width")
 }
 
 // Fig. 1: The Wild Sea Monster
-//
-//                   #
-// #    ##    ##    ###
-//  #  #  #  #  #  #
-
-const SEA_MONSTER_WIDTH: usize = 20;
-const SEA_MONSTER_HEIGHT: usize = 3;
-const SEA_MONSTER: &[Point] = &[
-    Point::new(0, 1),
-    Point::new(1, 0),
-    Point::new(4, 0),
-    Point::new(5, 1),
-    Point::new(6, 1),
-    Point::new(7, 0),
-    Point::new(10, 0),
-    Point::new(11, 1),
-    Point::new(12, 1),
-    Point::new(13, 0),
-    Point::new(16, 0),
-    Point::new(17, 1),
-    Point::new(18, 1),
-    Point::new(19, 1),
-    Point::new(18, 2),
-];
-
-// Note: while it doesn't make sense to me that there could be sea monsters
-// which overlap each other, it's in principle possible. If the answer is too low,
-// consider a more robust monster-marking solution.
-fn count_sea_monsters_in(image: &Map<Bool>) -> usize {
-    let mut count = 0;
-    for y in 0..=image.height() - SEA_MONSTER_HEIGHT {
-        for x in 0..=image.width() - SEA_MONSTER_WIDTH {
-            if SEA_MONSTER
-                .iter()
-                .all(|point| image[(x + point.x as usize, y + point.y as usize)].into())
-            {
-                count += 1;
-            }
-        }
-    }
-    count
+const SEA_MONSTER: &str = concat!(
+    "                  # \n",
+    "#    ##    ##    ###\n",
+    " #  #  #  #  #  #   \n",
+);
+
+fn sea_monster() -> Pattern<Bool> {
+    Pattern::parse(SEA_MONSTER, ' ').expect("sea monster is a valid pattern")
 }
 
-fn all_orientations(image: &Map<Bool>) -> impl Iterator<Item = Map<Bool>> {
-    let image = image.clone();
-    let flipped = image.flip_vertical();
-    (0..4)
-        .map(move |n| {
-            let mut image = image.clone();
-            for _ in 0..n {
-                image = image.rotate_left();
-            }
-            image
-        })
-        .chain((0..4).map(move |n| {
-            let mut image = flipped.clone();
-            for _ in 0..n {
-                image = image.rotate_left();
-            }
-            image
-        }))
+/// Count the tiles of the image which are rough water, not part of any sea monster.
+fn count_chop(mut image: Map<Bool>) -> usize {
+    let sea_monsters = find_pattern(&image, &sea_monster());
+    mark_matches(&mut image, &sea_monsters, |tile| *tile = Bool::False);
+    image.iter().filter(|&&tile| tile.into()).count()
 }
 
 pub fn part1(input: &Path) -> Result<Map<Tile>, Error> {
@@ -422,18 +372,42 @@ pub fn part1(input: &Path) -> Result<Map<Tile>, Error> {
 }
 
 pub fn part2(tiles_map: Map<Tile>) -> Result<(), Error> {
+    let chop = count_chop(convert_to_image(tiles_map));
+
+    println!("{} tiles of chop", chop);
+    Ok(())
+}
+
+/// Write the reconstructed image to a file, with its sea monsters highlighted.
+///
+/// The format is chosen by the file's extension.
+pub fn render(tiles_map: Map<Tile>, output: &Path) -> Result<(), Error> {
     let image = convert_to_image(tiles_map);
+    let sea_monsters = find_pattern(&image, &sea_monster());
 
-    let sea_monsters: usize = all_orientations(&image)
-        .map(|image| count_sea_monsters_in(&image))
-        .sum();
-    let total_hashes: usize = image.iter().filter(|&elem| (*elem).into()).count();
-    let chop = total_hashes - (sea_monsters * SEA_MONSTER.len());
+    let mut colors = Map::<Rgb>::new(image.width(), image.height());
+    image.for_each_point(|&tile, point| {
+        colors[point] = match tile {
+            Bool::True => Rgb::new(220, 230, 255),
+            Bool::False => Rgb::new(20, 60, 140),
+        };
+    });
+    mark_matches(&mut colors, &sea_monsters, |color| {
+        *color = Rgb::new(60, 200, 80)
+    });
 
-    println!("{} tiles of chop", chop);
+    Image::from_map(&colors, 4).save(output)?;
     Ok(())
 }
 
+/// Fuzzing entry point: parse and check `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    for tile in parse_newline_sep_str::<Tile>(input).flatten() {
+        let _ = TileRepr::from(&tile);
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -446,8 +420,12 @@ pub enum Error {
     Map(#[from] aoc2020::geometry::map::MapConversionErr),
     #[error("not all tiles have equal width and height; {0} is bad")]
     MalformedTile(u16),
+    #[error("tile {0} must be square and between 1 and 16 pixels wide")]
+    TileShape(u16),
     #[error("no solution found")]
     NoSolution,
+    #[error(transparent)]
+    Render(#[from] render::Error),
 }
 
 #[cfg(test)]
@@ -647,9 +625,9 @@ Tile 3079:
         println!("orientations:");
 
         let mut contains_example_image = false;
-        for orientation in all_orientations(&image) {
+        for orientation in image.view().orientations() {
             println!("{}", orientation);
-            if orientation == example_image {
+            if orientation.to_map() == example_image {
                 contains_example_image = true;
                 break;
             }
@@ -664,6 +642,15 @@ Tile 3079:
     #[test]
     fn example_image_has_two_sea_monsters() {
         let example_image = example_sea_monsters();
-        assert_eq!(count_sea_monsters_in(&example_image), 2);
+        assert_eq!(find_pattern(&example_image, &sea_monster()).len(), 2);
+        assert_eq!(count_chop(example_image), 273);
+    }
+
+    #[test]
+    fn malformed_tiles_are_rejected() {
+        let wide = format!("Tile 1:\n{}", vec![".".repeat(17); 17].join("\n"));
+        for tile in &["Tile 1:\n", "Tile 1:\n...\n...", wide.as_str()] {
+            assert!(matches!(tile.parse::<Tile>(), Err(Error::TileShape(1))));
+        }
     }
 }
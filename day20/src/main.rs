@@ -20,6 +20,10 @@ struct RunArgs {
     /// run part 2
     #[structopt(long)]
     part2: bool,
+
+    /// write the reconstructed image, with sea monsters highlighted, to this file
+    #[structopt(long, parse(from_os_str))]
+    render: Option<PathBuf>,
 }
 
 impl RunArgs {
@@ -47,11 +51,14 @@ fn main() -> Result<()> {
     if !args.no_part1 {
         tiles_map = Some(part1(&input_path)?);
     }
+    if (args.part2 || args.render.is_some()) && tiles_map.is_none() {
+        tiles_map = Some(day20::tiles_map_from_input(&input_path)?);
+    }
     if args.part2 {
-        if tiles_map.is_none() {
-            tiles_map = Some(day20::tiles_map_from_input(&input_path)?);
-        }
-        part2(tiles_map.expect("it can't be none here; qed"))?;
+        part2(tiles_map.clone().expect("it can't be none here; qed"))?;
+    }
+    if let Some(ref output) = args.render {
+        day20::render(tiles_map.expect("it can't be none here; qed"), output)?;
     }
     Ok(())
 }
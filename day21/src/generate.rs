@@ -0,0 +1,112 @@
+use crate::{plausible_allergens, Food};
+use rand::{
+    seq::{index::sample, SliceRandom},
+    Rng,
+};
+use std::{collections::HashSet, io::Write};
+
+const BASE_FOODS: usize = 40;
+const N_INGREDIENTS: usize = 200;
+const ALLERGENS: &[&str] = &[
+    "dairy",
+    "eggs",
+    "fish",
+    "nuts",
+    "peanuts",
+    "sesame",
+    "shellfish",
+    "soy",
+    "wheat",
+];
+
+fn ingredient_names(rng: &mut impl Rng) -> Vec<String> {
+    let mut names = HashSet::with_capacity(N_INGREDIENTS);
+    while names.len() < N_INGREDIENTS {
+        let len = rng.gen_range(4..=8);
+        let name: String = (0..len)
+            .map(|_| rng.gen_range(b'a'..=b'z') as char)
+            .collect();
+        names.insert(name);
+    }
+    let mut names: Vec<_> = names.into_iter().collect();
+    // hash set iteration order is not determined by the seed
+    names.sort_unstable();
+    names.shuffle(rng);
+    names
+}
+
+/// True when repeatedly assigning allergens with exactly one plausible ingredient
+/// identifies every allergen.
+fn solvable_by_elimination(foods: &[Food]) -> bool {
+    let mut plausible = plausible_allergens(foods);
+    if plausible.len() != ALLERGENS.len() {
+        return false;
+    }
+    while !plausible.is_empty() {
+        let known: HashSet<String> = plausible
+            .values()
+            .filter(|ingredients| ingredients.len() == 1)
+            .flatten()
+            .cloned()
+            .collect();
+        if known.is_empty() {
+            return false;
+        }
+        plausible.retain(|_, ingredients| {
+            ingredients.retain(|ingredient| !known.contains(ingredient));
+            !ingredients.is_empty()
+        });
+    }
+    true
+}
+
+/// Generate a list of foods in which each allergen is found in exactly one ingredient,
+/// and those ingredients can be identified by elimination.
+///
+/// `scale` multiplies the number of foods; the set of allergens is fixed.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let foods = loop {
+        let ingredients = ingredient_names(rng);
+        // the first ingredients contain the allergens, in order
+        let foods: Vec<_> = (0..BASE_FOODS * scale)
+            .map(|_| {
+                let n_allergens = rng.gen_range(1..=3);
+                let allergens: Vec<_> = sample(rng, ALLERGENS.len(), n_allergens)
+                    .into_iter()
+                    .collect();
+                let n_ingredients = rng.gen_range(40..=90);
+                let mut food_ingredients: HashSet<_> = sample(rng, N_INGREDIENTS, n_ingredients)
+                    .into_iter()
+                    .map(|idx| ingredients[idx].clone())
+                    .collect();
+                food_ingredients.extend(allergens.iter().map(|&idx| ingredients[idx].clone()));
+                Food {
+                    ingredients: food_ingredients,
+                    allergens: allergens
+                        .into_iter()
+                        .map(|idx| ALLERGENS[idx].to_string())
+                        .collect(),
+                }
+            })
+            .collect();
+        if solvable_by_elimination(&foods) {
+            break foods;
+        }
+    };
+
+    for food in foods {
+        let mut ingredients: Vec<_> = food.ingredients.into_iter().collect();
+        ingredients.sort_unstable();
+        ingredients.shuffle(rng);
+        let mut allergens: Vec<_> = food.allergens.into_iter().collect();
+        allergens.sort_unstable();
+        allergens.shuffle(rng);
+        writeln!(
+            out,
+            "{} (contains {})",
+            ingredients.join(" "),
+            allergens.join(", ")
+        )?;
+    }
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use aoc2020::parse;
+use aoc2020::{input::parse_str, parse};
 
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -9,6 +9,9 @@ use std::{
 };
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 lazy_static! {
     static ref FOOD_RE: Regex = Regex::new(r"([\w\s]*)\(contains ([\w\s,]+)\)").unwrap();
 }
@@ -87,12 +90,11 @@ fn implausible_allergens<'a>(
 ) -> impl 'a + Iterator<Item = String> {
     foods
         .iter()
-        .map(move |food| {
+        .flat_map(move |food| {
             food.ingredients.iter().filter(move |&ingredient| {
                 !plausible.values().any(|values| values.contains(ingredient))
             })
         })
-        .flatten()
         .cloned()
 }
 
@@ -124,10 +126,10 @@ fn identify_allergens(foods: &[Food]) -> HashMap<String, String> {
                 .collect();
         }
 
-        debug_assert!(newly_known_ingredients.len() > 0);
+        debug_assert!(!newly_known_ingredients.is_empty());
         newly_known_ingredients.clear();
 
-        plausible.retain(|_allergen, possible_ingredients| possible_ingredients.len() > 0);
+        plausible.retain(|_allergen, possible_ingredients| !possible_ingredients.is_empty());
     }
 
     allergens
@@ -165,6 +167,12 @@ pub fn part2(input: &Path) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    parse_str::<Food>(input).for_each(drop);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
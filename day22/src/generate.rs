@@ -0,0 +1,46 @@
+use crate::{play_round, Player};
+use rand::{seq::SliceRandom, Rng};
+use std::{collections::VecDeque, io::Write};
+
+const BASE_DECK: usize = 25;
+/// Cards are `u8`, so each player's deck is capped.
+const MAX_DECK: usize = 127;
+/// Plain combat can cycle forever; decks which take longer than this are rejected.
+const MAX_ROUNDS: usize = 100_000;
+
+fn finishes(deck1: &[u8], deck2: &[u8]) -> bool {
+    let mut player1 = Player {
+        id: 1,
+        cards: deck1.iter().copied().collect::<VecDeque<_>>(),
+    };
+    let mut player2 = Player {
+        id: 2,
+        cards: deck2.iter().copied().collect::<VecDeque<_>>(),
+    };
+    (0..MAX_ROUNDS).any(|_| play_round(&mut player1, &mut player2).is_some())
+}
+
+/// Generate a shuffled deck dealt between two players, such that a plain game finishes.
+///
+/// `scale` multiplies the size of each deck, up to a maximum of 127 cards.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let deck_size = (BASE_DECK * scale).min(MAX_DECK);
+    let mut cards: Vec<u8> = (1..=2 * deck_size as u8).collect();
+    loop {
+        cards.shuffle(rng);
+        if finishes(&cards[..deck_size], &cards[deck_size..]) {
+            break;
+        }
+    }
+
+    for (idx, deck) in cards.chunks(deck_size).enumerate() {
+        if idx > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "Player {}:", idx + 1)?;
+        for card in deck {
+            writeln!(out, "{}", card)?;
+        }
+    }
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use aoc2020::input::parse_newline_sep;
+use aoc2020::input::{parse_newline_sep, parse_newline_sep_str};
 
 use regex::Regex;
 use std::{
@@ -8,6 +8,9 @@ use std::{
 };
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 lazy_static::lazy_static! {
     static ref PLAYER_RE: Regex = Regex::new(r"^Player (\d+):$").unwrap();
 }
@@ -263,6 +266,12 @@ pub fn part2(input: &Path, trace: bool) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    parse_newline_sep_str::<Player>(input).for_each(drop);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -0,0 +1,14 @@
+use rand::{seq::SliceRandom, Rng};
+use std::io::Write;
+
+/// Generate `scale` starting cup arrangements, one per line.
+///
+/// Each arrangement is a permutation of the cups labeled 1 through 9.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let mut cups: Vec<_> = ('1'..='9').collect();
+    for _ in 0..scale {
+        cups.shuffle(rng);
+        writeln!(out, "{}", cups.iter().collect::<String>())?;
+    }
+    Ok(())
+}
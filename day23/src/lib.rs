@@ -1,8 +1,11 @@
-use aoc2020::parse;
+use aoc2020::{input::parse_str, parse};
 
 use std::{fmt, path::Path, str::FromStr};
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 #[derive(Clone, Default)]
 struct CupGame {
     successors: Vec<usize>,
@@ -14,31 +17,34 @@ impl FromStr for CupGame {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut first = None;
-        let mut predecessor = 0;
-        let mut n = 0;
-        let mut game = CupGame::default();
-        game.successors.resize(s.chars().count() + 1, 0);
-
-        for ch in s.chars() {
-            n = ch.to_string().parse()?;
-            if first.is_none() {
-                first = Some(n);
-            }
-            game.successors[predecessor] = n;
-            predecessor = n;
-        }
-        // we both insert and remove one element after this point,
-        // so we can check the length directly, even though the map
-        // isn't precisely as it will be on a successful return
-        if game.successors.len() < 5 {
+        let cups = s
+            .chars()
+            .map(|ch| ch.to_string().parse())
+            .collect::<Result<Vec<usize>, _>>()?;
+        if cups.len() < 4 {
             return Err(Error::TooFewCups);
         }
 
-        // we know that these must have been set, so just unwrap
-        game.successors[n] = first.unwrap();
-        game.max = game.successors.iter().copied().max().unwrap();
-        game.current = first.unwrap();
+        // every label from 1 through the number of cups must appear exactly once
+        let mut seen = vec![false; cups.len() + 1];
+        for &cup in &cups {
+            if cup == 0 || cup > cups.len() || std::mem::replace(&mut seen[cup], true) {
+                return Err(Error::NotAPermutation(s.to_string()));
+            }
+        }
+
+        let first = cups[0];
+        let last = cups[cups.len() - 1];
+        let mut game = CupGame {
+            successors: vec![0; cups.len() + 1],
+            max: cups.len(),
+            current: first,
+        };
+        game.successors[0] = first;
+        for pair in cups.windows(2) {
+            game.successors[pair[0]] = pair[1];
+        }
+        game.successors[last] = first;
 
         Ok(game)
     }
@@ -153,6 +159,12 @@ pub fn part2(input: &Path, trace: bool) -> Result<(), Error> {
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    parse_str::<CupGame>(input).for_each(drop);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -161,6 +173,8 @@ pub enum Error {
     Num(#[from] std::num::ParseIntError),
     #[error("the game doesn't work without at least 5 cups")]
     TooFewCups,
+    #[error("cups must be labeled 1 through the number of cups; got {0:?}")]
+    NotAPermutation(String),
 }
 
 #[cfg(test)]
@@ -223,4 +237,16 @@ mod tests {
 
         assert_eq!(product, 149245887792);
     }
+
+    #[test]
+    fn test_malformed_cups() {
+        assert!(matches!(CupGame::from_str("123"), Err(Error::TooFewCups)));
+        assert!(matches!(CupGame::from_str("12a4"), Err(Error::Num(_))));
+        for cups in &["0123", "1123", "1259", "98765432"] {
+            assert!(matches!(
+                CupGame::from_str(cups),
+                Err(Error::NotAPermutation(_))
+            ));
+        }
+    }
 }
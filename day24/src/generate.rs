@@ -0,0 +1,16 @@
+use rand::{seq::SliceRandom, Rng};
+use std::io::Write;
+
+const BASE_LEN: usize = 500;
+const DIRECTIONS: &[&str] = &["e", "se", "sw", "w", "nw", "ne"];
+
+/// Generate a list of tiles to flip, each described by a walk from the reference tile.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    for _ in 0..BASE_LEN * scale {
+        let walk: String = (0..rng.gen_range(15..=25))
+            .map(|_| *DIRECTIONS.choose(rng).unwrap())
+            .collect();
+        writeln!(out, "{}", walk)?;
+    }
+    Ok(())
+}
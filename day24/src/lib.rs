@@ -1,54 +1,18 @@
-use aoc2020::parse;
-use std::{
-    collections::HashSet,
-    iter::FromIterator,
-    ops::{Add, AddAssign},
-    path::Path,
-    str::FromStr,
+use aoc2020::{
+    geometry::{
+        hex::{Hex, HexDirection, Layout},
+        render::{self, Animation, Image, Rgb},
+        tile::Bool,
+        SparseMap,
+    },
+    input::parse_str,
+    parse,
 };
+use std::{iter::FromIterator, path::Path, str::FromStr};
 use thiserror::Error;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum HexDirection {
-    East,
-    Southeast,
-    Southwest,
-    West,
-    Northwest,
-    Northeast,
-}
-
-impl HexDirection {
-    fn iter() -> impl Iterator<Item = HexDirection> {
-        std::iter::successors(Some(HexDirection::East), |direction| {
-            use HexDirection::*;
-
-            match direction {
-                East => Some(Southeast),
-                Southeast => Some(Southwest),
-                Southwest => Some(West),
-                West => Some(Northwest),
-                Northwest => Some(Northeast),
-                Northeast => None,
-            }
-        })
-    }
-
-    fn try_parse(s: &str) -> (Option<HexDirection>, &str) {
-        let mut chars = s.chars();
-        let first = chars.next();
-        let second = chars.next();
-        match (first, second) {
-            (Some('e'), _) => (Some(HexDirection::East), &s[1..]),
-            (Some('s'), Some('e')) => (Some(HexDirection::Southeast), &s[2..]),
-            (Some('s'), Some('w')) => (Some(HexDirection::Southwest), &s[2..]),
-            (Some('w'), _) => (Some(HexDirection::West), &s[1..]),
-            (Some('n'), Some('w')) => (Some(HexDirection::Northwest), &s[2..]),
-            (Some('n'), Some('e')) => (Some(HexDirection::Northeast), &s[2..]),
-            _ => (None, s),
-        }
-    }
-}
+mod generate;
+pub use generate::generate;
 
 struct HexDirections(Vec<HexDirection>);
 
@@ -59,12 +23,10 @@ impl FromStr for HexDirections {
         let mut directions = Vec::with_capacity(s.len());
 
         while !s.is_empty() {
-            let (direction, remaining) = HexDirection::try_parse(s);
-            match direction {
-                None => return Err(Error::ParseFailure),
-                Some(direction) => directions.push(direction),
-            }
-
+            let (direction, remaining) = Layout::Pointy
+                .parse_direction(s)
+                .ok_or(Error::ParseFailure)?;
+            directions.push(direction);
             s = remaining;
         }
 
@@ -72,122 +34,38 @@ impl FromStr for HexDirections {
     }
 }
 
-/// Axial hex coordinates.
-///
-/// See [reference](https://www.redblobgames.com/grids/hexagons/#coordinates).
-///
-/// Constraint: `q + r + s == 0`
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
-struct HexCoordinate {
-    q: i32,
-    r: i32,
-}
-
-impl HexCoordinate {
-    fn neighbors(self) -> impl 'static + Iterator<Item = HexCoordinate> {
-        HexDirection::iter().map(move |direction| self + direction)
-    }
-}
-
-impl AddAssign<HexDirection> for HexCoordinate {
-    fn add_assign(&mut self, rhs: HexDirection) {
-        match rhs {
-            HexDirection::East => {
-                self.q += 1;
-            }
-            HexDirection::Southeast => {
-                self.r += 1;
-            }
-            HexDirection::Southwest => {
-                self.q -= 1;
-                self.r += 1;
-            }
-            HexDirection::West => {
-                self.q -= 1;
-            }
-            HexDirection::Northwest => {
-                self.r -= 1;
-            }
-            HexDirection::Northeast => {
-                self.q += 1;
-                self.r -= 1;
-            }
-        }
-    }
-}
-
-impl Add<HexDirection> for HexCoordinate {
-    type Output = HexCoordinate;
-
-    fn add(mut self, rhs: HexDirection) -> Self::Output {
-        self += rhs;
-        self
-    }
-}
-
+/// The lobby floor. Only black tiles are stored.
 #[derive(Debug, Default, Clone)]
-struct HexMap {
-    coords: HashSet<HexCoordinate>,
+struct Floor {
+    coords: SparseMap<Hex, Bool>,
 }
 
-impl HexMap {
-    fn toggle(&mut self, coord: HexCoordinate) {
+impl Floor {
+    fn toggle(&mut self, coord: Hex) {
         // try to remove the coordinate.
-        // `remove` returns whether the value was present, so if it wasn't,
+        // `remove` returns the value if it was present, so if it wasn't,
         // then we can add it.
-        if !self.coords.remove(&coord) {
-            self.coords.insert(coord);
-        }
-    }
-
-    fn is_black(&self, coord: HexCoordinate) -> bool {
-        self.coords.contains(&coord)
-    }
-
-    fn count_black_neighbors_of(&self, coord: HexCoordinate) -> usize {
-        coord
-            .neighbors()
-            .filter(|coord| self.coords.contains(coord))
-            .count()
-    }
-
-    fn checked_coordinates(&self) -> HashSet<HexCoordinate> {
-        let mut checked = self.coords.clone();
-        for coord in self.coords.iter() {
-            for neighbor in coord.neighbors() {
-                checked.insert(neighbor);
-            }
+        if self.coords.remove(coord).is_none() {
+            self.coords.insert(coord, Bool::True);
         }
-        checked
     }
 
-    fn conway_step(&self) -> HexMap {
-        let mut successor = self.clone();
-
-        for coord in self.checked_coordinates() {
-            match (self.is_black(coord), self.count_black_neighbors_of(coord)) {
-                (true, n) if n == 0 || n > 2 => {
-                    successor.coords.remove(&coord);
-                }
-                (false, 2) => {
-                    successor.coords.insert(coord);
-                }
-                _ => {
-                    // leave all other tiles as they are
-                }
-            }
+    fn conway_step(&self) -> Floor {
+        Floor {
+            coords: self.coords.life_step(|black, n| match (black, n) {
+                (true, n) => n == 1 || n == 2,
+                (false, n) => n == 2,
+            }),
         }
-
-        successor
     }
 }
 
-impl FromIterator<HexDirections> for HexMap {
+impl FromIterator<HexDirections> for Floor {
     fn from_iter<T: IntoIterator<Item = HexDirections>>(iter: T) -> Self {
-        let mut map = HexMap::default();
+        let mut map = Floor::default();
 
         for HexDirections(directions) in iter.into_iter() {
-            let mut coord = HexCoordinate::default();
+            let mut coord = Hex::ORIGIN;
             for direction in directions {
                 coord += direction;
             }
@@ -199,13 +77,13 @@ impl FromIterator<HexDirections> for HexMap {
 }
 
 pub fn part1(input: &Path) -> Result<(), Error> {
-    let map: HexMap = parse(input)?.collect();
+    let map: Floor = parse(input)?.collect();
     println!("black tiles: {}", map.coords.len());
     Ok(())
 }
 
 pub fn part2(input: &Path, trace: bool) -> Result<(), Error> {
-    let mut map: HexMap = parse(input)?.collect();
+    let mut map: Floor = parse(input)?.collect();
     for i in 1..=100 {
         map = map.conway_step();
         if trace && (i < 10 || i % 10 == 0) {
@@ -216,10 +94,63 @@ pub fn part2(input: &Path, trace: bool) -> Result<(), Error> {
     Ok(())
 }
 
+/// The position of a hex when the floor is drawn as a brick wall.
+///
+/// Each hex is two columns wide, and each row is offset by one column from the row above.
+fn brick(coord: Hex) -> (i32, i32) {
+    (2 * coord.q + coord.r, coord.r)
+}
+
+/// Write an animated GIF of the floor's black tiles, from the initial layout through day 100.
+pub fn render(input: &Path, output: &Path) -> Result<(), Error> {
+    // pixels per column and per row of bricks
+    const COLUMN: usize = 3;
+    const ROW: usize = 5;
+    const WHITE: Rgb = Rgb::new(235, 235, 225);
+    const BLACK: Rgb = Rgb::new(30, 30, 40);
+
+    let mut days = vec![parse(input)?.collect::<Floor>()];
+    for _ in 1..=100 {
+        let next = days[days.len() - 1].conway_step();
+        days.push(next);
+    }
+
+    // every frame has the same extent, so find the extent of all of them
+    let mut bricks = days.iter().flat_map(|map| map.coords.points().map(brick));
+    let first = bricks.next().unwrap_or_default();
+    let (min, max) = bricks.fold((first, first), |(min, max), (x, y)| {
+        ((min.0.min(x), min.1.min(y)), (max.0.max(x), max.1.max(y)))
+    });
+    let width = (max.0 - min.0 + 2) as usize * COLUMN;
+    let height = (max.1 - min.1 + 1) as usize * ROW;
+
+    let mut animation = Animation::create(output, width, height, 10)?;
+    for map in &days {
+        let mut image = Image::new(width, height, WHITE);
+        for (x, y) in map.coords.points().map(brick) {
+            let x = (x - min.0) as usize * COLUMN;
+            let y = (y - min.1) as usize * ROW;
+            // leave a gap between bricks, so that neighboring black tiles stay distinct
+            image.fill_rect(x, y, 2 * COLUMN - 1, ROW - 1, BLACK);
+        }
+        animation.push(&image)?;
+    }
+    animation.finish()?;
+    Ok(())
+}
+
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    parse_str::<HexDirections>(input).for_each(drop);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error("parse failure")]
     ParseFailure,
+    #[error(transparent)]
+    Render(#[from] render::Error),
 }
@@ -1,5 +1,5 @@
 use aoc2020::{config::Config, website::get_input};
-use day24::{part1, part2};
+use day24::{part1, part2, render};
 
 use color_eyre::eyre::Result;
 use std::path::PathBuf;
@@ -21,6 +21,10 @@ struct RunArgs {
     #[structopt(long)]
     part2: bool,
 
+    /// write an animated GIF of the floor over 100 days
+    #[structopt(long, parse(from_os_str))]
+    render: Option<PathBuf>,
+
     /// trace part 2 execution
     #[structopt(long)]
     trace: bool,
@@ -52,5 +56,8 @@ fn main() -> Result<()> {
     if args.part2 {
         part2(&input_path, args.trace)?;
     }
+    if let Some(ref output) = args.render {
+        render(&input_path, output)?;
+    }
     Ok(())
 }
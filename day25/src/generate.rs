@@ -0,0 +1,19 @@
+use crate::{transform, PUBLIC_KEY_SUBJECT};
+use rand::Rng;
+use std::io::Write;
+
+const BASE_MAX_LOOP_SIZE: u32 = 2_000_000;
+/// Loop sizes beyond the order of the multiplicative group repeat earlier keys.
+const MAX_LOOP_SIZE: u32 = 20_201_226;
+
+/// Generate the card's and the door's public keys.
+///
+/// `scale` multiplies the maximum secret loop size.
+pub fn generate(rng: &mut impl Rng, scale: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let max_loop_size = (BASE_MAX_LOOP_SIZE as usize * scale).min(MAX_LOOP_SIZE as usize) as u32;
+    for _ in 0..2 {
+        let loop_size = rng.gen_range(1..max_loop_size);
+        writeln!(out, "{}", transform(loop_size, PUBLIC_KEY_SUBJECT))?;
+    }
+    Ok(())
+}
@@ -1,54 +1,43 @@
-use aoc2020::parse;
+use aoc2020::{
+    input::parse_str,
+    numbers::modular::{discrete_log, pow_mod},
+    parse,
+};
 
 use std::path::Path;
 use thiserror::Error;
 
+mod generate;
+pub use generate::generate;
+
 type Key = u32;
 
-const MAX_ATTEMPTS: u32 = !0;
 const MAGIC_DIVISOR: u64 = 20201227;
 const PUBLIC_KEY_SUBJECT: Key = 7;
 
-fn transform(loop_size: u32, subject_number: u32) -> u32 {
-    let mut value = 1;
-    for _ in 0..loop_size {
-        value *= subject_number as u64;
-        value %= MAGIC_DIVISOR;
-    }
-    value as u32
+fn transform(loop_size: u32, subject_number: Key) -> Key {
+    pow_mod(subject_number as u64, loop_size as u64, MAGIC_DIVISOR) as Key
 }
 
-fn find_loop_size(subject_number: u32, key: Key) -> Option<u32> {
-    let mut value = 1;
-    for loop_size in 0..MAX_ATTEMPTS {
-        if value == key as u64 {
-            return Some(loop_size);
-        }
-        value *= subject_number as u64;
-        value %= MAGIC_DIVISOR;
+/// Find the smallest loop size which transforms `subject_number` into `key`, if there is one.
+///
+/// Every transform is reduced modulo the magic divisor, so no loop size produces a larger key.
+fn find_loop_size(subject_number: Key, key: Key) -> Option<u32> {
+    if key as u64 >= MAGIC_DIVISOR {
+        return None;
     }
-    None
+    // the logarithm is less than the order of the group, so it fits
+    discrete_log(subject_number as u64, key as u64, MAGIC_DIVISOR).map(|loop_size| loop_size as u32)
 }
 
-/// Compute the encryption key given the public keys
-fn crack_given_keys((card, door): (Key, Key)) -> Result<Key, Error> {
-    let card_loop_size =
-        find_loop_size(PUBLIC_KEY_SUBJECT, card).ok_or(Error::NoSolution("card"))?;
-    let encryption_key = transform(card_loop_size, door);
-
-    // if in debug mode, double-check the invariants
-    #[cfg(debug_assertions)]
-    {
-        let door_loop_size =
-            find_loop_size(PUBLIC_KEY_SUBJECT, door).ok_or(Error::NoSolution("door"))?;
-        let encryption_key_2 = transform(door_loop_size, card);
-        assert_eq!(
-            encryption_key, encryption_key_2,
-            "card and door must compute same encryption key!"
-        );
-    }
-
-    Ok(encryption_key)
+/// Compute the encryption key given the subject number and both public keys.
+///
+/// Only the card's loop size is needed to derive the key, but the door's public key must also be
+/// reachable from the subject number: otherwise no handshake could have produced it.
+fn crack_given_keys(subject_number: Key, (card, door): (Key, Key)) -> Result<Key, Error> {
+    let card_loop_size = find_loop_size(subject_number, card).ok_or(Error::NoSolution("card"))?;
+    find_loop_size(subject_number, door).ok_or(Error::NoSolution("door"))?;
+    Ok(transform(card_loop_size, door))
 }
 
 fn parse_keys(input: &Path) -> Result<(Key, Key), Error> {
@@ -60,11 +49,17 @@ fn parse_keys(input: &Path) -> Result<(Key, Key), Error> {
 }
 
 pub fn part1(input: &Path) -> Result<(), Error> {
-    let encryption_key = crack_given_keys(parse_keys(input)?)?;
+    let encryption_key = crack_given_keys(PUBLIC_KEY_SUBJECT, parse_keys(input)?)?;
     println!("encryption key: {}", encryption_key);
     Ok(())
 }
 
+/// Fuzzing entry point: parse `input` without reporting the results.
+#[doc(hidden)]
+pub fn fuzz(input: &str) {
+    parse_str::<Key>(input).for_each(drop);
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
@@ -74,3 +69,44 @@ pub enum Error {
     #[error("failed to crack {0} key to find its loop size")]
     NoSolution(&'static str),
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let (card, door) = (5764801, 17807724);
+        assert_eq!(find_loop_size(PUBLIC_KEY_SUBJECT, card), Some(8));
+        assert_eq!(find_loop_size(PUBLIC_KEY_SUBJECT, door), Some(11));
+        assert_eq!(
+            crack_given_keys(PUBLIC_KEY_SUBJECT, (card, door)).unwrap(),
+            14897079
+        );
+    }
+
+    #[test]
+    fn test_other_subjects() {
+        // 4 is a square, so it generates only half the group
+        let subject = 4;
+        let (card, door) = (transform(123_456, subject), transform(7_654_321, subject));
+        let key = crack_given_keys(subject, (card, door)).unwrap();
+        let shared = pow_mod(card as u64, 7_654_321, MAGIC_DIVISOR);
+        assert_eq!(key as u64, shared);
+        assert!(matches!(
+            crack_given_keys(subject, (card, PUBLIC_KEY_SUBJECT)),
+            Err(Error::NoSolution("door"))
+        ));
+    }
+
+    #[test]
+    fn test_unreduced_key() {
+        // congruent to the example card key, but no transform can produce it
+        let card = 5764801 + MAGIC_DIVISOR as Key;
+        assert_eq!(find_loop_size(PUBLIC_KEY_SUBJECT, card), None);
+        assert!(matches!(
+            crack_given_keys(PUBLIC_KEY_SUBJECT, (card, 17807724)),
+            Err(Error::NoSolution("card"))
+        ));
+    }
+}
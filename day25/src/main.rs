@@ -19,6 +19,7 @@ struct RunArgs {
 
     /// no-op: would run part 2 if today had one
     #[structopt(long)]
+    #[allow(dead_code)]
     part2: bool,
 }
 
@@ -0,0 +1,10 @@
+#![no_main]
+use aoc2020::geometry::{tile::Bool, Map};
+use libfuzzer_sys::fuzz_target;
+use std::convert::TryFrom;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = Map::<Bool>::try_from(input);
+    }
+});
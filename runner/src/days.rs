@@ -0,0 +1,122 @@
+use color_eyre::eyre::Result;
+use rand::rngs::StdRng;
+use std::{io::Write, path::Path};
+
+/// A single part of a day's puzzle, erased to a common signature.
+///
+/// Parts print their own answers; the runner only cares whether they succeeded.
+pub type PartFn = fn(&Path) -> Result<()>;
+
+/// A day's input generator, specialized to the runner's RNG.
+pub type GenerateFn = fn(&mut StdRng, usize, &mut dyn Write) -> std::io::Result<()>;
+
+/// Everything the runner needs to know about a day.
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub day: u8,
+    pub part1: PartFn,
+    /// Not every day has a second part.
+    pub part2: Option<PartFn>,
+    pub generate: GenerateFn,
+}
+
+impl Day {
+    /// Iterate over `(part_number, part)` for the parts this day implements.
+    pub fn parts(&self) -> impl Iterator<Item = (u8, PartFn)> {
+        std::iter::once((1, self.part1)).chain(self.part2.map(|part2| (2, part2)))
+    }
+}
+
+/// Adapt a part with the standard `fn(&Path) -> Result<_, E>` signature.
+///
+/// Any non-unit return value is discarded.
+macro_rules! part {
+    ($part:path) => {
+        |input: &Path| -> Result<()> {
+            $part(input)?;
+            Ok(())
+        }
+    };
+}
+
+macro_rules! day {
+    ($day:literal, $krate:ident) => {
+        Day {
+            day: $day,
+            part1: part!($krate::part1),
+            part2: Some(part!($krate::part2)),
+            generate: $krate::generate,
+        }
+    };
+}
+
+/// All days known to the runner.
+///
+/// When initializing a new day, add it here as well as to this crate's `Cargo.toml`.
+pub const DAYS: &[Day] = &[
+    day!(1, day01),
+    day!(2, day02),
+    day!(3, day03),
+    day!(4, day04),
+    day!(5, day05),
+    day!(6, day06),
+    day!(7, day07),
+    day!(8, day08),
+    Day {
+        day: 9,
+        part1: part!(day09::part1),
+        part2: Some(|input| Ok(day09::part2(input, None)?)),
+        generate: day09::generate,
+    },
+    day!(10, day10),
+    day!(11, day11),
+    day!(12, day12),
+    day!(13, day13),
+    Day {
+        day: 14,
+        part1: |input| Ok(day14::part1(input, false)?),
+        part2: Some(|input| Ok(day14::part2(input, false)?)),
+        generate: day14::generate,
+    },
+    day!(15, day15),
+    day!(16, day16),
+    day!(17, day17),
+    day!(18, day18),
+    day!(19, day19),
+    Day {
+        day: 20,
+        part1: part!(day20::part1),
+        part2: Some(|input| Ok(day20::part2(day20::tiles_map_from_input(input)?)?)),
+        generate: day20::generate,
+    },
+    day!(21, day21),
+    Day {
+        day: 22,
+        part1: part!(day22::part1),
+        part2: Some(|input| Ok(day22::part2(input, false)?)),
+        generate: day22::generate,
+    },
+    Day {
+        day: 23,
+        part1: |input| Ok(day23::part1(input, false)?),
+        part2: Some(|input| Ok(day23::part2(input, false)?)),
+        generate: day23::generate,
+    },
+    Day {
+        day: 24,
+        part1: part!(day24::part1),
+        part2: Some(|input| Ok(day24::part2(input, false)?)),
+        generate: day24::generate,
+    },
+    Day {
+        day: 25,
+        part1: part!(day25::part1),
+        part2: None,
+        generate: day25::generate,
+    },
+];
+
+/// Find a day by number.
+pub fn get(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
@@ -0,0 +1,260 @@
+mod days;
+mod pool;
+mod profile;
+
+use aoc2020::{config::Config, website::get_input};
+use color_eyre::eyre::{bail, Result};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    io::{BufWriter, Write},
+    path::PathBuf,
+    time::Duration,
+};
+use structopt::StructOpt;
+
+#[global_allocator]
+static ALLOCATOR: profile::CountingAllocator = profile::CountingAllocator;
+
+#[derive(StructOpt, Debug)]
+#[structopt(about = "run advent of code 2020 solutions")]
+enum Subcommand {
+    /// Run many days at once
+    Run(RunOpts),
+    /// Generate a random input for a day
+    Generate(GenerateOpts),
+}
+
+#[derive(StructOpt, Debug)]
+struct RunOpts {
+    /// Day to run; may be repeated (default: all days)
+    #[structopt(short, long = "day")]
+    days: Vec<u8>,
+
+    /// Number of parts to run concurrently (default: available parallelism)
+    #[structopt(short, long)]
+    jobs: Option<usize>,
+
+    /// Wall-clock seconds to allow each part before giving up on it
+    #[structopt(long, default_value = "60")]
+    timeout: u64,
+
+    /// Directory containing input files (default: per the configuration)
+    ///
+    /// When this is set, missing inputs are not downloaded.
+    #[structopt(long, parse(from_os_str))]
+    inputs: Option<PathBuf>,
+
+    /// Report peak heap, allocation count, and bytes allocated for each part
+    #[structopt(long = "profile-alloc")]
+    profile_alloc: bool,
+
+    /// skip part 1
+    #[structopt(long = "no-part1")]
+    no_part1: bool,
+
+    /// skip part 2
+    #[structopt(long = "no-part2")]
+    no_part2: bool,
+}
+
+impl RunOpts {
+    fn days(&self) -> Result<Vec<&'static days::Day>> {
+        if self.days.is_empty() {
+            return Ok(days::DAYS.iter().collect());
+        }
+        self.days
+            .iter()
+            .map(|&day| match days::get(day) {
+                Some(day) => Ok(day),
+                None => bail!("day {} is not known to the runner", day),
+            })
+            .collect()
+    }
+
+    fn run(self) -> Result<()> {
+        let days = self.days()?;
+
+        let config = match self.inputs {
+            Some(ref inputs) => Config {
+                input_files: Some(inputs.clone()),
+                ..Config::default()
+            },
+            None => Config::load()?,
+        };
+
+        let mut jobs = Vec::new();
+        let mut reports = Vec::new();
+        for day in days {
+            if self.inputs.is_none() {
+                // this does nothing if the input file already exists
+                if let Err(err) = get_input(&config, day.day) {
+                    for (part, _) in day.parts() {
+                        reports.push(pool::Report {
+                            day: day.day,
+                            part,
+                            outcome: pool::Outcome::Fail(format!("getting input: {}", err)),
+                            alloc: None,
+                        });
+                    }
+                    continue;
+                }
+            }
+            for (part, run) in day.parts() {
+                if (part == 1 && self.no_part1) || (part == 2 && self.no_part2) {
+                    continue;
+                }
+                jobs.push(pool::Job {
+                    day: day.day,
+                    part,
+                    run,
+                    input: config.input_for(day.day),
+                });
+            }
+        }
+
+        let workers = self.jobs.unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        });
+        let timeout = Duration::from_secs(self.timeout);
+        reports.extend(pool::run(
+            jobs,
+            workers,
+            timeout,
+            self.profile_alloc,
+            |report| eprintln!("{}", report),
+        ));
+        reports.sort_by_key(|report| (report.day, report.part));
+
+        println!();
+        println!("summary:");
+        for report in &reports {
+            println!("  {}", report);
+        }
+
+        let failures = reports
+            .iter()
+            .filter(|report| !report.outcome.is_pass())
+            .count();
+        if failures > 0 {
+            bail!("{} of {} parts did not pass", failures, reports.len());
+        }
+        Ok(())
+    }
+}
+
+#[derive(StructOpt, Debug)]
+struct GenerateOpts {
+    /// Day for which to generate input
+    #[structopt(short, long)]
+    day: u8,
+
+    /// Seed for the random number generator (default: random)
+    ///
+    /// The same seed and scale always produce the same input.
+    #[structopt(short, long)]
+    seed: Option<u64>,
+
+    /// Approximate size relative to an official input
+    ///
+    /// Some days cap the scale where the puzzle format imposes limits.
+    #[structopt(short = "k", long, default_value = "1")]
+    scale: usize,
+
+    /// Write the input to this file (default: stdout)
+    #[structopt(short, long, parse(from_os_str))]
+    output: Option<PathBuf>,
+}
+
+impl GenerateOpts {
+    fn run(self) -> Result<()> {
+        let day = match days::get(self.day) {
+            Some(day) => day,
+            None => bail!("day {} is not known to the runner", self.day),
+        };
+        if self.scale == 0 {
+            bail!("scale must be at least 1");
+        }
+        let seed = self.seed.unwrap_or_else(rand::random);
+        eprintln!("generating day {} with seed {}", day.day, seed);
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut out: BufWriter<Box<dyn Write>> = BufWriter::new(match self.output {
+            Some(ref path) => Box::new(std::fs::File::create(path)?),
+            None => Box::new(std::io::stdout()),
+        });
+        (day.generate)(&mut rng, self.scale, &mut out)?;
+        out.flush()?;
+        Ok(())
+    }
+}
+
+impl Subcommand {
+    fn run(self) -> Result<()> {
+        match self {
+            Self::Run(opts) => opts.run(),
+            Self::Generate(opts) => opts.run(),
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let opt = Subcommand::from_args();
+    let code = match opt.run() {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            1
+        }
+    };
+    // parts which timed out are still running on abandoned threads; don't wait for them
+    std::io::stdout().flush()?;
+    std::process::exit(code)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs::File;
+
+    /// Every generated input must be accepted by each of its day's parts.
+    #[test]
+    fn test_generated_inputs_round_trip() {
+        let dir = std::env::temp_dir().join(format!("aoc2020-generate-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut jobs = Vec::new();
+        for day in days::DAYS {
+            let input = dir.join(format!("input-{:02}.txt", day.day));
+            let mut out = BufWriter::new(File::create(&input).unwrap());
+            let mut rng = StdRng::seed_from_u64(day.day.into());
+            (day.generate)(&mut rng, 1, &mut out).unwrap();
+            out.flush().unwrap();
+            // run both parts: some generated properties, such as day16's uniquely solvable fields
+            // and day20's sea monsters, are only exercised by part 2
+            for (part, run) in day.parts() {
+                jobs.push(pool::Job {
+                    day: day.day,
+                    part,
+                    run,
+                    input: input.clone(),
+                });
+            }
+        }
+
+        let workers = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        let reports = pool::run(jobs, workers, Duration::from_secs(120), false, |_| {});
+        std::fs::remove_dir_all(&dir).ok();
+
+        let failures: Vec<_> = reports
+            .iter()
+            .filter(|report| !report.outcome.is_pass())
+            .map(ToString::to_string)
+            .collect();
+        assert!(failures.is_empty(), "{:#?}", failures);
+    }
+}
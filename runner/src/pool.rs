@@ -0,0 +1,286 @@
+//! Run puzzle parts concurrently, isolating each from the others.
+//!
+//! Each part runs on its own thread, so a panic in one part is caught and reported rather than
+//! tearing down the whole run. A fixed number of worker threads each supervise one part at a time,
+//! which bounds the concurrency.
+//!
+//! Rust threads cannot be killed. When a part exceeds its timeout, its supervisor reports it and
+//! moves on to the next part, leaving the timed-out thread detached. A part which never finishes
+//! therefore never holds up the others, at the cost of running alongside them; callers should exit
+//! the process once every part is reported.
+//!
+//! Optionally, each part's heap usage is profiled; see [`crate::profile`].
+
+use crate::{
+    days::PartFn,
+    profile::{self, AllocStats},
+};
+use std::{
+    any::Any,
+    collections::VecDeque,
+    fmt,
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::PathBuf,
+    sync::{mpsc, Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Parts run on threads with the same stack size as the main thread typically has,
+/// because some days recurse fairly deeply.
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// A single part of a single day, ready to run.
+pub struct Job {
+    pub day: u8,
+    pub part: u8,
+    pub run: PartFn,
+    pub input: PathBuf,
+}
+
+impl Job {
+    fn name(&self) -> String {
+        format!("day{:02}-part{}", self.day, self.part)
+    }
+}
+
+/// How a part's run ended.
+#[derive(Debug)]
+pub enum Outcome {
+    /// The part returned `Ok` after the given duration.
+    Pass(Duration),
+    /// The part returned an error, or could not be started.
+    Fail(String),
+    /// The part panicked with the given message.
+    Panic(String),
+    /// The part was still running when the timeout elapsed.
+    Timeout(Duration),
+}
+
+impl Outcome {
+    pub fn is_pass(&self) -> bool {
+        matches!(self, Outcome::Pass(_))
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Pass(duration) => write!(f, "pass ({:.3?})", duration),
+            Outcome::Fail(err) => write!(f, "FAIL: {}", err),
+            Outcome::Panic(msg) => write!(f, "PANIC: {}", msg),
+            Outcome::Timeout(duration) => write!(f, "TIMEOUT after {:?}", duration),
+        }
+    }
+}
+
+/// The outcome of a particular day and part.
+#[derive(Debug)]
+pub struct Report {
+    pub day: u8,
+    pub part: u8,
+    pub outcome: Outcome,
+    /// Heap usage of the part, if it was profiled and finished before its timeout.
+    pub alloc: Option<AllocStats>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day{:02} part{}: {}", self.day, self.part, self.outcome)?;
+        if let Some(alloc) = self.alloc {
+            write!(f, "; {}", alloc)?;
+        }
+        Ok(())
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "<non-string panic payload>".into()
+    }
+}
+
+/// Run a single job on its own thread, waiting at most `timeout` for it to complete.
+fn supervise(job: Job, timeout: Duration, profile_alloc: bool) -> (Outcome, Option<AllocStats>) {
+    let (tx, rx) = mpsc::channel();
+    let spawned = std::thread::Builder::new()
+        .name(job.name())
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            if profile_alloc {
+                profile::start();
+            }
+            let start = Instant::now();
+            let result = catch_unwind(AssertUnwindSafe(|| (job.run)(&job.input)));
+            let outcome = match result {
+                Ok(Ok(())) => Outcome::Pass(start.elapsed()),
+                Ok(Err(err)) => Outcome::Fail(format!("{:#}", err)),
+                Err(payload) => Outcome::Panic(panic_message(payload)),
+            };
+            let alloc = profile::finish();
+            // the supervisor may have given up on us; nothing to do about that
+            let _ = tx.send((outcome, alloc));
+        });
+    if let Err(err) = spawned {
+        return (Outcome::Fail(format!("spawning thread: {}", err)), None);
+    }
+
+    match rx.recv_timeout(timeout) {
+        Ok(outcome) => outcome,
+        Err(mpsc::RecvTimeoutError::Timeout) => (Outcome::Timeout(timeout), None),
+        Err(mpsc::RecvTimeoutError::Disconnected) => (
+            Outcome::Panic("part thread exited without reporting".into()),
+            None,
+        ),
+    }
+}
+
+/// Run all jobs on a pool of `workers` threads, each job limited to `timeout` of wall-clock time.
+///
+/// If `profile_alloc` is set, each report includes the heap usage of its part.
+///
+/// A worker whose job times out moves straight on to the next job, so timed-out jobs may keep
+/// running alongside up to `workers` others.
+///
+/// `on_report` is called on the calling thread as each job finishes, in completion order.
+/// All reports are also returned, in the order in which the jobs were supplied. Jobs which timed
+/// out may still be running when this returns.
+pub fn run<F>(
+    jobs: Vec<Job>,
+    workers: usize,
+    timeout: Duration,
+    profile_alloc: bool,
+    mut on_report: F,
+) -> Vec<Report>
+where
+    F: FnMut(&Report),
+{
+    let n_jobs = jobs.len();
+    let queue = Arc::new(Mutex::new(
+        jobs.into_iter().enumerate().collect::<VecDeque<_>>(),
+    ));
+    let (tx, rx) = mpsc::channel();
+
+    for _ in 0..workers.max(1).min(n_jobs) {
+        let queue = queue.clone();
+        let tx = tx.clone();
+        std::thread::spawn(move || loop {
+            let next = queue.lock().expect("queue lock poisoned").pop_front();
+            let (idx, job) = match next {
+                Some(next) => next,
+                None => break,
+            };
+            let (day, part) = (job.day, job.part);
+            let (outcome, alloc) = supervise(job, timeout, profile_alloc);
+            let report = Report {
+                day,
+                part,
+                outcome,
+                alloc,
+            };
+            if tx.send((idx, report)).is_err() {
+                break;
+            }
+        });
+    }
+    drop(tx);
+
+    let mut reports: Vec<_> = rx.iter().inspect(|(_, report)| on_report(report)).collect();
+    debug_assert_eq!(reports.len(), n_jobs);
+    reports.sort_by_key(|(idx, _)| *idx);
+    reports.into_iter().map(|(_, report)| report).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use color_eyre::eyre::bail;
+    use std::path::Path;
+
+    fn job(part: u8, run: PartFn) -> Job {
+        Job {
+            day: 0,
+            part,
+            run,
+            input: PathBuf::new(),
+        }
+    }
+
+    #[test]
+    fn test_outcomes_are_isolated() {
+        let jobs = vec![
+            job(1, |_: &Path| Ok(())),
+            job(2, |_: &Path| bail!("no solution found")),
+            job(3, |_: &Path| panic!("validity calculation failed")),
+            job(4, |_: &Path| {
+                // nothing ever sends on this channel, so this part never finishes
+                let (_tx, rx) = mpsc::channel::<()>();
+                rx.recv()?;
+                Ok(())
+            }),
+            job(5, |_: &Path| unimplemented!()),
+        ];
+
+        let reports = run(jobs, 2, Duration::from_secs(2), false, |_| {});
+        let parts: Vec<_> = reports.iter().map(|report| report.part).collect();
+        assert_eq!(parts, [1, 2, 3, 4, 5]);
+
+        assert!(reports[0].outcome.is_pass());
+        assert!(matches!(&reports[1].outcome, Outcome::Fail(msg) if msg == "no solution found"));
+        assert!(
+            matches!(&reports[2].outcome, Outcome::Panic(msg) if msg == "validity calculation failed")
+        );
+        assert!(matches!(reports[3].outcome, Outcome::Timeout(_)));
+        assert!(matches!(reports[4].outcome, Outcome::Panic(_)));
+    }
+
+    #[test]
+    fn test_hung_job_does_not_block_its_worker() {
+        let jobs = vec![
+            job(1, |_: &Path| {
+                // nothing ever sends on this channel, so this part never finishes
+                let (_tx, rx) = mpsc::channel::<()>();
+                rx.recv()?;
+                Ok(())
+            }),
+            job(2, |_: &Path| Ok(())),
+            job(3, |_: &Path| bail!("no solution found")),
+        ];
+
+        let reports = run(jobs, 1, Duration::from_secs(1), false, |_| {});
+        assert!(matches!(reports[0].outcome, Outcome::Timeout(_)));
+        assert!(reports[1].outcome.is_pass());
+        assert!(matches!(reports[2].outcome, Outcome::Fail(_)));
+    }
+
+    #[test]
+    fn test_alloc_profiling() {
+        let jobs = vec![
+            job(1, |_: &Path| Ok(())),
+            job(2, |_: &Path| {
+                let data = vec![0_u8; 1 << 20];
+                std::hint::black_box(data);
+                Ok(())
+            }),
+        ];
+
+        let reports = run(jobs, 2, Duration::from_secs(2), true, |_| {});
+        let quiet = reports[0].alloc.expect("part 1 was profiled");
+        let noisy = reports[1].alloc.expect("part 2 was profiled");
+        assert!(quiet.peak < 1 << 20);
+        assert!(noisy.peak >= 1 << 20);
+        assert!(noisy.bytes >= 1 << 20);
+
+        let reports = run(
+            vec![job(1, |_: &Path| Ok(()))],
+            1,
+            Duration::from_secs(2),
+            false,
+            |_| {},
+        );
+        assert!(reports[0].alloc.is_none());
+    }
+}
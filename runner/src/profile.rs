@@ -0,0 +1,168 @@
+//! Opt-in allocation profiling.
+//!
+//! The runner installs [`CountingAllocator`] as its global allocator. It forwards everything to the
+//! system allocator, and additionally counts the allocations made by any thread between its calls
+//! to [`start`] and [`finish`]. Each part runs on its own thread, so this attributes allocations to
+//! parts even when several run concurrently. Threads which are not profiling pay only for a
+//! thread-local lookup per allocation.
+//!
+//! Memory is attributed to the thread which allocates or frees it. A part which hands memory to
+//! other threads, or frees memory which it did not allocate, will report skewed figures.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    fmt,
+};
+
+/// Allocation statistics for a single part.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Number of allocations, including reallocations.
+    pub allocations: u64,
+    /// Total bytes requested over all allocations.
+    pub bytes: u64,
+    /// Greatest number of bytes live at once.
+    pub peak: u64,
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "peak heap {}, {} allocations, {} allocated",
+            Bytes(self.peak),
+            self.allocations,
+            Bytes(self.bytes),
+        )
+    }
+}
+
+/// Display a byte count with a binary unit prefix.
+struct Bytes(u64);
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+        if self.0 < 1024 {
+            return write!(f, "{} B", self.0);
+        }
+        let mut value = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+        write!(f, "{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct Counters {
+    stats: AllocStats,
+    /// Bytes allocated less bytes freed since profiling started.
+    ///
+    /// This goes negative if the thread frees memory it allocated before then.
+    live: i64,
+}
+
+thread_local! {
+    // `const` initialization means that accessing this never allocates,
+    // which would otherwise recurse into the allocator.
+    static COUNTERS: Cell<Option<Counters>> = const { Cell::new(None) };
+}
+
+fn record(allocated: usize, freed: usize) {
+    // this fails only during thread teardown, when there's nothing to record anyway
+    let _ = COUNTERS.try_with(|counters| {
+        if let Some(mut current) = counters.get() {
+            if allocated > 0 {
+                current.stats.allocations += 1;
+                current.stats.bytes += allocated as u64;
+            }
+            current.live += allocated as i64 - freed as i64;
+            current.stats.peak = current.stats.peak.max(current.live.max(0) as u64);
+            counters.set(Some(current));
+        }
+    });
+}
+
+/// Begin counting allocations made by the current thread.
+///
+/// This discards any counts from a previous call which was not followed by [`finish`].
+pub fn start() {
+    COUNTERS.with(|counters| counters.set(Some(Counters::default())));
+}
+
+/// Stop counting allocations made by the current thread, returning the counts since [`start`].
+///
+/// Returns `None` if the current thread was not counting.
+pub fn finish() -> Option<AllocStats> {
+    COUNTERS
+        .with(|counters| counters.take())
+        .map(|counters| counters.stats)
+}
+
+/// A global allocator which counts allocations for threads which have opted in with [`start`].
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_counts_only_between_start_and_finish() {
+        let before = vec![0_u8; 4096];
+        start();
+        let mut during = Vec::with_capacity(1 << 20);
+        during.push(1_u8);
+        drop(during);
+        drop(before);
+        let stats = finish().expect("profiling was started");
+        let _after = vec![0_u8; 4096];
+
+        assert_eq!(stats.allocations, 1);
+        assert_eq!(stats.bytes, 1 << 20);
+        assert_eq!(stats.peak, 1 << 20);
+        assert_eq!(finish(), None);
+    }
+
+    #[test]
+    fn test_bytes_display() {
+        assert_eq!(Bytes(1023).to_string(), "1023 B");
+        assert_eq!(Bytes(1536).to_string(), "1.5 KiB");
+        assert_eq!(Bytes(3 << 30).to_string(), "3.0 GiB");
+    }
+}
@@ -99,7 +99,7 @@ fn render_templates_into(
 
     // render templates
     let template_dir = current_dir.join("day-template");
-    for template in &["Cargo.toml", "src/lib.rs", "src/main.rs"] {
+    for template in &["Cargo.toml", "src/lib.rs", "src/main.rs", "src/generate.rs"] {
         let mut tt = TinyTemplate::new();
         let template_text = std::fs::read_to_string(template_dir.join(template))?;
         tt.add_template(template, &template_text)
@@ -0,0 +1,258 @@
+//! Axis-aligned boxes of lattice points in `D` dimensions.
+
+use crate::geometry::{interval::Interval, vector_n::InclusiveRange, VectorN};
+use std::{collections::BTreeSet, iter::FromIterator};
+
+/// The lattice points from `min` to `max` inclusive, along every axis.
+///
+/// A box which is empty along any axis contains no points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AaBox<const D: usize> {
+    pub min: VectorN<D>,
+    pub max: VectorN<D>,
+}
+
+impl<const D: usize> AaBox<D> {
+    pub fn new(min: VectorN<D>, max: VectorN<D>) -> Self {
+        AaBox { min, max }
+    }
+
+    pub fn empty() -> Self {
+        AaBox::new(VectorN([1; D]), VectorN([0; D]))
+    }
+
+    /// The box containing only `point`.
+    pub fn around(point: VectorN<D>) -> Self {
+        AaBox::new(point, point)
+    }
+
+    /// Build a box from its extent along each axis.
+    pub fn from_axes(axes: [Interval<i32>; D]) -> Self {
+        let mut aabox = AaBox::new(VectorN::default(), VectorN::default());
+        for (axis, interval) in axes.iter().enumerate() {
+            aabox.min[axis] = interval.lo;
+            aabox.max[axis] = interval.hi;
+        }
+        aabox
+    }
+
+    /// The extent of this box along `axis`.
+    pub fn axis(&self, axis: usize) -> Interval<i32> {
+        Interval::new(self.min[axis], self.max[axis])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..D).any(|axis| self.axis(axis).is_empty())
+    }
+
+    pub fn contains(&self, point: VectorN<D>) -> bool {
+        (0..D).all(|axis| self.axis(axis).contains(point[axis]))
+    }
+
+    /// The number of lattice points in this box.
+    pub fn volume(&self) -> i64 {
+        if self.is_empty() {
+            return 0;
+        }
+        // measure in `i64`, as a box may span more than `i32::MAX` along an axis
+        (0..D).fold(1, |volume, axis| {
+            volume * (self.max[axis] as i64 - self.min[axis] as i64 + 1)
+        })
+    }
+
+    /// The points in both boxes. The result may be empty.
+    pub fn intersect(&self, other: &Self) -> Self {
+        AaBox::new(
+            self.min.boundary_max(other.min),
+            self.max.boundary_min(other.max),
+        )
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersect(other).is_empty()
+    }
+
+    /// The smallest box containing both boxes.
+    pub fn hull(&self, other: &Self) -> Self {
+        match (self.is_empty(), other.is_empty()) {
+            (true, _) => *other,
+            (_, true) => *self,
+            _ => AaBox::new(
+                self.min.boundary_min(other.min),
+                self.max.boundary_max(other.max),
+            ),
+        }
+    }
+
+    /// Grow this box to include `point`.
+    pub fn include(&mut self, point: VectorN<D>) {
+        *self = self.hull(&AaBox::around(point));
+    }
+
+    /// Grow this box by `margin` in every direction; a negative margin shrinks it.
+    pub fn expand(&self, margin: i32) -> Self {
+        AaBox::new(self.min.map(|c| c - margin), self.max.map(|c| c + margin))
+    }
+
+    /// Split this box into the points whose `axis` coordinate is below `at`, and the rest.
+    pub fn split(&self, axis: usize, at: i32) -> (Self, Self) {
+        let (mut below, mut above) = (*self, *self);
+        let (low, high) = self.axis(axis).split(at);
+        below.min[axis] = low.lo;
+        below.max[axis] = low.hi;
+        above.min[axis] = high.lo;
+        above.max[axis] = high.hi;
+        (below, above)
+    }
+
+    /// The points in this box but not in `other`, as at most `2 * D` disjoint boxes.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        if !self.overlaps(other) {
+            return if self.is_empty() {
+                Vec::new()
+            } else {
+                vec![*self]
+            };
+        }
+        // peel off the slabs outside `other`, one axis at a time
+        let mut pieces = Vec::new();
+        let mut remaining = *self;
+        for axis in 0..D {
+            let (below, rest) = remaining.split(axis, other.min[axis]);
+            let (rest, above) = match other.max[axis].checked_add(1) {
+                Some(at) => rest.split(axis, at),
+                None => (rest, AaBox::empty()),
+            };
+            pieces.extend([below, above].iter().filter(|piece| !piece.is_empty()));
+            remaining = rest;
+        }
+        pieces
+    }
+
+    /// Iterate over the points of this box.
+    pub fn points(&self) -> InclusiveRange<D> {
+        VectorN::inclusive_range(self.min, self.max)
+    }
+}
+
+/// The number of lattice points covered by at least one of `boxes`.
+///
+/// This sweeps each axis in turn: the boundaries of the boxes along the axis divide it into
+/// slabs, and within each slab the boxes spanning it are measured along the remaining axes.
+pub fn union_volume<const D: usize>(boxes: &[AaBox<D>]) -> i64 {
+    let boxes: Vec<_> = boxes.iter().filter(|aabox| !aabox.is_empty()).collect();
+    sweep(&boxes, 0)
+}
+
+fn sweep<const D: usize>(boxes: &[&AaBox<D>], axis: usize) -> i64 {
+    if boxes.is_empty() {
+        return 0;
+    }
+    if axis == D {
+        return 1;
+    }
+
+    // each slab runs from one boundary up to just before the next
+    let boundaries: BTreeSet<i64> = boxes
+        .iter()
+        .flat_map(|aabox| {
+            let extent = aabox.axis(axis);
+            std::iter::once(extent.lo as i64).chain(std::iter::once(extent.hi as i64 + 1))
+        })
+        .collect();
+    let boundaries: Vec<_> = boundaries.into_iter().collect();
+
+    boundaries
+        .windows(2)
+        .map(|slab| {
+            let spanning: Vec<_> = boxes
+                .iter()
+                .copied()
+                .filter(|aabox| aabox.axis(axis).contains(slab[0] as i32))
+                .collect();
+            (slab[1] - slab[0]) * sweep(&spanning, axis + 1)
+        })
+        .sum()
+}
+
+impl<const D: usize> From<(VectorN<D>, VectorN<D>)> for AaBox<D> {
+    fn from((min, max): (VectorN<D>, VectorN<D>)) -> Self {
+        AaBox::new(min, max)
+    }
+}
+
+impl<const D: usize> FromIterator<VectorN<D>> for AaBox<D> {
+    /// The bounding box of some points. It is empty if there are none.
+    fn from_iter<T: IntoIterator<Item = VectorN<D>>>(iter: T) -> Self {
+        let mut iter = iter.into_iter();
+        let mut aabox = match iter.next() {
+            Some(first) => AaBox::around(first),
+            None => return AaBox::empty(),
+        };
+        for point in iter {
+            aabox.include(point);
+        }
+        aabox
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cube(min: [i32; 3], max: [i32; 3]) -> AaBox<3> {
+        AaBox::new(VectorN(min), VectorN(max))
+    }
+
+    #[test]
+    fn test_box_operations() {
+        let a = cube([0, 0, 0], [3, 3, 3]);
+        let b = cube([2, 2, 2], [5, 5, 5]);
+        assert_eq!(a.volume(), 64);
+        assert_eq!(a.intersect(&b), cube([2, 2, 2], [3, 3, 3]));
+        assert_eq!(a.hull(&b), cube([0, 0, 0], [5, 5, 5]));
+        assert!(a.intersect(&cube([4, 0, 0], [9, 9, 9])).is_empty());
+        assert_eq!(a.expand(1), cube([-1, -1, -1], [4, 4, 4]));
+        assert_eq!(a.points().count() as i64, a.volume());
+
+        let (below, above) = a.split(1, 1);
+        assert_eq!(below, cube([0, 0, 0], [3, 0, 3]));
+        assert_eq!(above, cube([0, 1, 0], [3, 3, 3]));
+
+        let pieces = a.subtract(&b);
+        assert_eq!(pieces.iter().map(AaBox::volume).sum::<i64>(), 64 - 8);
+        for point in a.points() {
+            let covering = pieces.iter().filter(|piece| piece.contains(point)).count();
+            assert_eq!(covering, if b.contains(point) { 0 } else { 1 });
+        }
+
+        let bounds: AaBox<3> = vec![VectorN([1, -2, 0]), VectorN([-1, 4, 2])]
+            .into_iter()
+            .collect();
+        assert_eq!(bounds, cube([-1, -2, 0], [1, 4, 2]));
+        assert_eq!(std::iter::empty().collect::<AaBox<3>>(), AaBox::empty());
+    }
+
+    #[test]
+    fn test_union_volume() {
+        let boxes = [
+            cube([0, 0, 0], [3, 3, 3]),
+            cube([2, 2, 2], [5, 5, 5]),
+            cube([1, 1, 1], [1, 1, 1]),
+            cube([-3, 0, 0], [-2, 0, 0]),
+        ];
+        assert_eq!(union_volume(&boxes), 64 + 64 - 8 + 2);
+        let naive = cube([-3, 0, 0], [5, 5, 5])
+            .points()
+            .filter(|&point| boxes.iter().any(|aabox| aabox.contains(point)))
+            .count();
+        assert_eq!(union_volume(&boxes), naive as i64);
+
+        // coordinates near the limits of `i32` don't overflow the sweep
+        let huge = cube([i32::MIN, 0, 0], [i32::MAX, 0, 0]);
+        assert_eq!(huge.volume(), 1 << 32);
+        assert_eq!(union_volume(&[huge]), 1 << 32);
+        let pieces = huge.subtract(&cube([0, 0, 0], [i32::MAX, 0, 0]));
+        assert_eq!(pieces, vec![cube([i32::MIN, 0, 0], [-1, 0, 0])]);
+    }
+}
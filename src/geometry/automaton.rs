@@ -0,0 +1,386 @@
+//! A cellular automaton engine over [`Map`].
+//!
+//! Each generation, every tile is replaced by the result of a rule applied to the tile and its
+//! neighborhood. The engine double-buffers two maps so that stepping never allocates, and it only
+//! re-evaluates tiles whose neighborhood changed in the previous generation.
+
+use super::{Map, Point};
+use bitvec::{bitvec, vec::BitVec};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
+/// The neighbors of a tile, as passed to an automaton's rule.
+#[derive(Clone)]
+pub struct Neighbors<'a, T> {
+    tiles: &'a [T],
+    indices: std::slice::Iter<'a, usize>,
+}
+
+impl<'a, T> Iterator for Neighbors<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.indices.next().map(|&idx| &self.tiles[idx])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Neighbors<'a, T> {}
+
+/// Why [`Automaton::run`] stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+    /// The map stopped changing at this generation.
+    FixedPoint { generation: usize },
+    /// The map at generation `start + period` repeats the map at generation `start`.
+    Cycle { start: usize, period: usize },
+    /// Neither a fixed point nor a cycle was found within the generation limit.
+    GenerationLimit,
+}
+
+/// A cellular automaton whose state is a [`Map`].
+///
+/// The neighborhood of each tile is computed once, from the initial map. Rules which need a
+/// neighborhood depending on the evolving state must include every tile they might inspect.
+pub struct Automaton<T, R> {
+    current: Map<T>,
+    /// Equal to `current` between steps; the rule's results are written here.
+    next: Map<T>,
+    rule: R,
+    /// The neighbors of tile `i` are `neighbors[neighbor_offsets[i]..neighbor_offsets[i + 1]]`.
+    neighbor_offsets: Vec<usize>,
+    neighbors: Vec<usize>,
+    /// The tiles with tile `i` in their neighborhood are
+    /// `dependents[dependent_offsets[i]..dependent_offsets[i + 1]]`.
+    dependent_offsets: Vec<usize>,
+    dependents: Vec<usize>,
+    /// Tiles to re-evaluate in the next step.
+    dirty: Vec<usize>,
+    is_dirty: BitVec,
+    /// Tiles which changed in the most recent step.
+    changed: Vec<usize>,
+    /// Order-independent hash of the current map, maintained incrementally.
+    hash: u64,
+    generation: usize,
+}
+
+fn tile_hash<T: Hash>(idx: usize, tile: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    idx.hash(&mut hasher);
+    tile.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<T, R> Automaton<T, R>
+where
+    T: Clone + PartialEq + Hash,
+    R: Fn(&T, Neighbors<'_, T>) -> T,
+{
+    /// Create an automaton from an initial map.
+    ///
+    /// `neighborhood` lists the points which the rule inspects for the tile at a given point.
+    /// Out-of-bounds points are ignored.
+    pub fn new<N, I>(map: Map<T>, neighborhood: N, rule: R) -> Self
+    where
+        N: Fn(&Map<T>, Point) -> I,
+        I: IntoIterator<Item = Point>,
+    {
+        let len = map.tiles().len();
+
+        let mut neighbor_offsets = Vec::with_capacity(len + 1);
+        let mut neighbors = Vec::new();
+        let mut n_dependents = vec![0; len];
+        neighbor_offsets.push(0);
+        for point in map.points() {
+            for neighbor in neighborhood(&map, point) {
+                if map.in_bounds(neighbor) {
+                    let idx = map.point2index(neighbor.x as usize, neighbor.y as usize);
+                    neighbors.push(idx);
+                    n_dependents[idx] += 1;
+                }
+            }
+            neighbor_offsets.push(neighbors.len());
+        }
+
+        let mut dependent_offsets = Vec::with_capacity(len + 1);
+        dependent_offsets.push(0);
+        for n in n_dependents {
+            dependent_offsets.push(dependent_offsets[dependent_offsets.len() - 1] + n);
+        }
+        let mut fill = dependent_offsets.clone();
+        let mut dependents = vec![0; neighbors.len()];
+        for idx in 0..len {
+            for &neighbor in &neighbors[neighbor_offsets[idx]..neighbor_offsets[idx + 1]] {
+                dependents[fill[neighbor]] = idx;
+                fill[neighbor] += 1;
+            }
+        }
+
+        let hash = map
+            .tiles()
+            .iter()
+            .enumerate()
+            .fold(0_u64, |hash, (idx, tile)| {
+                hash.wrapping_add(tile_hash(idx, tile))
+            });
+
+        Automaton {
+            next: map.clone(),
+            current: map,
+            rule,
+            neighbor_offsets,
+            neighbors,
+            dependent_offsets,
+            dependents,
+            dirty: (0..len).collect(),
+            is_dirty: bitvec![1; len],
+            changed: Vec::with_capacity(len),
+            hash,
+            generation: 0,
+        }
+    }
+
+    /// The current state.
+    pub fn map(&self) -> &Map<T> {
+        &self.current
+    }
+
+    pub fn into_map(self) -> Map<T> {
+        self.current
+    }
+
+    /// The number of steps which have changed the map.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// The points which changed in the most recent step.
+    pub fn changed(&self) -> impl '_ + Iterator<Item = Point> {
+        let width = self.current.width();
+        self.changed
+            .iter()
+            .map(move |&idx| Point::from((idx % width, idx / width)))
+    }
+
+    /// Advance by one generation.
+    ///
+    /// Returns `false` and leaves the generation count alone if nothing changed: the map is at a
+    /// fixed point.
+    pub fn step(&mut self) -> bool {
+        let Automaton {
+            current,
+            next,
+            rule,
+            neighbor_offsets,
+            neighbors,
+            dependent_offsets,
+            dependents,
+            dirty,
+            is_dirty,
+            changed,
+            hash,
+            generation,
+        } = self;
+
+        changed.clear();
+        {
+            let tiles = current.tiles();
+            let next_tiles = next.tiles_mut();
+            for &idx in dirty.iter() {
+                is_dirty.set(idx, false);
+                let tile = rule(
+                    &tiles[idx],
+                    Neighbors {
+                        tiles,
+                        indices: neighbors[neighbor_offsets[idx]..neighbor_offsets[idx + 1]].iter(),
+                    },
+                );
+                if tile != tiles[idx] {
+                    *hash = hash
+                        .wrapping_sub(tile_hash(idx, &tiles[idx]))
+                        .wrapping_add(tile_hash(idx, &tile));
+                    next_tiles[idx] = tile;
+                    changed.push(idx);
+                }
+            }
+        }
+        dirty.clear();
+
+        if changed.is_empty() {
+            return false;
+        }
+
+        std::mem::swap(current, next);
+        let tiles = current.tiles();
+        let next_tiles = next.tiles_mut();
+        for &idx in changed.iter() {
+            next_tiles[idx] = tiles[idx].clone();
+            let affected = std::iter::once(&idx)
+                .chain(&dependents[dependent_offsets[idx]..dependent_offsets[idx + 1]]);
+            for &affected in affected {
+                if !is_dirty[affected] {
+                    is_dirty.set(affected, true);
+                    dirty.push(affected);
+                }
+            }
+        }
+        *generation += 1;
+        true
+    }
+
+    /// Step until a fixed point or cycle, or until `max_generations` steps have been taken.
+    pub fn run(&mut self, max_generations: usize) -> Termination {
+        self.run_with(max_generations, |_| {})
+    }
+
+    /// Compute the map `generations` steps after `from`, by evaluating every tile each step.
+    ///
+    /// This ignores the automaton's own state, so it is only used to confirm suspected cycles.
+    fn replay(&self, from: &Map<T>, generations: usize) -> Map<T> {
+        let mut current = from.clone();
+        let mut next = from.clone();
+        for _ in 0..generations {
+            let tiles = current.tiles();
+            for (idx, tile) in next.tiles_mut().iter_mut().enumerate() {
+                *tile = (self.rule)(
+                    &tiles[idx],
+                    Neighbors {
+                        tiles,
+                        indices: self.neighbors
+                            [self.neighbor_offsets[idx]..self.neighbor_offsets[idx + 1]]
+                            .iter(),
+                    },
+                );
+            }
+            std::mem::swap(&mut current, &mut next);
+        }
+        current
+    }
+
+    /// As [`run`][Self::run], calling `on_generation` after each step which changes the map.
+    ///
+    /// Cycles are found by hashing each generation's map. Only the hashes and the map at the start
+    /// of the run are kept; a hash match is confirmed by replaying from that map to the earlier
+    /// generation and comparing it exactly, so a collision never reports a false cycle.
+    pub fn run_with<F>(&mut self, max_generations: usize, mut on_generation: F) -> Termination
+    where
+        F: FnMut(&Self),
+    {
+        let first = self.generation;
+        let checkpoint = self.current.clone();
+        let mut seen: HashMap<u64, Vec<usize>> = HashMap::new();
+        seen.entry(self.hash).or_default().push(first);
+        for _ in 0..max_generations {
+            if !self.step() {
+                return Termination::FixedPoint {
+                    generation: self.generation,
+                };
+            }
+            on_generation(self);
+            let candidates = seen.entry(self.hash).or_default();
+            if let Some(&start) = candidates
+                .iter()
+                .find(|&&generation| self.replay(&checkpoint, generation - first) == self.current)
+            {
+                return Termination::Cycle {
+                    start,
+                    period: self.generation - start,
+                };
+            }
+            candidates.push(self.generation);
+        }
+        Termination::GenerationLimit
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::tile::Bool;
+    use std::convert::TryFrom;
+
+    fn life(map: Map<Bool>) -> Automaton<Bool, impl Fn(&Bool, Neighbors<'_, Bool>) -> Bool> {
+        Automaton::new(
+            map,
+            |map: &Map<Bool>, point| map.adjacencies(point).collect::<Vec<_>>(),
+            |&tile: &Bool, neighbors: Neighbors<'_, Bool>| {
+                let live = neighbors.filter(|&&neighbor| neighbor == true).count();
+                Bool::from(live == 3 || (tile == true && live == 2))
+            },
+        )
+    }
+
+    fn parse(map: &str) -> Map<Bool> {
+        <Map<Bool> as TryFrom<&str>>::try_from(map.trim()).unwrap()
+    }
+
+    #[test]
+    fn test_still_life() {
+        let block = parse("....\n.##.\n.##.\n....");
+        let mut automaton = life(block.clone());
+        assert_eq!(automaton.run(10), Termination::FixedPoint { generation: 0 });
+        assert!(automaton.map() == &block);
+    }
+
+    #[test]
+    fn test_blinker() {
+        let vertical = parse(".....\n..#..\n..#..\n..#..\n.....");
+        let horizontal = parse(".....\n.....\n.###.\n.....\n.....");
+        let mut automaton = life(vertical);
+
+        assert!(automaton.step());
+        assert!(automaton.map() == &horizontal);
+        assert_eq!(automaton.changed().count(), 4);
+
+        let mut traced = Vec::new();
+        let termination = automaton.run_with(10, |automaton| traced.push(automaton.generation()));
+        assert_eq!(
+            termination,
+            Termination::Cycle {
+                start: 1,
+                period: 2
+            }
+        );
+        assert_eq!(traced, vec![2, 3]);
+        assert!(automaton.map() == &horizontal);
+    }
+
+    #[test]
+    fn test_replay_matches_incremental_steps() {
+        let glider = parse(".#...\n..#..\n###..\n.....\n.....");
+        let mut automaton = life(glider.clone());
+        for _ in 0..4 {
+            automaton.step();
+        }
+        assert!(automaton.replay(&glider, 4) == *automaton.map());
+        assert!(automaton.replay(&glider, 0) == glider);
+    }
+
+    #[test]
+    fn test_generation_limit() {
+        let glider = parse(
+            "
+.#......
+..#.....
+###.....
+........
+........
+........
+........
+........
+",
+        );
+        let mut automaton = life(glider);
+        assert_eq!(automaton.run(8), Termination::GenerationLimit);
+        assert_eq!(automaton.generation(), 8);
+        assert_eq!(
+            automaton.map().iter().filter(|&&tile| tile == true).count(),
+            5
+        );
+    }
+}
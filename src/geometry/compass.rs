@@ -0,0 +1,208 @@
+use crate::geometry::Direction;
+use std::{convert::TryFrom, fmt, str::FromStr};
+
+/// One of the eight compass directions: the orthogonals, and the diagonals between them.
+///
+/// Variants are declared clockwise from north, and north is `+y`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Compass8 {
+    #[default]
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("turn of {0} degrees is not a supported angle")]
+    Angle(i32),
+    #[error("unknown compass direction {0:?}")]
+    Unknown(String),
+    #[error("{0} is not an orthogonal direction")]
+    Diagonal(Compass8),
+}
+
+impl Compass8 {
+    /// Every direction, clockwise from north.
+    pub const ALL: [Compass8; 8] = [
+        Compass8::N,
+        Compass8::NE,
+        Compass8::E,
+        Compass8::SE,
+        Compass8::S,
+        Compass8::SW,
+        Compass8::W,
+        Compass8::NW,
+    ];
+
+    pub fn iter() -> impl Iterator<Item = Compass8> {
+        Compass8::ALL.iter().copied()
+    }
+
+    /// `(dx, dy)`, for `E` is `+x` and `N` is `+y`
+    pub fn deltas(self) -> (i32, i32) {
+        use Compass8::*;
+        match self {
+            N => (0, 1),
+            NE => (1, 1),
+            E => (1, 0),
+            SE => (1, -1),
+            S => (0, -1),
+            SW => (-1, -1),
+            W => (-1, 0),
+            NW => (-1, 1),
+        }
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as u8 % 2 == 1
+    }
+
+    /// The bearing of this direction in degrees, clockwise from north.
+    pub fn bearing(self) -> i32 {
+        self as i32 * 45
+    }
+
+    /// Turn clockwise by `steps` eighths of a turn; negative values turn counterclockwise.
+    pub fn rotate(self, steps: i32) -> Compass8 {
+        Compass8::ALL[(self as i32 + steps).rem_euclid(8) as usize]
+    }
+
+    /// Turn clockwise by 45 degrees.
+    pub fn turn_right(self) -> Compass8 {
+        self.rotate(1)
+    }
+
+    /// Turn counterclockwise by 45 degrees.
+    pub fn turn_left(self) -> Compass8 {
+        self.rotate(-1)
+    }
+
+    pub fn reverse(self) -> Compass8 {
+        self.rotate(4)
+    }
+
+    /// Turn clockwise by `degrees`; negative values turn counterclockwise.
+    ///
+    /// The angle must be a multiple of 45 degrees. It may exceed a full turn.
+    ///
+    /// ```
+    /// # use aoc2020::geometry::Compass8;
+    /// assert_eq!(Compass8::E.turn(-90), Ok(Compass8::N));
+    /// assert_eq!(Compass8::E.turn(405), Ok(Compass8::SE));
+    /// assert!(Compass8::E.turn(30).is_err());
+    /// ```
+    pub fn turn(self, degrees: i32) -> Result<Compass8, Error> {
+        if degrees % 45 != 0 {
+            return Err(Error::Angle(degrees));
+        }
+        Ok(self.rotate(degrees / 45))
+    }
+
+    /// The direction at `degrees` clockwise from north.
+    pub fn from_bearing(degrees: i32) -> Result<Compass8, Error> {
+        Compass8::N.turn(degrees)
+    }
+}
+
+impl From<Direction> for Compass8 {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Up => Compass8::N,
+            Direction::Right => Compass8::E,
+            Direction::Down => Compass8::S,
+            Direction::Left => Compass8::W,
+        }
+    }
+}
+
+impl TryFrom<Compass8> for Direction {
+    type Error = Error;
+
+    fn try_from(compass: Compass8) -> Result<Self, Self::Error> {
+        match compass {
+            Compass8::N => Ok(Direction::Up),
+            Compass8::E => Ok(Direction::Right),
+            Compass8::S => Ok(Direction::Down),
+            Compass8::W => Ok(Direction::Left),
+            diagonal => Err(Error::Diagonal(diagonal)),
+        }
+    }
+}
+
+impl FromStr for Compass8 {
+    type Err = Error;
+
+    /// Parse a compass direction like `N` or `SW`, or a screen direction like `U` or `DL`.
+    ///
+    /// Parsing is case-insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Compass8::*;
+        Ok(match s.to_ascii_uppercase().as_str() {
+            "N" | "U" => N,
+            "NE" | "UR" => NE,
+            "E" | "R" => E,
+            "SE" | "DR" => SE,
+            "S" | "D" => S,
+            "SW" | "DL" => SW,
+            "W" | "L" => W,
+            "NW" | "UL" => NW,
+            _ => return Err(Error::Unknown(s.to_string())),
+        })
+    }
+}
+
+impl fmt::Display for Compass8 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::Point;
+
+    #[test]
+    fn test_turns_and_deltas() {
+        for compass in Compass8::iter() {
+            assert_eq!(compass.turn_right().turn_left(), compass);
+            assert_eq!(compass.turn(360), Ok(compass));
+            assert_eq!(Compass8::from_bearing(compass.bearing()), Ok(compass));
+            assert_eq!(
+                Point::new(0, 0) + compass + compass.reverse(),
+                Point::new(0, 0)
+            );
+            let (dx, dy) = compass.deltas();
+            assert_eq!(compass.is_diagonal(), dx != 0 && dy != 0);
+            assert_eq!(compass.to_string().parse(), Ok(compass));
+        }
+        assert_eq!(Compass8::NW.turn(-135), Ok(Compass8::S));
+        assert_eq!(Compass8::N.turn(-1), Err(Error::Angle(-1)));
+    }
+
+    #[test]
+    fn test_direction_conversions() {
+        for direction in Direction::iter() {
+            let compass = Compass8::from(direction);
+            assert_eq!(compass.deltas(), direction.deltas());
+            assert_eq!(Direction::try_from(compass), Ok(direction));
+            assert_eq!(
+                Compass8::from(direction.turn_right()),
+                compass.turn(90).unwrap()
+            );
+        }
+        assert_eq!("dl".parse(), Ok(Compass8::SW));
+        assert_eq!("R".parse(), Ok(Compass8::E));
+        assert!("NNE".parse::<Compass8>().is_err());
+        assert_eq!(
+            Direction::try_from(Compass8::SE),
+            Err(Error::Diagonal(Compass8::SE))
+        );
+    }
+}
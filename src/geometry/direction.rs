@@ -1,7 +1,8 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Direction {
     Right,
     Left,
+    #[default]
     Up,
     Down,
 }
@@ -64,9 +65,3 @@ impl Direction {
             .copied()
     }
 }
-
-impl Default for Direction {
-    fn default() -> Self {
-        Direction::Up
-    }
-}
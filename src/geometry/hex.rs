@@ -0,0 +1,724 @@
+//! Hexagonal grids.
+//!
+//! Hexes are addressed by axial coordinates `(q, r)`; the implicit third cube coordinate is
+//! `s = -q - r`. See the [reference](https://www.redblobgames.com/grids/hexagons/) for the
+//! conventions used here: `r` increases downward, `q` increases to the east.
+//!
+//! Axial coordinates don't depend on whether hexes are drawn pointy side up or flat side up;
+//! only names, offset coordinates, and rendering do. Those take a [`Layout`].
+
+use crate::geometry::{
+    neighbors::OffsetIter,
+    point::{PointRange, PointTrait},
+    tile::DisplayWidth,
+    Map, Point, SparseMap,
+};
+use std::{
+    fmt,
+    hash::Hash,
+    ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub},
+};
+
+/// How hexes are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Layout {
+    /// Pointy side up: hexes form horizontal rows, and east and west are neighbors.
+    #[default]
+    Pointy,
+    /// Flat side up: hexes form vertical columns, and north and south are neighbors.
+    Flat,
+}
+
+impl Layout {
+    /// The offset coordinate system natural to this layout.
+    pub fn offset(self) -> Offset {
+        match self {
+            Layout::Pointy => Offset::OddR,
+            Layout::Flat => Offset::OddQ,
+        }
+    }
+
+    /// Compass names of the six directions in this layout, in the order of [`HexDirection::ALL`].
+    fn names(self) -> [&'static str; 6] {
+        match self {
+            Layout::Pointy => ["e", "se", "sw", "w", "nw", "ne"],
+            Layout::Flat => ["se", "s", "sw", "nw", "n", "ne"],
+        }
+    }
+
+    /// The compass name of a direction in this layout, like `"se"`.
+    pub fn direction_name(self, direction: HexDirection) -> &'static str {
+        self.names()[direction as usize]
+    }
+
+    /// Parse a direction by its compass name from the start of `s`.
+    ///
+    /// Directions are often concatenated without delimiters, so this returns the remainder.
+    /// Two-letter names are preferred over one-letter names.
+    pub fn parse_direction(self, s: &str) -> Option<(HexDirection, &str)> {
+        let names = self.names();
+        [2, 1].iter().find_map(|&len| {
+            let name = s.get(..len)?;
+            let idx = names.iter().position(|&candidate| candidate == name)?;
+            Some((HexDirection::ALL[idx], &s[len..]))
+        })
+    }
+
+    /// The center of a hex of unit size, in pixel coordinates with `y` increasing downward.
+    ///
+    /// The size is the distance from the center of a hex to any of its corners.
+    pub fn to_pixel(self, hex: Hex) -> (f64, f64) {
+        let sqrt3 = 3_f64.sqrt();
+        let (q, r) = (hex.q as f64, hex.r as f64);
+        match self {
+            Layout::Pointy => (sqrt3 * (q + r / 2.0), 1.5 * r),
+            Layout::Flat => (1.5 * q, sqrt3 * (r + q / 2.0)),
+        }
+    }
+}
+
+/// A conventional rectangular addressing of hexes, as `(column, row)`.
+///
+/// Every other row (or column) is shoved by half a hex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Offset {
+    /// Pointy layout; odd rows are shoved right.
+    OddR,
+    /// Pointy layout; even rows are shoved right.
+    EvenR,
+    /// Flat layout; odd columns are shoved down.
+    OddQ,
+    /// Flat layout; even columns are shoved down.
+    EvenQ,
+}
+
+/// The six directions from a hex to its neighbors.
+///
+/// They are named for the pointy layout. In the flat layout, see [`Layout::direction_name`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HexDirection {
+    East,
+    Southeast,
+    Southwest,
+    West,
+    Northwest,
+    Northeast,
+}
+
+impl HexDirection {
+    /// Every direction, clockwise from east.
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::East,
+        HexDirection::Southeast,
+        HexDirection::Southwest,
+        HexDirection::West,
+        HexDirection::Northwest,
+        HexDirection::Northeast,
+    ];
+
+    pub fn iter() -> impl Iterator<Item = HexDirection> {
+        HexDirection::ALL.iter().copied()
+    }
+
+    /// The offset to the neighbor in this direction.
+    pub fn deltas(self) -> Hex {
+        use HexDirection::*;
+        match self {
+            East => Hex::new(1, 0),
+            Southeast => Hex::new(0, 1),
+            Southwest => Hex::new(-1, 1),
+            West => Hex::new(-1, 0),
+            Northwest => Hex::new(0, -1),
+            Northeast => Hex::new(1, -1),
+        }
+    }
+
+    /// Turn clockwise by `sixths` sixths of a turn; negative values turn counterclockwise.
+    pub fn turn(self, sixths: i32) -> HexDirection {
+        HexDirection::ALL[(self as i32 + sixths).rem_euclid(6) as usize]
+    }
+
+    pub fn reverse(self) -> HexDirection {
+        self.turn(3)
+    }
+}
+
+/// Axial hex coordinates.
+///
+/// Constraint: `q + r + s == 0`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    pub const ORIGIN: Hex = Hex::new(0, 0);
+
+    pub const fn new(q: i32, r: i32) -> Hex {
+        Hex { q, r }
+    }
+
+    /// The implicit third cube coordinate.
+    pub fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// The number of steps from the origin to this hex.
+    pub fn length(self) -> i32 {
+        (self.q.abs() + self.r.abs() + self.s().abs()) / 2
+    }
+
+    /// The number of steps between two hexes.
+    pub fn distance(self, other: Hex) -> i32 {
+        (self - other).length()
+    }
+
+    pub fn neighbor(self, direction: HexDirection) -> Hex {
+        self + direction
+    }
+
+    /// Rotate this hex clockwise by a sixth of a turn around the origin.
+    ///
+    /// ```
+    /// # use aoc2020::geometry::hex::{Hex, HexDirection};
+    /// let east = Hex::ORIGIN + HexDirection::East;
+    /// assert_eq!(east.rotate_right(), Hex::ORIGIN + HexDirection::Southeast);
+    /// ```
+    pub fn rotate_right(self) -> Hex {
+        Hex::new(-self.r, -self.s())
+    }
+
+    /// Rotate this hex counterclockwise by a sixth of a turn around the origin.
+    pub fn rotate_left(self) -> Hex {
+        Hex::new(-self.s(), -self.q)
+    }
+
+    /// Rotate this hex clockwise around `center` by `sixths` sixths of a turn; negative values
+    /// rotate counterclockwise.
+    pub fn rotate_about(self, center: Hex, sixths: i32) -> Hex {
+        let mut relative = self - center;
+        for _ in 0..sixths.rem_euclid(6) {
+            relative = relative.rotate_right();
+        }
+        center + relative
+    }
+
+    /// Convert to offset coordinates `(column, row)`.
+    pub fn to_offset(self, offset: Offset) -> Point {
+        let Hex { q, r } = self;
+        match offset {
+            Offset::OddR => Point::new(q + (r - (r & 1)) / 2, r),
+            Offset::EvenR => Point::new(q + (r + (r & 1)) / 2, r),
+            Offset::OddQ => Point::new(q, r + (q - (q & 1)) / 2),
+            Offset::EvenQ => Point::new(q, r + (q + (q & 1)) / 2),
+        }
+    }
+
+    /// Convert from offset coordinates `(column, row)`.
+    pub fn from_offset(point: Point, offset: Offset) -> Hex {
+        let Point { x: col, y: row } = point;
+        match offset {
+            Offset::OddR => Hex::new(col - (row - (row & 1)) / 2, row),
+            Offset::EvenR => Hex::new(col - (row + (row & 1)) / 2, row),
+            Offset::OddQ => Hex::new(col, row - (col - (col & 1)) / 2),
+            Offset::EvenQ => Hex::new(col, row - (col + (col & 1)) / 2),
+        }
+    }
+
+    /// Iterate over the `6 * radius` hexes at exactly `radius` steps from this one.
+    ///
+    /// The ring starts at the hex `radius` steps east, and proceeds clockwise.
+    /// A ring of radius 0 is just this hex.
+    pub fn ring(self, radius: u32) -> impl Iterator<Item = Hex> {
+        let radius = radius as i32;
+        let start = self + HexDirection::East.deltas() * radius;
+        let sides = if radius == 0 { 1 } else { 6 };
+        (0..sides)
+            .flat_map(move |side| (0..radius.max(1)).map(move |step| (side, step)))
+            .scan(start, |hex, (side, _)| {
+                let current = *hex;
+                *hex += HexDirection::ALL[(side + 2) % 6];
+                Some(current)
+            })
+    }
+
+    /// Iterate over the `1 + 3 * radius * (radius + 1)` hexes within `radius` steps of this one.
+    ///
+    /// Hexes are produced ring by ring, from the center outward.
+    pub fn spiral(self, radius: u32) -> impl Iterator<Item = Hex> {
+        (0..=radius).flat_map(move |radius| self.ring(radius))
+    }
+
+    /// The hexes on a straight line from this hex to `other`, inclusive.
+    ///
+    /// This always returns `distance + 1` items, each adjacent to the last. Where the line passes
+    /// exactly between two hexes, it is nudged consistently to one side.
+    pub fn line_to(self, other: Hex) -> impl Iterator<Item = Hex> {
+        const NUDGE: f64 = 1e-6;
+        let n = self.distance(other);
+        let lerp = move |a: i32, b: i32, t: f64| a as f64 + (b - a) as f64 * t;
+        (0..=n).map(move |step| {
+            let t = if n == 0 { 0.0 } else { step as f64 / n as f64 };
+            Hex::round(
+                lerp(self.q, other.q, t) + NUDGE,
+                lerp(self.r, other.r, t) + NUDGE,
+                lerp(self.s(), other.s(), t) - 2.0 * NUDGE,
+            )
+        })
+    }
+
+    /// Round fractional cube coordinates to the nearest hex.
+    pub fn round(q: f64, r: f64, s: f64) -> Hex {
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        // the component which rounded furthest is the least trustworthy; recompute it
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+        Hex::new(rq as i32, rr as i32)
+    }
+}
+
+impl From<Point> for Hex {
+    /// Interpret a point as axial coordinates: `x` is `q`, and `y` is `r`.
+    fn from(point: Point) -> Self {
+        Hex::new(point.x, point.y)
+    }
+}
+
+impl From<Hex> for Point {
+    fn from(hex: Hex) -> Self {
+        Point::new(hex.q, hex.r)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, other: Hex) {
+        self.q += other.q;
+        self.r += other.r;
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(mut self, other: Hex) -> Hex {
+        self += other;
+        self
+    }
+}
+
+impl AddAssign<HexDirection> for Hex {
+    fn add_assign(&mut self, direction: HexDirection) {
+        *self += direction.deltas();
+    }
+}
+
+impl Add<HexDirection> for Hex {
+    type Output = Hex;
+
+    fn add(mut self, direction: HexDirection) -> Hex {
+        self += direction;
+        self
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, other: Hex) -> Hex {
+        Hex::new(self.q - other.q, self.r - other.r)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, scalar: i32) -> Hex {
+        Hex::new(self.q * scalar, self.r * scalar)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+
+    fn neg(self) -> Hex {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+/// Iterator over the hexes of an inclusive range of axial coordinates, row by row.
+#[derive(Debug, Clone)]
+pub struct HexRange(PointRange);
+
+impl Iterator for HexRange {
+    type Item = Hex;
+
+    fn next(&mut self) -> Option<Hex> {
+        self.0.next().map(Hex::from)
+    }
+}
+
+impl PointTrait for Hex {
+    type N = i32;
+
+    /// The number of steps from the origin to this hex.
+    fn manhattan(self) -> Self::N {
+        self.length()
+    }
+
+    fn decr(self) -> Self {
+        Hex::new(self.q - 1, self.r - 1)
+    }
+
+    fn incr(self) -> Self {
+        Hex::new(self.q + 1, self.r + 1)
+    }
+
+    type InclusiveRange = HexRange;
+    type Adjacent = OffsetIter<'static, Hex, HexDirection>;
+
+    /// The range is a parallelogram of axial coordinates.
+    fn inclusive_range(min: Self, max: Self) -> Self::InclusiveRange {
+        HexRange(PointRange::new(min.into(), max.into()))
+    }
+
+    /// A hex has six neighbors, not the eight of a square grid.
+    fn adjacent(self) -> Self::Adjacent {
+        OffsetIter::new(self, &HexDirection::ALL)
+    }
+
+    fn boundary_min(self, other: Self) -> Self {
+        Hex::new(self.q.min(other.q), self.r.min(other.r))
+    }
+
+    fn boundary_max(self, other: Self) -> Self {
+        Hex::new(self.q.max(other.q), self.r.max(other.r))
+    }
+
+    fn volume<T>(self) -> T
+    where
+        T: From<Self::N> + Mul<Output = T>,
+    {
+        let q: T = self.q.abs().into();
+        let r: T = self.r.abs().into();
+        q * r
+    }
+}
+
+/// A dense, rectangular grid of hexes.
+///
+/// Tiles are stored by the offset coordinates natural to the layout, so that rows (or columns,
+/// for the flat layout) are shoved alternately. The tile at `(0, 0)` of the underlying map is the
+/// top left hex, at offset coordinates `origin`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct HexMap<T> {
+    layout: Layout,
+    origin: Point,
+    tiles: Map<T>,
+}
+
+impl<T> HexMap<T> {
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// The number of columns, in offset coordinates.
+    pub fn width(&self) -> usize {
+        self.tiles.width()
+    }
+
+    /// The number of rows, in offset coordinates.
+    pub fn height(&self) -> usize {
+        self.tiles.height()
+    }
+
+    /// The offset coordinates of the top left hex.
+    pub fn origin(&self) -> Point {
+        self.origin
+    }
+
+    /// The underlying map, indexed by offset coordinates relative to the origin.
+    ///
+    /// Note that its `y` axis increases downward. This is suitable for tools which expect a square grid, like
+    /// [`Automaton`][crate::geometry::Automaton], with [`offset_adjacencies`][Self::offset_adjacencies]
+    /// as the neighborhood.
+    pub fn tiles(&self) -> &Map<T> {
+        &self.tiles
+    }
+
+    pub fn into_tiles(self) -> Map<T> {
+        self.tiles
+    }
+
+    /// Interpret a map indexed by offset coordinates relative to `origin` as a hex map.
+    pub fn from_tiles(layout: Layout, origin: Point, tiles: Map<T>) -> Self {
+        HexMap {
+            layout,
+            origin,
+            tiles,
+        }
+    }
+
+    /// The position of a hex in the underlying map.
+    fn hex2point(&self, hex: Hex) -> Point {
+        hex.to_offset(self.layout.offset()) - self.origin
+    }
+
+    fn point2hex(&self, point: Point) -> Hex {
+        Hex::from_offset(point + self.origin, self.layout.offset())
+    }
+
+    pub fn contains(&self, hex: Hex) -> bool {
+        self.tiles.in_bounds(self.hex2point(hex))
+    }
+
+    pub fn get(&self, hex: Hex) -> Option<&T> {
+        let point = self.hex2point(hex);
+        self.tiles.in_bounds(point).then(|| &self.tiles[point])
+    }
+
+    pub fn get_mut(&mut self, hex: Hex) -> Option<&mut T> {
+        let point = self.hex2point(hex);
+        if self.tiles.in_bounds(point) {
+            Some(&mut self.tiles[point])
+        } else {
+            None
+        }
+    }
+
+    /// Iterate over the hexes of this map, row by row in offset coordinates.
+    pub fn hexes(&self) -> impl '_ + Iterator<Item = Hex> {
+        self.tiles.points().map(move |point| self.point2hex(point))
+    }
+
+    /// Return an iterator of the hexes adjacent to the given hex which lie within this map.
+    pub fn adjacencies(&self, hex: Hex) -> impl '_ + Iterator<Item = Hex> {
+        hex.adjacent().filter(move |&hex| self.contains(hex))
+    }
+
+    /// The positions in the underlying map of the hexes adjacent to the hex at `point`.
+    ///
+    /// Points may lie outside the map.
+    pub fn offset_adjacencies(&self, point: Point) -> impl '_ + Iterator<Item = Point> {
+        self.point2hex(point)
+            .adjacent()
+            .map(move |hex| self.hex2point(hex))
+    }
+}
+
+impl<T: Clone + Default> HexMap<T> {
+    /// Create a map of default tiles with the given dimensions in offset coordinates.
+    ///
+    /// The top left hex is the origin.
+    pub fn new(layout: Layout, width: usize, height: usize) -> Self {
+        HexMap::from_tiles(layout, Point::default(), Map::new(width, height))
+    }
+
+    /// Render the bounding box, in offset coordinates, of a sparse hex map into a dense one.
+    ///
+    /// Hexes keep their coordinates. Unwritten hexes hold the default tile.
+    pub fn from_sparse(layout: Layout, sparse: &SparseMap<Hex, T>) -> Self {
+        let offset = layout.offset();
+        let mut points = sparse.points().map(|hex| hex.to_offset(offset));
+        let first = match points.next() {
+            Some(first) => first,
+            None => return HexMap::new(layout, 0, 0),
+        };
+        let (min, max) = points.fold((first, first), |(min, max), point| {
+            (min.boundary_min(point), max.boundary_max(point))
+        });
+
+        let mut tiles = Map::new((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize);
+        tiles.for_each_mut(|tile| *tile = sparse.default_tile().clone());
+        let mut map = HexMap::from_tiles(layout, min, tiles);
+        for (hex, tile) in sparse.iter() {
+            map[hex] = tile.clone();
+        }
+        map
+    }
+}
+
+impl<T> Index<Hex> for HexMap<T> {
+    type Output = T;
+
+    fn index(&self, hex: Hex) -> &T {
+        &self.tiles[self.hex2point(hex)]
+    }
+}
+
+impl<T> IndexMut<Hex> for HexMap<T> {
+    fn index_mut(&mut self, hex: Hex) -> &mut T {
+        let point = self.hex2point(hex);
+        &mut self.tiles[point]
+    }
+}
+
+impl<T> fmt::Display for HexMap<T>
+where
+    T: fmt::Display + DisplayWidth,
+{
+    /// Render the map as ASCII art, top row first.
+    ///
+    /// Each tile is followed by a space. In the pointy layout, each text line is a row of hexes,
+    /// and odd rows are indented by half a tile. In the flat layout, each column of hexes is
+    /// spread over alternate lines, and odd columns are a line lower. Parity is that of the
+    /// offset coordinates, not of the position within the map.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = T::DISPLAY_WIDTH;
+        let pitch = width + 1;
+        let mut line = String::new();
+        let flush = |line: &mut String, f: &mut fmt::Formatter<'_>| {
+            let result = writeln!(f, "{}", line.trim_end());
+            line.clear();
+            result
+        };
+
+        match self.layout {
+            Layout::Pointy => {
+                for row in 0..self.height() {
+                    if (row as i32 + self.origin.y) & 1 == 1 {
+                        line.push_str(&" ".repeat(pitch / 2));
+                    }
+                    for col in 0..self.width() {
+                        line.push_str(&format!("{:width$} ", self.tiles[(col, row)]));
+                    }
+                    flush(&mut line, f)?;
+                }
+            }
+            Layout::Flat => {
+                for text_row in 0..2 * self.height() {
+                    for col in 0..self.width() {
+                        if text_row & 1 == (col as i32 + self.origin.x) as usize & 1 {
+                            let tile = &self.tiles[(col, text_row / 2)];
+                            line.push_str(&format!("{:width$} ", tile));
+                        } else {
+                            line.push_str(&" ".repeat(pitch));
+                        }
+                    }
+                    flush(&mut line, f)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::{tile::Bool, Automaton};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_directions() {
+        for direction in HexDirection::iter() {
+            assert_eq!(direction.deltas().length(), 1);
+            assert_eq!(
+                direction.deltas() + direction.reverse().deltas(),
+                Hex::ORIGIN
+            );
+            assert_eq!(
+                direction.deltas().rotate_right(),
+                direction.turn(1).deltas()
+            );
+            for layout in [Layout::Pointy, Layout::Flat].iter().copied() {
+                let name = layout.direction_name(direction);
+                assert_eq!(layout.parse_direction(name), Some((direction, "")));
+            }
+        }
+        assert_eq!(
+            Layout::Pointy.parse_direction("sew"),
+            Some((HexDirection::Southeast, "w"))
+        );
+        assert_eq!(Layout::Flat.parse_direction("e"), None);
+    }
+
+    #[test]
+    fn test_offset_round_trip() {
+        let offsets = [Offset::OddR, Offset::EvenR, Offset::OddQ, Offset::EvenQ];
+        for hex in Hex::new(1, -2).spiral(4) {
+            for &offset in &offsets {
+                assert_eq!(Hex::from_offset(hex.to_offset(offset), offset), hex);
+            }
+        }
+        // odd rows are shoved right: their west neighbor shares a column
+        assert_eq!(Hex::new(0, 1).to_offset(Offset::OddR), Point::new(0, 1));
+        assert_eq!(Hex::new(-1, 1).to_offset(Offset::OddR), Point::new(-1, 1));
+        assert_eq!(Hex::new(0, 1).to_offset(Offset::EvenR), Point::new(1, 1));
+    }
+
+    #[test]
+    fn test_rings_and_spirals() {
+        let center = Hex::new(3, -1);
+        assert_eq!(center.ring(0).collect::<Vec<_>>(), vec![center]);
+        for radius in 1..5 {
+            let ring: Vec<_> = center.ring(radius).collect();
+            assert_eq!(ring.len(), 6 * radius as usize);
+            assert!(ring
+                .iter()
+                .all(|&hex| hex.distance(center) == radius as i32));
+            assert!(ring.windows(2).all(|pair| pair[0].distance(pair[1]) == 1));
+        }
+        let spiral: HashSet<_> = center.spiral(3).collect();
+        assert_eq!(spiral.len(), 1 + 3 * 3 * 4);
+    }
+
+    #[test]
+    fn test_line_and_rotation() {
+        let (a, b) = (Hex::new(-2, 0), Hex::new(3, -4));
+        let line: Vec<_> = a.line_to(b).collect();
+        assert_eq!(line.len(), a.distance(b) as usize + 1);
+        assert_eq!((line[0], line[line.len() - 1]), (a, b));
+        assert!(line.windows(2).all(|pair| pair[0].distance(pair[1]) == 1));
+
+        let center = Hex::new(1, 1);
+        let hex = Hex::new(3, 0);
+        assert_eq!(hex.rotate_about(center, 6), hex);
+        assert_eq!(hex.rotate_about(center, -1), hex.rotate_about(center, 5));
+        assert_eq!(hex.rotate_about(center, 2).distance(center), 2);
+        assert_eq!(hex.rotate_left().rotate_right(), hex);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut sparse = SparseMap::<Hex, Bool>::default();
+        for hex in Hex::ORIGIN.ring(1) {
+            sparse.insert(hex, Bool::True);
+        }
+        let pointy = HexMap::from_sparse(Layout::Pointy, &sparse);
+        assert_eq!(pointy.origin(), Point::new(-1, -1));
+        assert_eq!(pointy.to_string(), " # # .\n# . #\n # # .\n");
+        assert!(pointy.hexes().all(|hex| pointy.get(hex).is_some()));
+
+        let flat = HexMap::from_sparse(Layout::Flat, &sparse);
+        assert_eq!(flat.to_string(), "  #\n#   #\n  .\n#   #\n  #\n.   .\n");
+        assert_eq!(flat.hexes().filter(|&hex| flat[hex].into()).count(), 6);
+    }
+
+    #[test]
+    fn test_automaton() {
+        // a hex flower, under a rule where a tile is alive when exactly two neighbors are
+        let mut map = HexMap::<Bool>::new(Layout::Pointy, 5, 5);
+        let center = Hex::from_offset(Point::new(2, 2), Layout::Pointy.offset());
+        for hex in center.ring(1) {
+            map[hex] = Bool::True;
+        }
+        let mut automaton = Automaton::new(
+            map.tiles().clone(),
+            |_, point| map.offset_adjacencies(point),
+            |_, neighbors| {
+                let alive = neighbors.filter(|&&tile| tile == Bool::True).count();
+                (alive == 2).into()
+            },
+        );
+        automaton.step();
+        let stepped = HexMap::from_tiles(Layout::Pointy, map.origin(), automaton.into_map());
+        // the ring survives, and the center, with six living neighbors, stays dead
+        assert!(center.ring(1).all(|hex| stepped[hex].into()));
+        assert_eq!(stepped[center], Bool::False);
+    }
+}
@@ -0,0 +1,355 @@
+//! Closed intervals of integers, and sets of them.
+
+use num::PrimInt;
+use std::{fmt, iter::FromIterator, str::FromStr};
+
+/// The integers from `lo` to `hi`, inclusive.
+///
+/// An interval with `lo > hi` is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub lo: T,
+    pub hi: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(lo: T, hi: T) -> Self {
+        Interval { lo, hi }
+    }
+
+    /// An interval containing only `value`.
+    pub fn singleton(value: T) -> Self {
+        Interval::new(value, value)
+    }
+
+    pub fn empty() -> Self {
+        Interval::new(T::one(), T::zero())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lo > self.hi
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+
+    /// The number of integers in this interval.
+    ///
+    /// This overflows only for an interval spanning every value of `T`.
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::zero()
+        } else {
+            self.hi - self.lo + T::one()
+        }
+    }
+
+    /// The integers in both intervals. The result may be empty.
+    pub fn intersect(&self, other: &Self) -> Self {
+        Interval::new(self.lo.max(other.lo), self.hi.min(other.hi))
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersect(other).is_empty()
+    }
+
+    /// The smallest interval containing both intervals.
+    pub fn hull(&self, other: &Self) -> Self {
+        match (self.is_empty(), other.is_empty()) {
+            (true, _) => *other,
+            (_, true) => *self,
+            _ => Interval::new(self.lo.min(other.lo), self.hi.max(other.hi)),
+        }
+    }
+
+    /// Can these intervals be merged into one: do they overlap or abut?
+    pub fn touches(&self, other: &Self) -> bool {
+        fn reaches<T: PrimInt>(hi: T, lo: T) -> bool {
+            hi.checked_add(&T::one()).is_none_or(|next| lo <= next)
+        }
+        !self.is_empty()
+            && !other.is_empty()
+            && reaches(self.hi, other.lo)
+            && reaches(other.hi, self.lo)
+    }
+
+    /// The integers in this interval but not in `other`: at most two non-empty pieces.
+    pub fn subtract(&self, other: &Self) -> impl Iterator<Item = Self> {
+        let (mut left, mut right) = (None, None);
+        if !self.overlaps(other) {
+            if !self.is_empty() {
+                left = Some(*self);
+            }
+        } else {
+            if other.lo > self.lo {
+                left = Some(Interval::new(self.lo, other.lo - T::one()));
+            }
+            if other.hi < self.hi {
+                right = Some(Interval::new(other.hi + T::one(), self.hi));
+            }
+        }
+        left.into_iter().chain(right)
+    }
+
+    /// Split this interval into the values below `at` and the values from `at` upward.
+    pub fn split(&self, at: T) -> (Self, Self) {
+        let below = match at.checked_sub(&T::one()) {
+            Some(prev) => Interval::new(self.lo, self.hi.min(prev)),
+            None => Interval::empty(),
+        };
+        (below, Interval::new(self.lo.max(at), self.hi))
+    }
+}
+
+impl<T: PrimInt> Default for Interval<T> {
+    fn default() -> Self {
+        Interval::empty()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.lo, self.hi)
+    }
+}
+
+impl<T> FromStr for Interval<T>
+where
+    T: PrimInt + FromStr,
+    T::Err: fmt::Display,
+{
+    type Err = String;
+
+    /// Parse an interval like `3-7`. Either bound may be negative, as in `-7--3`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        // skip the first character so that a leading minus sign isn't taken as the separator
+        let sep = s
+            .char_indices()
+            .skip(1)
+            .find(|&(_, ch)| ch == '-')
+            .map(|(idx, _)| idx)
+            .ok_or_else(|| format!("interval {:?} has no separator", s))?;
+        let bound = |part: &str| {
+            part.trim()
+                .parse()
+                .map_err(|err| format!("interval {:?}: {}", s, err))
+        };
+        Ok(Interval::new(bound(&s[..sep])?, bound(&s[sep + 1..])?))
+    }
+}
+
+/// A set of integers, stored as sorted, disjoint intervals.
+///
+/// No two stored intervals touch, so the representation of any set is unique.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    /// The disjoint intervals making up this set, in ascending order.
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The number of integers in this set.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::zero(), |total, interval| total + interval.len())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let idx = self
+            .intervals
+            .partition_point(|interval| interval.hi < value);
+        self.intervals
+            .get(idx)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    /// Add every integer in `interval` to this set, merging with whatever it touches.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        // the intervals touching the new one form a contiguous run
+        let start = self
+            .intervals
+            .partition_point(|existing| existing.hi < interval.lo && !existing.touches(&interval));
+        let end = start
+            + self.intervals[start..]
+                .iter()
+                .take_while(|existing| existing.touches(&interval))
+                .count();
+        let merged = self.intervals[start..end]
+            .iter()
+            .fold(interval, |merged, existing| merged.hull(existing));
+        self.intervals.splice(start..end, std::iter::once(merged));
+    }
+
+    /// Remove every integer in `interval` from this set.
+    pub fn remove(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        let start = self
+            .intervals
+            .partition_point(|existing| existing.hi < interval.lo);
+        let end = start
+            + self.intervals[start..]
+                .iter()
+                .take_while(|existing| existing.lo <= interval.hi)
+                .count();
+        let remaining: Vec<_> = self.intervals[start..end]
+            .iter()
+            .flat_map(|existing| existing.subtract(&interval))
+            .collect();
+        self.intervals.splice(start..end, remaining);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        union.extend(other.intervals.iter().copied());
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut a, mut b) = (
+            self.intervals.iter().peekable(),
+            other.intervals.iter().peekable(),
+        );
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let overlap = x.intersect(y);
+            if !overlap.is_empty() {
+                intervals.push(overlap);
+            }
+            // whichever ends first can't overlap anything further along the other set
+            if x.hi < y.hi {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for &interval in &other.intervals {
+            difference.remove(interval);
+        }
+        difference
+    }
+}
+
+impl<T: PrimInt> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        let mut set = IntervalSet::new();
+        set.insert(interval);
+        set
+    }
+}
+
+impl<T: PrimInt> Extend<Interval<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = Interval<T>>>(&mut self, iter: I) {
+        for interval in iter {
+            self.insert(interval);
+        }
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        intervals
+            .iter()
+            .map(|&(lo, hi)| Interval::new(lo, hi))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let a = Interval::new(1, 5);
+        assert_eq!(a.len(), 5);
+        assert!(Interval::<i32>::empty().is_empty());
+        assert_eq!(Interval::<i32>::empty().len(), 0);
+        assert_eq!(a.intersect(&Interval::new(4, 9)), Interval::new(4, 5));
+        assert!(!a.overlaps(&Interval::new(6, 9)));
+        assert!(a.touches(&Interval::new(6, 9)));
+        assert!(!a.touches(&Interval::new(7, 9)));
+        assert!(Interval::new(0, i32::MAX).touches(&Interval::new(i32::MAX, i32::MAX)));
+
+        let pieces: Vec<_> = a.subtract(&Interval::new(2, 3)).collect();
+        assert_eq!(pieces, vec![Interval::new(1, 1), Interval::new(4, 5)]);
+        assert_eq!(a.subtract(&Interval::new(0, 9)).count(), 0);
+        assert_eq!(
+            a.subtract(&Interval::new(7, 9)).collect::<Vec<_>>(),
+            vec![a]
+        );
+
+        assert_eq!(a.split(3), (Interval::new(1, 2), Interval::new(3, 5)));
+        assert!(a.split(0).0.is_empty());
+
+        assert_eq!("-7--3".parse(), Ok(Interval::new(-7, -3)));
+        assert_eq!("25-974".parse(), Ok(Interval::new(25u32, 974)));
+        assert_eq!(Interval::new(-7, -3).to_string(), "-7--3");
+        assert!("12".parse::<Interval<u32>>().is_err());
+    }
+
+    #[test]
+    fn test_interval_set() {
+        let mut s = set(&[(10, 12), (1, 3), (5, 6)]);
+        assert_eq!(s.intervals().len(), 3);
+        assert_eq!(s.len(), 8);
+        assert!(s.contains(11) && !s.contains(4) && !s.contains(13));
+
+        // bridging and abutting intervals merge
+        s.insert(Interval::new(4, 4));
+        assert_eq!(s, set(&[(1, 6), (10, 12)]));
+        s.insert(Interval::new(7, 9));
+        assert_eq!(s, set(&[(1, 12)]));
+
+        s.remove(Interval::new(3, 5));
+        s.remove(Interval::new(12, 20));
+        assert_eq!(s, set(&[(1, 2), (6, 11)]));
+
+        let other = set(&[(0, 1), (5, 7), (10, 30)]);
+        assert_eq!(s.intersection(&other), set(&[(1, 1), (6, 7), (10, 11)]));
+        assert_eq!(s.union(&other), set(&[(0, 2), (5, 30)]));
+        assert_eq!(s.difference(&other), set(&[(2, 2), (8, 9)]));
+        assert_eq!(
+            s.difference(&other).len() + s.intersection(&other).len(),
+            s.len()
+        );
+    }
+}
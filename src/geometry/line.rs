@@ -1,4 +1,11 @@
+//! Line segments between lattice points, and their intersections.
+//!
+//! Intersections are computed exactly: products of coordinates are taken in `i128`, and points of
+//! intersection which do not lie on the lattice are expressed as ratios.
+
 use crate::geometry::Point;
+use num::rational::Ratio;
+use std::collections::BTreeSet;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Line {
@@ -14,44 +21,424 @@ impl Line {
     pub fn manhattan_len(&self) -> i32 {
         (self.to - self.from).manhattan()
     }
+
+    /// A line of a single point is both horizontal and vertical.
+    pub fn is_horizontal(&self) -> bool {
+        self.from.y == self.to.y
+    }
+
+    /// A line of a single point is both horizontal and vertical.
+    pub fn is_vertical(&self) -> bool {
+        self.from.x == self.to.x
+    }
+
+    /// Does this line pass through `point`, including at its ends?
+    pub fn contains(&self, point: Point) -> bool {
+        !intersect(*self, Line::new(point, point)).is_none()
+    }
+}
+
+pub(crate) type Delta = (i128, i128);
+
+/// The exact offset from `from` to `to`.
+pub(crate) fn delta(from: Point, to: Point) -> Delta {
+    (to.x as i128 - from.x as i128, to.y as i128 - from.y as i128)
+}
+
+pub(crate) fn cross(a: Delta, b: Delta) -> i128 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+fn dot(a: Delta, b: Delta) -> i128 {
+    a.0 * b.0 + a.1 * b.1
+}
+
+/// How two lines meet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intersection {
+    /// The lines do not meet.
+    None,
+    /// The lines meet at exactly one point, which need not lie on the lattice.
+    Point { x: Ratio<i128>, y: Ratio<i128> },
+    /// The lines are collinear, and share this line, which has positive length.
+    ///
+    /// Its ends are ordered as they are along the first line given to [`intersect`].
+    Overlap(Line),
+}
+
+impl Intersection {
+    fn at(point: Point) -> Intersection {
+        Intersection::Point {
+            x: Ratio::from_integer(point.x.into()),
+            y: Ratio::from_integer(point.y.into()),
+        }
+    }
+
+    pub fn is_none(&self) -> bool {
+        matches!(self, Intersection::None)
+    }
+
+    /// The single point at which the lines meet, if there is one and it lies on the lattice.
+    pub fn lattice_point(&self) -> Option<Point> {
+        match self {
+            Intersection::Point { x, y } if x.is_integer() && y.is_integer() => {
+                Some(Point::new(x.to_integer() as i32, y.to_integer() as i32))
+            }
+            _ => None,
+        }
+    }
 }
 
-// https://stackoverflow.com/a/1968345/504550
-pub fn intersect(a: Line, b: Line) -> Option<Point> {
-    let p0 = a.from;
-    let p1 = a.to;
-    let p2 = b.from;
-    let p3 = b.to;
+/// Compute exactly how two lines meet.
+///
+/// Lines include their ends, so lines which merely touch meet at a point.
+pub fn intersect(a: Line, b: Line) -> Intersection {
+    let r = delta(a.from, a.to);
+    let s = delta(b.from, b.to);
+    let qp = delta(a.from, b.from);
+    let rxs = cross(r, s);
+
+    if rxs == 0 {
+        // parallel; only lines on the same infinite line can meet
+        if cross(qp, r) != 0 || cross(qp, s) != 0 {
+            return Intersection::None;
+        }
+        return intersect_collinear(a, b, r, s);
+    }
 
-    let s1_x = (p1.x - p0.x) as f32;
-    let s1_y = (p1.y - p0.y) as f32;
-    let s2_x = (p3.x - p2.x) as f32;
-    let s2_y = (p3.y - p2.y) as f32;
+    // a.from + t/d * r == b.from + u/d * s
+    let (mut t, mut u, mut d) = (cross(qp, s), cross(qp, r), rxs);
+    if d < 0 {
+        t = -t;
+        u = -u;
+        d = -d;
+    }
+    if !(0..=d).contains(&t) || !(0..=d).contains(&u) {
+        return Intersection::None;
+    }
+    Intersection::Point {
+        x: Ratio::new(a.from.x as i128 * d + t * r.0, d),
+        y: Ratio::new(a.from.y as i128 * d + t * r.1, d),
+    }
+}
 
-    let s =
-        (-s1_y * (p0.x - p2.x) as f32 + s1_x * (p0.y - p2.y) as f32) / (-s2_x * s1_y + s1_x * s2_y);
-    let t =
-        (s2_x * (p0.y - p2.y) as f32 - s2_y * (p0.x - p2.x) as f32) / (-s2_x * s1_y + s1_x * s2_y);
+fn intersect_collinear(a: Line, b: Line, r: Delta, s: Delta) -> Intersection {
+    let direction = if r != (0, 0) { r } else { s };
+    if direction == (0, 0) {
+        return if a.from == b.from {
+            Intersection::at(a.from)
+        } else {
+            Intersection::None
+        };
+    }
 
-    if s >= 0.0 && s <= 1.0 && t >= 0.0 && t <= 1.0 {
-        // round the results so errors line up nicely
-        Some(Point::new(
-            p0.x + (t * s1_x).round() as i32,
-            p0.y + (t * s1_y).round() as i32,
-        ))
+    // position along the shared line
+    let key = |point: Point| dot(delta(a.from, point), direction);
+    let span = |line: Line| {
+        let (from, to) = (key(line.from), key(line.to));
+        (from.min(to), from.max(to))
+    };
+    let (a_lo, a_hi) = span(a);
+    let (b_lo, b_hi) = span(b);
+    let (lo, hi) = (a_lo.max(b_lo), a_hi.min(b_hi));
+    if lo > hi {
+        return Intersection::None;
+    }
+
+    // every bound of the shared span is the end of one of the lines
+    let ends = [a.from, a.to, b.from, b.to];
+    let at = |k| {
+        *ends
+            .iter()
+            .find(|&&point| key(point) == k)
+            .expect("span bounds are line ends")
+    };
+    if lo == hi {
+        Intersection::at(at(lo))
+    } else if key(a.from) <= key(a.to) {
+        Intersection::Overlap(Line::new(at(lo), at(hi)))
     } else {
-        None
+        Intersection::Overlap(Line::new(at(hi), at(lo)))
+    }
+}
+
+/// A meeting between a line from each of two sets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crossing {
+    /// Index of the line in the first set.
+    pub a: usize,
+    /// Index of the line in the second set.
+    pub b: usize,
+    pub intersection: Intersection,
+}
+
+/// Find every pair of lines, one from each set, which meet, by checking every pair.
+///
+/// Crossings are ordered by `(a, b)`.
+pub fn intersections_naive(a: &[Line], b: &[Line]) -> Vec<Crossing> {
+    let mut crossings = Vec::new();
+    for (ai, &al) in a.iter().enumerate() {
+        for (bi, &bl) in b.iter().enumerate() {
+            let intersection = intersect(al, bl);
+            if !intersection.is_none() {
+                crossings.push(Crossing {
+                    a: ai,
+                    b: bi,
+                    intersection,
+                });
+            }
+        }
+    }
+    crossings
+}
+
+/// An axis-aligned line: `(level, lo, hi, index)`.
+///
+/// For a horizontal line, the level is its `y` and the span is of `x`; for a vertical line,
+/// the reverse.
+type Span = (i32, i32, i32, usize);
+
+fn horizontal_span(line: Line, idx: usize) -> Span {
+    let (x0, x1) = (line.from.x, line.to.x);
+    (line.from.y, x0.min(x1), x0.max(x1), idx)
+}
+
+fn vertical_span(line: Line, idx: usize) -> Span {
+    let (y0, y1) = (line.from.y, line.to.y);
+    (line.from.x, y0.min(y1), y0.max(y1), idx)
+}
+
+/// Find every pair of lines, one from each set, which meet.
+///
+/// Axis-aligned lines, like those produced by
+/// [`follow`][crate::geometry::line_segment::follow], are handled by sweeps in
+/// `O((n + k) log n)` time for `n` lines and `k` crossings. Any other lines are checked
+/// against every line of the other set.
+///
+/// Crossings are ordered by `(a, b)`.
+pub fn intersections(a: &[Line], b: &[Line]) -> Vec<Crossing> {
+    // lines of a single point are treated as horizontal
+    let partition = |lines: &[Line]| {
+        let (mut horizontal, mut vertical, mut other) = (Vec::new(), Vec::new(), Vec::new());
+        for (idx, &line) in lines.iter().enumerate() {
+            if line.is_horizontal() {
+                horizontal.push(horizontal_span(line, idx));
+            } else if line.is_vertical() {
+                vertical.push(vertical_span(line, idx));
+            } else {
+                other.push(idx);
+            }
+        }
+        (horizontal, vertical, other)
+    };
+    let (a_horizontal, a_vertical, a_other) = partition(a);
+    let (b_horizontal, b_vertical, b_other) = partition(b);
+
+    let mut crossings = Vec::new();
+    let mut push = |ai: usize, bi: usize| {
+        let intersection = intersect(a[ai], b[bi]);
+        if !intersection.is_none() {
+            crossings.push(Crossing {
+                a: ai,
+                b: bi,
+                intersection,
+            });
+        }
+    };
+
+    sweep_perpendicular(&a_horizontal, &b_vertical, &mut push);
+    sweep_perpendicular(&b_horizontal, &a_vertical, |bi, ai| push(ai, bi));
+    sweep_parallel(&a_horizontal, &b_horizontal, &mut push);
+    sweep_parallel(&a_vertical, &b_vertical, &mut push);
+
+    for &ai in &a_other {
+        for bi in 0..b.len() {
+            push(ai, bi);
+        }
+    }
+    let a_aligned = a_horizontal.iter().chain(&a_vertical).map(|span| span.3);
+    for ai in a_aligned {
+        for &bi in &b_other {
+            push(ai, bi);
+        }
+    }
+
+    crossings.sort_unstable_by_key(|crossing| (crossing.a, crossing.b));
+    crossings
+}
+
+/// Report every horizontal line which meets a vertical line, as `found(horizontal, vertical)`.
+///
+/// Sweep from left to right, keeping the horizontal lines under the sweep ordered by `y`.
+fn sweep_perpendicular(
+    horizontal: &[Span],
+    vertical: &[Span],
+    mut found: impl FnMut(usize, usize),
+) {
+    // at equal x, enter before querying before exiting, so that touching ends count
+    const ENTER: u8 = 0;
+    const QUERY: u8 = 1;
+    const EXIT: u8 = 2;
+
+    let mut events = Vec::with_capacity(2 * horizontal.len() + vertical.len());
+    for (position, &(_, lo, hi, _)) in horizontal.iter().enumerate() {
+        events.push((lo, ENTER, position));
+        events.push((hi, EXIT, position));
+    }
+    for (position, &(x, _, _, _)) in vertical.iter().enumerate() {
+        events.push((x, QUERY, position));
+    }
+    events.sort_unstable();
+
+    let mut active = BTreeSet::new();
+    for (_, kind, position) in events {
+        let (level, lo, hi, idx) = match kind {
+            QUERY => vertical[position],
+            _ => horizontal[position],
+        };
+        match kind {
+            ENTER => {
+                active.insert((level, idx));
+            }
+            EXIT => {
+                active.remove(&(level, idx));
+            }
+            _ => {
+                for &(_, horizontal_idx) in active.range((lo, 0)..=(hi, usize::MAX)) {
+                    found(horizontal_idx, idx);
+                }
+            }
+        }
     }
 }
 
-pub fn intersections_naive(ap: &[Line], bp: &[Line]) -> Vec<Point> {
-    let mut isects = Vec::new();
-    for a in ap {
-        for b in bp {
-            if let Some(isect) = intersect(*a, *b) {
-                isects.push(isect);
+/// Report every pair of parallel lines at the same level which overlap or touch,
+/// as `found(a, b)`.
+fn sweep_parallel(a: &[Span], b: &[Span], mut found: impl FnMut(usize, usize)) {
+    const ENTER: u8 = 0;
+    const EXIT: u8 = 1;
+
+    let mut events = Vec::with_capacity(2 * (a.len() + b.len()));
+    for (set, spans) in [a, b].iter().enumerate() {
+        for &(level, lo, hi, idx) in spans.iter() {
+            events.push((level, lo, ENTER, set, idx));
+            events.push((level, hi, EXIT, set, idx));
+        }
+    }
+    // every line exits before the sweep moves on to the next level
+    events.sort_unstable();
+
+    let mut active = [BTreeSet::new(), BTreeSet::new()];
+    for (_, _, kind, set, idx) in events {
+        if kind == EXIT {
+            active[set].remove(&idx);
+            continue;
+        }
+        for &other in &active[1 - set] {
+            if set == 0 {
+                found(idx, other);
+            } else {
+                found(other, idx);
             }
         }
+        active[set].insert(idx);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::line_segment::{follow, LineSegment};
+
+    fn line(x0: i32, y0: i32, x1: i32, y1: i32) -> Line {
+        Line::new(Point::new(x0, y0), Point::new(x1, y1))
+    }
+
+    #[test]
+    fn test_intersect() {
+        let isect = intersect(line(0, 0, 1, 1), line(0, 1, 1, 0));
+        let half = Ratio::new(1, 2);
+        assert_eq!(isect, Intersection::Point { x: half, y: half });
+        assert_eq!(isect.lattice_point(), None);
+
+        // products of these coordinates overflow i64, and are far beyond f32's precision
+        let big = i32::MAX - 1;
+        let isect = intersect(line(-big, -big, big, big), line(-big, big, big, -big));
+        assert_eq!(isect.lattice_point(), Some(Point::new(0, 0)));
+        let (x, y) = (
+            Ratio::from_integer(big as i128 - 1),
+            Ratio::new(2 * big as i128 - 1, big as i128),
+        );
+        assert_eq!(
+            intersect(line(-big, 0, big, 2), line(big - 1, -big, big - 1, big)),
+            Intersection::Point { x, y }
+        );
+
+        // touching ends
+        assert_eq!(
+            intersect(line(0, 0, 2, 0), line(2, 0, 2, 5)).lattice_point(),
+            Some(Point::new(2, 0))
+        );
+        assert!(intersect(line(0, 0, 2, 0), line(3, 0, 3, 5)).is_none());
+        // parallel
+        assert!(intersect(line(0, 0, 2, 2), line(0, 1, 2, 3)).is_none());
+    }
+
+    #[test]
+    fn test_intersect_collinear() {
+        assert_eq!(
+            intersect(line(4, 4, 0, 0), line(1, 1, 6, 6)),
+            Intersection::Overlap(line(4, 4, 1, 1))
+        );
+        assert_eq!(
+            intersect(line(0, 0, 0, 3), line(0, 5, 0, 3)).lattice_point(),
+            Some(Point::new(0, 3))
+        );
+        assert!(intersect(line(0, 0, 0, 3), line(0, 4, 0, 6)).is_none());
+        // single points
+        assert!(line(0, 0, 6, 3).contains(Point::new(2, 1)));
+        assert!(!line(0, 0, 6, 3).contains(Point::new(1, 1)));
+        assert!(line(5, 5, 5, 5).contains(Point::new(5, 5)));
+    }
+
+    fn wire(path: &str) -> Vec<Line> {
+        let segments: Vec<LineSegment> = path.split(',').map(|s| s.parse().unwrap()).collect();
+        follow(&segments)
+    }
+
+    #[test]
+    fn test_sweep_matches_naive() {
+        let a = wire("R75,D30,R83,U83,L12,D49,R71,U7,L72");
+        let b = wire("U62,R66,U55,R34,D71,R55,D58,R83");
+        let crossings = intersections(&a, &b);
+        assert_eq!(crossings, intersections_naive(&a, &b));
+        let closest = crossings
+            .iter()
+            .filter_map(|crossing| crossing.intersection.lattice_point())
+            .map(|point| point.manhattan())
+            .filter(|&distance| distance > 0)
+            .min();
+        assert_eq!(closest, Some(159));
+
+        // overlaps, single points, and diagonals
+        let mut a = wire("R8,U5,L5,D3,L10,U20,R30");
+        let mut b = wire("U7,R6,D4,L4,D10,R20,U30");
+        a.extend(vec![
+            line(3, 3, 3, 3),
+            line(-5, -5, 10, 10),
+            line(0, 5, 8, 5),
+        ]);
+        b.extend(vec![
+            line(6, 5, 6, 5),
+            line(0, 10, 10, 0),
+            line(-2, 7, 0, 7),
+        ]);
+        a.extend(vec![line(40, 0, 40, 10), line(50, 3, 60, 3)]);
+        b.extend(vec![line(40, 15, 40, 5), line(55, 3, 52, 3)]);
+        let crossings = intersections(&a, &b);
+        assert!(crossings
+            .iter()
+            .any(|crossing| matches!(crossing.intersection, Intersection::Overlap(_))));
+        assert_eq!(crossings, intersections_naive(&a, &b));
     }
-    isects
 }
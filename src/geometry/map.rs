@@ -1,6 +1,8 @@
-use crate::geometry::{tile::DisplayWidth, Direction, Point};
+use crate::geometry::{tile::DisplayWidth, Direction, Orientation, Point, Rect, Wrap};
+use crate::priority_queue::IndexedPriorityQueue;
 use bitvec::bitvec;
-use std::collections::{BinaryHeap, HashMap, VecDeque};
+use itertools::Either;
+use std::collections::VecDeque;
 use std::convert::{TryFrom, TryInto};
 use std::fmt;
 use std::ops::{Index, IndexMut};
@@ -104,9 +106,70 @@ impl<T> Map<T> {
             && point.y < self.height.try_into().unwrap_or(i32::MAX)
     }
 
+    /// Bring a point into bounds by wrapping it along the given axes.
+    ///
+    /// Returns `None` if the point is out of bounds along an axis which does not wrap.
+    pub(crate) fn wrap_point(&self, wrap: Option<Wrap>, point: Point) -> Option<Point> {
+        let width = self.width.try_into().unwrap_or(i32::MAX);
+        let height = self.height.try_into().unwrap_or(i32::MAX);
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let wrap_x = wrap.is_some_and(Wrap::wraps_x);
+        let wrap_y = wrap.is_some_and(Wrap::wraps_y);
+        let point = Point::new(
+            if wrap_x {
+                point.x.rem_euclid(width)
+            } else {
+                point.x
+            },
+            if wrap_y {
+                point.y.rem_euclid(height)
+            } else {
+                point.y
+            },
+        );
+        Some(point).filter(|&point| self.in_bounds(point))
+    }
+
+    /// The number of orthogonal steps between two in-bounds points, which may cross wrapping edges.
+    pub(crate) fn wrapped_manhattan(&self, wrap: Option<Wrap>, a: Point, b: Point) -> i32 {
+        let axis = |delta: i32, size: usize, wraps: bool| {
+            let delta = delta.abs();
+            if wraps {
+                delta.min(size as i32 - delta)
+            } else {
+                delta
+            }
+        };
+        axis(a.x - b.x, self.width, wrap.is_some_and(Wrap::wraps_x))
+            + axis(a.y - b.y, self.height, wrap.is_some_and(Wrap::wraps_y))
+    }
+
+    /// Assemble a map from its tiles in storage order.
+    ///
+    /// Panics if the number of tiles does not match the dimensions.
+    pub(crate) fn from_tiles(width: usize, height: usize, tiles: Vec<T>) -> Map<T> {
+        assert_eq!(tiles.len(), width * height, "tiles must fill the map");
+        Map {
+            tiles,
+            width,
+            height,
+        }
+    }
+
+    /// the tiles in storage order: row by row, starting from the bottom left
+    pub(crate) fn tiles(&self) -> &[T] {
+        &self.tiles
+    }
+
+    pub(crate) fn tiles_mut(&mut self) -> &mut [T] {
+        &mut self.tiles
+    }
+
     /// convert a 2d point into a 1d index into the tiles
     #[inline]
-    fn point2index(&self, x: usize, y: usize) -> usize {
+    pub(crate) fn point2index(&self, x: usize, y: usize) -> usize {
         x + (y * self.width)
     }
 
@@ -122,6 +185,62 @@ impl<T> Map<T> {
         move |idx: usize| (idx % width, idx / width)
     }
 
+    /// The tiles of row `y`, from left to right.
+    ///
+    /// Panics if `y` is out of bounds.
+    pub fn row(&self, y: usize) -> &[T] {
+        assert!(y < self.height, "row out of bounds");
+        &self.tiles[y * self.width..(y + 1) * self.width]
+    }
+
+    /// The tiles of row `y`, from left to right.
+    ///
+    /// Panics if `y` is out of bounds.
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        assert!(y < self.height, "row out of bounds");
+        &mut self.tiles[y * self.width..(y + 1) * self.width]
+    }
+
+    /// The tiles of column `x`, from bottom to top.
+    ///
+    /// Panics if `x` is out of bounds.
+    pub fn column(&self, x: usize) -> impl '_ + DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        assert!(x < self.width, "column out of bounds");
+        (0..self.height).map(move |y| &self.tiles[self.point2index(x, y)])
+    }
+
+    /// Iterate over the rows of this map from the bottom up: storage order.
+    pub fn rows(&self) -> impl '_ + DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    /// Iterate over the rows of this map from the top down: natural graphical order.
+    pub fn rows_top_down(&self) -> impl '_ + DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        self.rows().rev()
+    }
+
+    /// Iterate over the columns of this map from left to right; each runs from the bottom up.
+    pub fn columns(
+        &self,
+    ) -> impl '_ + DoubleEndedIterator<Item = impl '_ + DoubleEndedIterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Iterate over the columns of this map from left to right; each runs from the top down.
+    pub fn columns_top_down(
+        &self,
+    ) -> impl '_ + DoubleEndedIterator<Item = impl '_ + DoubleEndedIterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x).rev())
+    }
+
+    /// Create a map of the same dimensions, by applying a function to each tile.
+    pub fn map_tiles<U, F>(&self, f: F) -> Map<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Map::from_tiles(self.width, self.height, self.tiles.iter().map(f).collect())
+    }
+
     /// Return an iterator of all legal points adjacent to the given point.
     ///
     /// This iterator will return up to 8 elements; it includes diagonals.
@@ -180,7 +299,7 @@ impl<T> Map<T> {
 impl<T: Clone + Default> Map<T> {
     pub fn new(width: usize, height: usize) -> Map<T> {
         Map {
-            tiles: vec![T::default(); width * height].into(),
+            tiles: vec![T::default(); width * height],
             width,
             height,
         }
@@ -188,56 +307,124 @@ impl<T: Clone + Default> Map<T> {
 
     /// Create a copy of this map which has been flipped vertically: the axis of symmetry is horizontal.
     pub fn flip_vertical(&self) -> Map<T> {
-        let mut flipped = Map::new(self.width, self.height);
-
-        for y in 0..self.height {
-            let flipped_y = self.height - y - 1;
-            for x in 0..self.width {
-                flipped[(x, flipped_y)] = self[(x, y)].clone();
-            }
-        }
-
-        flipped
+        self.view().flip_vertical().to_map()
     }
 
     /// Create a copy of this map which has been flipped horizontally; the axis of symmetry is vertical.
     pub fn flip_horizontal(&self) -> Map<T> {
-        let mut flipped = Map::new(self.width, self.height);
-
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let flipped_x = self.width - x - 1;
-                flipped[(flipped_x, y)] = self[(x, y)].clone();
-            }
-        }
-
-        flipped
+        self.view().flip_horizontal().to_map()
     }
 
     /// Create a copy of this map which has been rotated counter-clockwise.
     pub fn rotate_left(&self) -> Map<T> {
-        let mut rotated = Map::new(self.height, self.width);
+        self.view().rotate_left().to_map()
+    }
 
-        let rotated_origin = self.bottom_right();
-        for point in self.points() {
-            let rotated_point = point.rotate_left() + rotated_origin;
-            rotated[rotated_point] = self[point].clone();
+    /// Create a copy of this map which has been rotated clockwise.
+    pub fn rotate_right(&self) -> Map<T> {
+        self.view().rotate_right().to_map()
+    }
+
+    /// Create a copy of this map reflected across its diagonal: `(x, y)` moves to `(y, x)`.
+    pub fn transpose(&self) -> Map<T> {
+        self.to_oriented(Orientation::transpose())
+    }
+
+    /// Create a copy of this map in the given orientation.
+    ///
+    /// See [`Map::oriented`] to avoid the copy.
+    pub fn to_oriented(&self, orientation: Orientation) -> Map<T> {
+        self.oriented(orientation).to_map()
+    }
+}
+
+impl<T: Clone> Map<T> {
+    /// Copy the part of this map within `rect`, whose corner becomes the new origin.
+    ///
+    /// Returns `None` unless `rect` lies entirely within this map.
+    pub fn crop(&self, rect: Rect) -> Option<Map<T>> {
+        self.view()
+            .window(rect.corner, rect.width, rect.height)
+            .map(|window| window.to_map())
+    }
+
+    /// Create a copy of this map surrounded by a border `n` tiles wide of `fill`.
+    pub fn pad(&self, n: usize, fill: T) -> Map<T> {
+        let width = self.width + 2 * n;
+        let mut tiles = vec![fill.clone(); width * n];
+        for row in self.rows() {
+            tiles.extend(std::iter::repeat_n(fill.clone(), n));
+            tiles.extend_from_slice(row);
+            tiles.extend(std::iter::repeat_n(fill.clone(), n));
         }
+        tiles.extend(std::iter::repeat_n(fill, width * n));
+        Map::from_tiles(width, self.height + 2 * n, tiles)
+    }
 
-        rotated
+    /// Join `right` onto the right edge of this map.
+    ///
+    /// The maps must have the same height.
+    pub fn concat_horizontal(&self, right: &Map<T>) -> Result<Map<T>, MapConversionErr> {
+        if self.height != right.height {
+            return Err(MapConversionErr::NotRectangular);
+        }
+        let mut tiles = Vec::with_capacity(self.tiles.len() + right.tiles.len());
+        for (left, right) in self.rows().zip(right.rows()) {
+            tiles.extend_from_slice(left);
+            tiles.extend_from_slice(right);
+        }
+        Ok(Map::from_tiles(
+            self.width + right.width,
+            self.height,
+            tiles,
+        ))
     }
 
-    /// Create a copy of this map which has been rotated clockwise.
-    pub fn rotate_right(&self) -> Map<T> {
-        let mut rotated = Map::new(self.height, self.width);
+    /// Join `above` onto the top edge of this map.
+    ///
+    /// The maps must have the same width.
+    pub fn concat_vertical(&self, above: &Map<T>) -> Result<Map<T>, MapConversionErr> {
+        if self.width != above.width {
+            return Err(MapConversionErr::NotRectangular);
+        }
+        let mut tiles = Vec::with_capacity(self.tiles.len() + above.tiles.len());
+        tiles.extend_from_slice(&self.tiles);
+        tiles.extend_from_slice(&above.tiles);
+        Ok(Map::from_tiles(
+            self.width,
+            self.height + above.height,
+            tiles,
+        ))
+    }
 
-        let rotated_origin = self.top_left();
-        for point in self.points() {
-            let rotated_point = point.rotate_right() + rotated_origin;
-            rotated[rotated_point] = self[point].clone();
+    /// Assemble a map from a grid of sub-maps, each placed according to its point in the grid.
+    ///
+    /// The sub-maps need not all be the same size, but every sub-map in a column of the grid
+    /// must have the same width, and every sub-map in a row the same height.
+    pub fn from_grid(grid: &Map<Map<T>>) -> Result<Map<T>, MapConversionErr> {
+        if grid.width == 0 || grid.height == 0 {
+            return Ok(Map::from_tiles(0, 0, Vec::new()));
+        }
+        let widths: Vec<usize> = grid.row(0).iter().map(Map::width).collect();
+        let heights: Vec<usize> = grid.column(0).map(Map::height).collect();
+        if grid.points().any(|point| {
+            let sub_map = &grid[point];
+            sub_map.width != widths[point.x as usize] || sub_map.height != heights[point.y as usize]
+        }) {
+            return Err(MapConversionErr::NotRectangular);
         }
 
-        rotated
+        let width = widths.iter().sum();
+        let height = heights.iter().sum();
+        let mut tiles = Vec::with_capacity(width * height);
+        for grid_row in grid.rows() {
+            for y in 0..grid_row[0].height {
+                for sub_map in grid_row {
+                    tiles.extend_from_slice(sub_map.row(y));
+                }
+            }
+        }
+        Ok(Map::from_tiles(width, height, tiles))
     }
 }
 
@@ -405,7 +592,7 @@ where
     /// its first characters are the top left.
     fn try_from(path: &std::path::Path) -> Result<Self, Self::Error> {
         <Self as TryFrom<std::fs::File>>::try_from(std::fs::File::open(path)?)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, Box::new(e)))
+            .map_err(|e| std::io::Error::other(Box::new(e)))
     }
 }
 
@@ -456,7 +643,7 @@ where
             for x in 0..self.width {
                 write!(f, "{:width$}", self.index((x, y)), width = T::DISPLAY_WIDTH)?;
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
         Ok(())
     }
@@ -535,6 +722,29 @@ where
     pub fn navigate(&self, from: Point, to: Point) -> Option<Vec<Direction>> {
         self.navigate_ctx(&(), from, to)
     }
+
+    /// Count the steps from the initial point to every tile reachable from it.
+    ///
+    /// Unreachable tiles have no distance.
+    pub fn distances_from(&self, point: Point) -> Map<Option<u32>> {
+        self.distances_from_ctx(&(), point)
+    }
+
+    /// Find the distance from each tile to its nearest source, which source that is, and how to
+    /// get there.
+    ///
+    /// See [`DistanceField`] for details.
+    pub fn distance_field(&self, sources: &[Point]) -> DistanceField {
+        self.distance_field_ctx(&(), sources)
+    }
+
+    /// Replace every tile reachable from the initial point with `fill`.
+    ///
+    /// Reachability is as for [`reachable_from`][Map::reachable_from].
+    /// Returns the number of tiles filled.
+    pub fn flood_fill(&mut self, point: Point, fill: T) -> usize {
+        self.flood_fill_ctx(&(), point, fill)
+    }
 }
 
 impl<T: Clone + ContextInto<Traversable>> Map<T> {
@@ -546,10 +756,26 @@ impl<T: Clone + ContextInto<Traversable>> Map<T> {
         &self,
         context: &<T as ContextInto<Traversable>>::Context,
         point: Point,
+        visit: F,
+    ) where
+        F: FnMut(&T, Point) -> bool,
+    {
+        self.reachable_from_wrapped(None, context, point, visit)
+    }
+
+    pub(crate) fn reachable_from_wrapped<F>(
+        &self,
+        wrap: Option<Wrap>,
+        context: &<T as ContextInto<Traversable>>::Context,
+        point: Point,
         mut visit: F,
     ) where
         F: FnMut(&T, Point) -> bool,
     {
+        let point = match self.wrap_point(wrap, point) {
+            Some(point) => point,
+            None => return,
+        };
         let mut visited = bitvec!(0; self.tiles.len());
         let mut queue = VecDeque::new();
         queue.push_back(point);
@@ -565,21 +791,128 @@ impl<T: Clone + ContextInto<Traversable>> Map<T> {
 
             visited.set(idx(point), true);
             let traversable = self[point].clone().ctx_into(context);
-            if traversable != Traversable::Obstructed {
-                if visit(&self[point], point) {
-                    break;
-                }
+            if traversable != Traversable::Obstructed && visit(&self[point], point) {
+                break;
             }
 
             if traversable == Traversable::Free {
                 for direction in Direction::iter() {
-                    let neighbor = point + direction;
-                    if !visited[idx(neighbor)] {
+                    if let Some(neighbor) = self.wrap_point(wrap, point + direction) {
+                        if !visited[idx(neighbor)] {
+                            queue.push_back(neighbor);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Replace every tile reachable from the initial point with `fill`.
+    ///
+    /// Reachability is as for [`reachable_from_ctx`][Map::reachable_from_ctx].
+    /// Returns the number of tiles filled.
+    pub fn flood_fill_ctx(
+        &mut self,
+        context: &<T as ContextInto<Traversable>>::Context,
+        point: Point,
+        fill: T,
+    ) -> usize {
+        let mut filled = Vec::new();
+        self.reachable_from_ctx(context, point, |_, point| {
+            filled.push(point);
+            false
+        });
+        for &point in &filled {
+            self[point] = fill.clone();
+        }
+        filled.len()
+    }
+
+    /// Count the steps from the initial point to every tile reachable from it.
+    ///
+    /// Unreachable tiles have no distance.
+    pub fn distances_from_ctx(
+        &self,
+        context: &<T as ContextInto<Traversable>>::Context,
+        point: Point,
+    ) -> Map<Option<u32>> {
+        self.distance_field_ctx(context, &[point]).distances
+    }
+
+    /// Find the distance from each tile to its nearest source, which source that is, and how to
+    /// get there.
+    ///
+    /// This is a breadth-first search from all sources at once. Movement follows the same rules as
+    /// [`reachable_from_ctx`][Map::reachable_from_ctx]. See [`DistanceField`] for details.
+    pub fn distance_field_ctx(
+        &self,
+        context: &<T as ContextInto<Traversable>>::Context,
+        sources: &[Point],
+    ) -> DistanceField {
+        self.distance_field_wrapped(None, context, sources)
+    }
+
+    pub(crate) fn distance_field_wrapped(
+        &self,
+        wrap: Option<Wrap>,
+        context: &<T as ContextInto<Traversable>>::Context,
+        sources: &[Point],
+    ) -> DistanceField {
+        let mut field = DistanceField {
+            distances: Map::new(self.width, self.height),
+            nearest: Map::new(self.width, self.height),
+            predecessors: Map::new(self.width, self.height),
+            wrap,
+        };
+        let traversable = |point: Point| self[point].clone().ctx_into(context);
+
+        let mut queue = VecDeque::new();
+        for (source, &point) in sources.iter().enumerate() {
+            let point = match self.wrap_point(wrap, point) {
+                Some(point) if traversable(point) != Traversable::Obstructed => point,
+                _ => continue,
+            };
+            if field.distances[point].is_some() {
+                // the same point was given as more than one source
+                field.nearest[point] = None;
+            } else {
+                field.distances[point] = Some(0);
+                field.nearest[point] = Some(source);
+                queue.push_back(point);
+            }
+        }
+
+        // every tile at a given distance is labeled before any tile at that distance is dequeued,
+        // so each tile's label is final by the time it is used to label its neighbors
+        while let Some(point) = queue.pop_front() {
+            if traversable(point) != Traversable::Free {
+                continue;
+            }
+            let distance = field.distances[point].expect("queued points have a distance; qed") + 1;
+            let nearest = field.nearest[point];
+
+            for direction in Direction::iter() {
+                let neighbor = match self.wrap_point(wrap, point + direction) {
+                    Some(neighbor) if traversable(neighbor) != Traversable::Obstructed => neighbor,
+                    _ => continue,
+                };
+                match field.distances[neighbor] {
+                    None => {
+                        field.distances[neighbor] = Some(distance);
+                        field.nearest[neighbor] = nearest;
+                        field.predecessors[neighbor] = Some(direction);
                         queue.push_back(neighbor);
                     }
+                    Some(known) if known == distance && field.nearest[neighbor] != nearest => {
+                        // equidistant from distinct sources
+                        field.nearest[neighbor] = None;
+                    }
+                    Some(_) => {}
                 }
             }
         }
+
+        field
     }
 
     /// navigate between the given points using A*
@@ -590,79 +923,237 @@ impl<T: Clone + ContextInto<Traversable>> Map<T> {
         from: Point,
         to: Point,
     ) -> Option<Vec<Direction>> {
-        let mut open_set = BinaryHeap::new();
-        open_set.push(AStarNode {
-            cost: 0,
-            position: from,
-        });
+        self.navigate_wrapped(None, context, from, to)
+    }
 
-        // key: node
-        // value: node preceding it on the cheapest known path from start
-        let mut came_from = HashMap::new();
+    pub(crate) fn navigate_wrapped(
+        &self,
+        wrap: Option<Wrap>,
+        context: &<T as ContextInto<Traversable>>::Context,
+        from: Point,
+        to: Point,
+    ) -> Option<Vec<Direction>> {
+        let from = self.wrap_point(wrap, from)?;
+        let to = self.wrap_point(wrap, to)?;
+        let path = self.weighted_search(
+            wrap,
+            from,
+            to,
+            |tile| match tile.clone().ctx_into(context) {
+                Traversable::Obstructed => None,
+                Traversable::Free | Traversable::Halt => Some(1),
+            },
+            |point| self.wrapped_manhattan(wrap, to, point) as u32,
+        )?;
+        debug_assert!(path.directions.len() as i32 >= self.wrapped_manhattan(wrap, to, from));
+        Some(path.directions)
+    }
+}
+
+/// The result of a breadth-first search from one or more sources.
+///
+/// Each of these maps has the same dimensions as the map which was searched.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DistanceField {
+    /// The number of steps from each tile's nearest source, or `None` if no source can reach it.
+    pub distances: Map<Option<u32>>,
+    /// The index into the sources of each tile's nearest source.
+    ///
+    /// This is `None` for unreachable tiles, and for tiles which are equidistant from more than
+    /// one source: a Voronoi labeling.
+    pub nearest: Map<Option<usize>>,
+    /// The step taken to enter each tile on some shortest path from a nearest source.
+    ///
+    /// This is `None` for sources and for unreachable tiles.
+    pub predecessors: Map<Option<Direction>>,
+    /// The axes along which the searched map wrapped.
+    wrap: Option<Wrap>,
+}
 
-        // gscore
-        // key: position
-        // value: cost of cheapest path from start to node
-        let mut cheapest_path_cost = HashMap::new();
-        cheapest_path_cost.insert(from, 0_u32);
+impl DistanceField {
+    /// Reconstruct a shortest path from a nearest source to the target.
+    ///
+    /// Returns the source at which the path starts, and the steps from there to the target.
+    /// Returns `None` if the target is out of bounds or unreachable. If the searched map wrapped,
+    /// the target is wrapped in the same way.
+    pub fn path_to(&self, target: Point) -> Option<(Point, Vec<Direction>)> {
+        let target = self.distances.wrap_point(self.wrap, target)?;
+        self.distances[target]?;
+
+        let mut position = target;
+        let mut path = Vec::new();
+        while let Some(direction) = self.predecessors[position] {
+            path.push(direction);
+            let (dx, dy) = direction.deltas();
+            position = self
+                .distances
+                .wrap_point(self.wrap, position + (-dx, -dy))
+                .expect("predecessors lead back to a source; qed");
+        }
+        path.reverse();
+        Some((position, path))
+    }
+}
+
+/// Summary of a connected component of a [`Map`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComponentInfo {
+    /// The number of tiles in the component.
+    pub size: usize,
+    /// The lower left corner of the component's bounding box.
+    pub min: Point,
+    /// The upper right corner of the component's bounding box, inclusive.
+    pub max: Point,
+    /// The number of tile edges which separate the component from other tiles or the map's edge.
+    ///
+    /// Only orthogonal edges count, even for components connected diagonally.
+    pub perimeter: usize,
+}
 
-        // fscore
-        // key: position
-        // value: best guess as to total cost from here to finish
-        let mut total_cost_guess = HashMap::new();
-        total_cost_guess.insert(from, (to - from).manhattan() as u32);
+impl<T> Map<T> {
+    /// Label the connected components of this map.
+    ///
+    /// Adjacent tiles belong to the same component when `same` returns true for them; it should
+    /// be symmetric. Tiles are adjacent orthogonally, and also diagonally if `diagonal` is set.
+    ///
+    /// Returns a map of the component index of each tile, and a summary of each component.
+    /// Components are numbered in order of their lowest, then leftmost, tile.
+    pub fn components<F>(&self, same: F, diagonal: bool) -> (Map<usize>, Vec<ComponentInfo>)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        const UNLABELED: usize = usize::MAX;
+
+        let mut labels = Map::new(self.width, self.height);
+        labels.for_each_mut(|label| *label = UNLABELED);
+        let mut components = Vec::new();
+        let mut stack = Vec::new();
+
+        for start in self.points() {
+            if labels[start] != UNLABELED {
+                continue;
+            }
+            let label = components.len();
+            let mut info = ComponentInfo {
+                size: 0,
+                min: start,
+                max: start,
+                perimeter: 0,
+            };
+
+            labels[start] = label;
+            stack.push(start);
+            while let Some(point) = stack.pop() {
+                info.size += 1;
+                info.min = Point::new(info.min.x.min(point.x), info.min.y.min(point.y));
+                info.max = Point::new(info.max.x.max(point.x), info.max.y.max(point.y));
+
+                let neighbors = if diagonal {
+                    Either::Left(self.adjacencies(point))
+                } else {
+                    Either::Right(self.orthogonal_adjacencies(point))
+                };
+                for neighbor in neighbors {
+                    if labels[neighbor] == UNLABELED && same(&self[point], &self[neighbor]) {
+                        labels[neighbor] = label;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+
+            components.push(info);
+        }
+
+        for point in self.points() {
+            let label = labels[point];
+            components[label].perimeter += Direction::iter()
+                .map(|direction| point + direction)
+                .filter(|&neighbor| !self.in_bounds(neighbor) || labels[neighbor] != label)
+                .count();
+        }
+
+        (labels, components)
+    }
+}
 
-        while let Some(AStarNode { cost, position }) = open_set.pop() {
+/// A path found by a weighted search, and its total cost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeightedPath {
+    /// The steps to take from the origin to reach the destination.
+    pub directions: Vec<Direction>,
+    /// The sum of the costs of each tile entered along the path.
+    pub cost: u32,
+}
+
+impl<T> Map<T> {
+    /// Find the cheapest path between two points by A*.
+    ///
+    /// `cost` gives the cost of entering a tile, or `None` if it cannot be entered. The cost of the
+    /// origin tile is never counted. `heuristic` must never overestimate the remaining cost
+    /// from a point to the destination; if it is not also consistent, tiles may be expanded more
+    /// than once.
+    fn weighted_search<C, H>(
+        &self,
+        wrap: Option<Wrap>,
+        from: Point,
+        to: Point,
+        cost: C,
+        heuristic: H,
+    ) -> Option<WeightedPath>
+    where
+        C: Fn(&T) -> Option<u32>,
+        H: Fn(Point) -> u32,
+    {
+        if !self.in_bounds(from) || !self.in_bounds(to) {
+            return None;
+        }
+        let index = |point: Point| self.point2index(point.x as usize, point.y as usize);
+
+        // cost of the cheapest known path from the origin to each tile
+        let mut cheapest = vec![u32::MAX; self.tiles.len()];
+        // step by which each tile is reached on that path, and the tile it is taken from
+        let mut came_from: Vec<Option<(Direction, usize)>> = vec![None; self.tiles.len()];
+        // prioritized by the best guess of the total cost of a path through each tile
+        let mut open_set = IndexedPriorityQueue::with_capacity(self.tiles.len());
+
+        cheapest[index(from)] = 0;
+        open_set.push_or_decrease(index(from), heuristic(from));
+
+        while let Some((idx, _)) = open_set.pop() {
+            let position = Point::from(self.index2point(idx));
             if position == to {
-                let mut current = position;
                 let mut path = Vec::new();
-                while let Some((direction, predecessor)) = came_from.remove(&current) {
-                    current = predecessor;
+                let mut current = idx;
+                while let Some((direction, predecessor)) = came_from[current] {
                     path.push(direction);
+                    current = predecessor;
                 }
-                debug_assert!(path.len() as i32 >= (to - from).manhattan());
                 path.reverse();
-                return Some(path);
+                return Some(WeightedPath {
+                    directions: path,
+                    cost: cheapest[idx],
+                });
             }
 
             for direction in Direction::iter() {
-                let neighbor = position + direction;
-                if !self.in_bounds(neighbor) {
-                    continue;
-                }
-                match self[neighbor].clone().ctx_into(context) {
-                    Traversable::Obstructed => {}
-                    Traversable::Free | Traversable::Halt => {
-                        let tentative_cheapest_path_cost = cost + 1;
-                        if tentative_cheapest_path_cost
-                            < cheapest_path_cost
-                                .get(&neighbor)
-                                .cloned()
-                                .unwrap_or(u32::MAX)
-                        {
-                            // this path to the neighbor is better than any previous one
-                            came_from.insert(neighbor, (direction, position));
-                            cheapest_path_cost.insert(neighbor, tentative_cheapest_path_cost);
-                            total_cost_guess.insert(
-                                neighbor,
-                                tentative_cheapest_path_cost + (to - neighbor).manhattan() as u32,
-                            );
-
-                            // this thing with the iterator is not very efficient, but for some weird reason BinaryHeap
-                            // doesn't have a .contains method; see
-                            // https://github.com/rust-lang/rust/issues/66724
-                            if open_set
-                                .iter()
-                                .find(|elem| elem.position == neighbor)
-                                .is_none()
-                            {
-                                open_set.push(AStarNode {
-                                    cost: tentative_cheapest_path_cost,
-                                    position: neighbor,
-                                });
-                            }
-                        }
-                    }
+                let neighbor = match self.wrap_point(wrap, position + direction) {
+                    Some(neighbor) => neighbor,
+                    None => continue,
+                };
+                let neighbor_idx = index(neighbor);
+                let step_cost = match cost(&self.tiles[neighbor_idx]) {
+                    Some(step_cost) => step_cost,
+                    None => continue,
+                };
+                let tentative_cost = cheapest[idx].saturating_add(step_cost);
+                if tentative_cost < cheapest[neighbor_idx] {
+                    // this path to the neighbor is better than any previous one
+                    cheapest[neighbor_idx] = tentative_cost;
+                    came_from[neighbor_idx] = Some((direction, idx));
+                    open_set.push_or_decrease(
+                        neighbor_idx,
+                        tentative_cost.saturating_add(heuristic(neighbor)),
+                    );
                 }
             }
         }
@@ -671,33 +1162,75 @@ impl<T: Clone + ContextInto<Traversable>> Map<T> {
     }
 }
 
-/// A* State
-// https://doc.rust-lang.org/std/collections/binary_heap/#examples
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
-struct AStarNode {
-    cost: u32,
-    position: Point,
-}
+impl<T> Map<T>
+where
+    T: Clone + ContextInto<Option<u32>, Context = ()>,
+{
+    /// Find the cheapest path between the given points using Dijkstra's algorithm.
+    ///
+    /// Each tile converts into the cost of entering it, or `None` if it cannot be entered.
+    pub fn dijkstra(&self, from: Point, to: Point) -> Option<WeightedPath> {
+        self.dijkstra_ctx(&(), from, to)
+    }
 
-// The priority queue depends on `Ord`.
-// Explicitly implement the trait so the queue becomes a min-heap
-// instead of a max-heap.
-impl Ord for AStarNode {
-    fn cmp(&self, other: &AStarNode) -> std::cmp::Ordering {
-        // Notice that the we flip the ordering on costs.
-        // In case of a tie we compare positions - this step is necessary
-        // to make implementations of `PartialEq` and `Ord` consistent.
-        other
-            .cost
-            .cmp(&self.cost)
-            .then_with(|| self.position.cmp(&other.position))
+    /// Find the cheapest path between the given points using A*.
+    ///
+    /// Each tile converts into the cost of entering it, or `None` if it cannot be entered.
+    /// `heuristic` estimates the remaining cost from a point to `to`; it must never overestimate.
+    pub fn astar<H>(&self, from: Point, to: Point, heuristic: H) -> Option<WeightedPath>
+    where
+        H: Fn(Point) -> u32,
+    {
+        self.astar_ctx(&(), from, to, heuristic)
     }
 }
 
-// `PartialOrd` needs to be implemented as well.
-impl PartialOrd for AStarNode {
-    fn partial_cmp(&self, other: &AStarNode) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+impl<T: Clone + ContextInto<Option<u32>>> Map<T> {
+    /// Find the cheapest path between the given points using Dijkstra's algorithm.
+    ///
+    /// Each tile converts into the cost of entering it, or `None` if it cannot be entered.
+    pub fn dijkstra_ctx(
+        &self,
+        context: &<T as ContextInto<Option<u32>>>::Context,
+        from: Point,
+        to: Point,
+    ) -> Option<WeightedPath> {
+        self.astar_ctx(context, from, to, |_| 0)
+    }
+
+    /// Find the cheapest path between the given points using A*.
+    ///
+    /// Each tile converts into the cost of entering it, or `None` if it cannot be entered.
+    /// `heuristic` estimates the remaining cost from a point to `to`; it must never overestimate.
+    pub fn astar_ctx<H>(
+        &self,
+        context: &<T as ContextInto<Option<u32>>>::Context,
+        from: Point,
+        to: Point,
+        heuristic: H,
+    ) -> Option<WeightedPath>
+    where
+        H: Fn(Point) -> u32,
+    {
+        self.weighted_search(
+            None,
+            from,
+            to,
+            |tile| tile.clone().ctx_into(context),
+            heuristic,
+        )
+    }
+
+    pub(crate) fn dijkstra_wrapped(
+        &self,
+        wrap: Option<Wrap>,
+        context: &<T as ContextInto<Option<u32>>>::Context,
+        from: Point,
+        to: Point,
+    ) -> Option<WeightedPath> {
+        let from = self.wrap_point(wrap, from)?;
+        let to = self.wrap_point(wrap, to)?;
+        self.weighted_search(wrap, from, to, |tile| tile.clone().ctx_into(context), |_| 0)
     }
 }
 
@@ -747,3 +1280,295 @@ impl std::iter::DoubleEndedIterator for Edge {
         Some(next)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Weighted {
+        Wall,
+        Cost(u32),
+    }
+
+    impl DisplayWidth for Weighted {
+        const DISPLAY_WIDTH: usize = 1;
+    }
+
+    impl FromStr for Weighted {
+        type Err = std::num::ParseIntError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "#" => Ok(Weighted::Wall),
+                _ => s.parse().map(Weighted::Cost),
+            }
+        }
+    }
+
+    impl From<Weighted> for Option<u32> {
+        fn from(tile: Weighted) -> Self {
+            match tile {
+                Weighted::Wall => None,
+                Weighted::Cost(cost) => Some(cost),
+            }
+        }
+    }
+
+    impl From<Weighted> for Traversable {
+        fn from(tile: Weighted) -> Self {
+            match tile {
+                Weighted::Wall => Traversable::Obstructed,
+                Weighted::Cost(_) => Traversable::Free,
+            }
+        }
+    }
+
+    fn example() -> Map<Weighted> {
+        <Map<Weighted> as TryFrom<&str>>::try_from("1111\n9#91\n1111\n####\n1111").unwrap()
+    }
+
+    #[test]
+    fn test_dijkstra_avoids_expensive_tiles() {
+        let map = example();
+        let path = map.dijkstra(Point::new(0, 4), Point::new(0, 2)).unwrap();
+        assert_eq!(path.cost, 8);
+        assert_eq!(path.directions.len(), 8);
+
+        let mut position = Point::new(0, 4);
+        let mut cost = 0;
+        for &direction in &path.directions {
+            position += direction;
+            cost += Option::<u32>::from(map[position]).unwrap();
+        }
+        assert_eq!(position, Point::new(0, 2));
+        assert_eq!(cost, path.cost);
+    }
+
+    #[test]
+    fn test_astar_agrees_with_dijkstra() {
+        let map = example();
+        let to = Point::new(3, 2);
+        for from in map.points() {
+            let dijkstra = map.dijkstra(from, to).map(|path| path.cost);
+            let astar = map
+                .astar(from, to, |point| (to - point).manhattan() as u32)
+                .map(|path| path.cost);
+            assert_eq!(dijkstra, astar, "from {:?}", from);
+        }
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let map = example();
+        assert_eq!(map.dijkstra(Point::new(0, 4), Point::new(0, 0)), None);
+        assert_eq!(map.dijkstra(Point::new(0, 4), Point::new(1, 3)), None);
+        assert_eq!(map.dijkstra(Point::new(0, 4), Point::new(9, 9)), None);
+        assert_eq!(
+            map.dijkstra(Point::new(0, 0), Point::new(0, 0)),
+            Some(WeightedPath {
+                directions: Vec::new(),
+                cost: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_navigate_ignores_costs() {
+        let map = example();
+        let path = map.navigate(Point::new(0, 4), Point::new(0, 2)).unwrap();
+        assert_eq!(path, [Direction::Down, Direction::Down]);
+        assert_eq!(map.navigate(Point::new(0, 4), Point::new(0, 0)), None);
+    }
+
+    #[test]
+    fn test_distances_from() {
+        // 1111
+        // 9#91
+        // 1111
+        // ####
+        // 1111
+        let map = example();
+        let distances = map.distances_from(Point::new(0, 4));
+        assert_eq!(distances[Point::new(0, 4)], Some(0));
+        assert_eq!(distances[Point::new(3, 4)], Some(3));
+        assert_eq!(distances[Point::new(2, 2)], Some(4));
+        assert_eq!(distances[Point::new(1, 3)], None);
+        assert_eq!(distances[Point::new(0, 0)], None);
+    }
+
+    #[test]
+    fn test_voronoi() {
+        let map = example();
+        let sources = [
+            Point::new(0, 4),
+            Point::new(3, 2),
+            Point::new(0, 0),
+            Point::new(2, 0),
+        ];
+        let field = map.distance_field(&sources);
+
+        let nearest: Vec<_> = (0..4).map(|x| field.nearest[(x, 4)]).collect();
+        assert_eq!(nearest, [Some(0), Some(0), Some(0), Some(1)]);
+        let nearest: Vec<_> = (0..4).map(|x| field.nearest[(x, 0)]).collect();
+        assert_eq!(nearest, [Some(2), None, Some(3), Some(3)]);
+        assert_eq!(field.distances[(1, 0)], Some(1));
+        assert_eq!(field.nearest[(1, 2)], Some(1));
+        assert_eq!(field.nearest[(1, 3)], None);
+        assert_eq!(field.distances[(1, 3)], None);
+    }
+
+    #[test]
+    fn test_path_to() {
+        let map = example();
+        let field = map.distance_field(&[Point::new(3, 2)]);
+        let (source, path) = field.path_to(Point::new(0, 2)).unwrap();
+        assert_eq!(source, Point::new(3, 2));
+        assert_eq!(path.len(), 3);
+        let end = path
+            .iter()
+            .fold(source, |position, &direction| position + direction);
+        assert_eq!(end, Point::new(0, 2));
+
+        assert_eq!(
+            field.path_to(Point::new(3, 2)),
+            Some((Point::new(3, 2), Vec::new()))
+        );
+        assert_eq!(field.path_to(Point::new(0, 0)), None);
+        assert_eq!(field.path_to(Point::new(-1, 0)), None);
+    }
+
+    #[test]
+    fn test_reachable_from_edge() {
+        let map = example();
+        let mut visited = 0;
+        map.reachable_from(Point::new(0, 0), |_, _| {
+            visited += 1;
+            false
+        });
+        assert_eq!(visited, 4);
+    }
+
+    #[test]
+    fn test_components() {
+        let map = example();
+        let (labels, components) = map.components(
+            |a, b| (*a == Weighted::Wall) == (*b == Weighted::Wall),
+            false,
+        );
+
+        assert_eq!(components.len(), 4);
+        assert_eq!(labels[(0, 0)], 0);
+        assert_eq!(labels[(3, 1)], 1);
+        assert_eq!(labels[(3, 4)], 2);
+        assert_eq!(labels[(1, 3)], 3);
+
+        let sizes: Vec<_> = components.iter().map(|info| info.size).collect();
+        assert_eq!(sizes, [4, 4, 11, 1]);
+        let perimeters: Vec<_> = components.iter().map(|info| info.perimeter).collect();
+        assert_eq!(perimeters, [10, 10, 18, 4]);
+        assert_eq!(components[2].min, Point::new(0, 2));
+        assert_eq!(components[2].max, Point::new(3, 4));
+    }
+
+    #[test]
+    fn test_components_diagonal() {
+        let map = <Map<Weighted> as TryFrom<&str>>::try_from("#1\n1#").unwrap();
+        let same = |a: &Weighted, b: &Weighted| a == b;
+        assert_eq!(map.components(same, false).1.len(), 4);
+
+        let (labels, components) = map.components(same, true);
+        assert_eq!(components.len(), 2);
+        assert_eq!(labels[(0, 1)], labels[(1, 0)]);
+        assert_eq!(components[0].size, 2);
+        assert_eq!(components[0].perimeter, 8);
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let mut map = example();
+        assert_eq!(map.flood_fill(Point::new(3, 3), Weighted::Cost(0)), 11);
+        assert_eq!(map[Point::new(0, 3)], Weighted::Cost(0));
+        assert_eq!(map[Point::new(1, 3)], Weighted::Wall);
+        assert_eq!(map[Point::new(0, 0)], Weighted::Cost(1));
+    }
+
+    #[test]
+    fn test_rotate_non_square() {
+        // 3 wide, 2 tall: row y=0 is [0, 1, 2], row y=1 is [3, 4, 5]
+        let map = Map::from_tiles(3, 2, (0..6).collect::<Vec<u8>>());
+
+        let left = map.rotate_left();
+        assert_eq!((left.width(), left.height()), (2, 3));
+        assert_eq!(left.tiles(), &[3, 0, 4, 1, 5, 2]);
+
+        let right = map.rotate_right();
+        assert_eq!((right.width(), right.height()), (2, 3));
+        assert_eq!(right.tiles(), &[2, 5, 1, 4, 0, 3]);
+
+        assert!(left.rotate_right() == map);
+        assert!(map.to_oriented(Orientation::rotate_left()) == left);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        // 3 wide, 2 tall: row y=0 is [0, 1, 2], row y=1 is [3, 4, 5]
+        let map = Map::from_tiles(3, 2, (0..6).collect::<Vec<u8>>());
+        assert_eq!(map.row(1), &[3, 4, 5]);
+        assert_eq!(map.rows().collect::<Vec<_>>(), vec![&[0, 1, 2], &[3, 4, 5]]);
+        assert_eq!(map.rows_top_down().next(), Some(&[3, 4, 5][..]));
+        assert_eq!(map.column(2).copied().collect::<Vec<_>>(), vec![2, 5]);
+        let columns: Vec<Vec<u8>> = map
+            .columns_top_down()
+            .map(|column| column.copied().collect())
+            .collect();
+        assert_eq!(columns, vec![vec![3, 0], vec![4, 1], vec![5, 2]]);
+
+        let transposed = map.transpose();
+        assert_eq!((transposed.width(), transposed.height()), (2, 3));
+        assert_eq!(transposed.tiles(), &[0, 3, 1, 4, 2, 5]);
+    }
+
+    #[test]
+    fn test_crop_pad_concat() {
+        let map = Map::from_tiles(3, 2, (0..6).collect::<Vec<u8>>());
+        let cropped = map.crop(Rect::new(Point::new(1, 0), 2, 2)).unwrap();
+        assert_eq!(cropped.tiles(), &[1, 2, 4, 5]);
+        assert!(map.crop(Rect::new(Point::new(2, 0), 2, 2)).is_none());
+
+        let padded = map.pad(1, 9);
+        assert_eq!((padded.width(), padded.height()), (5, 4));
+        assert_eq!(padded.row(0), &[9; 5]);
+        assert_eq!(padded.row(2), &[9, 3, 4, 5, 9]);
+        assert!(padded.crop(Rect::new(Point::new(1, 1), 3, 2)).unwrap() == map);
+
+        let wide = map.concat_horizontal(&cropped).unwrap();
+        assert_eq!(wide.row(1), &[3, 4, 5, 4, 5]);
+        let tall = map.concat_vertical(&map).unwrap();
+        assert_eq!(
+            tall.column(0).copied().collect::<Vec<_>>(),
+            vec![0, 3, 0, 3]
+        );
+        assert!(map.concat_vertical(&cropped).is_err());
+    }
+
+    #[test]
+    fn test_from_grid() {
+        let map = Map::from_tiles(3, 2, (0..6).collect::<Vec<u8>>());
+        let pieces = [
+            Rect::new(Point::new(0, 0), 1, 1),
+            Rect::new(Point::new(1, 0), 2, 1),
+            Rect::new(Point::new(0, 1), 1, 1),
+            Rect::new(Point::new(1, 1), 2, 1),
+        ];
+        let grid = Map::from_tiles(
+            2,
+            2,
+            pieces.iter().map(|&rect| map.crop(rect).unwrap()).collect(),
+        );
+        assert!(Map::from_grid(&grid).unwrap() == map);
+
+        let ragged = Map::from_tiles(2, 1, vec![map.clone(), map.transpose()]);
+        assert!(Map::from_grid(&ragged).is_err());
+    }
+}
@@ -0,0 +1,320 @@
+//! Borrowed, transformed windows onto a [`Map`].
+
+use crate::geometry::{tile::DisplayWidth, Map, Orientation, Point};
+use std::{fmt, ops::Index};
+
+/// A borrowed view of some or all of a [`Map`], possibly strided, rotated, or flipped.
+///
+/// Creating or transforming a view never copies tiles. A view has its own coordinate system,
+/// with its origin in the lower left just like a map. Each point in the view corresponds to a
+/// point in the underlying map by an affine transformation.
+///
+/// ## Entry Points
+///
+/// - [`Map::view`] views an entire map.
+/// - [`Map::oriented`] views an entire map in some [`Orientation`].
+/// - [`MapView::window`], [`MapView::step_by`], and the rotations and flips derive new views.
+/// - [`MapView::to_map`] copies a view into a new map.
+pub struct MapView<'a, T> {
+    map: &'a Map<T>,
+    /// The map point shown at the view's origin.
+    origin: Point,
+    /// The map step taken for each step along the view's x axis.
+    x_step: Point,
+    /// The map step taken for each step along the view's y axis.
+    y_step: Point,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, T> Clone for MapView<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for MapView<'a, T> {}
+
+impl<T> Map<T> {
+    /// View this entire map without transformation.
+    pub fn view(&self) -> MapView<'_, T> {
+        MapView {
+            map: self,
+            origin: Point::new(0, 0),
+            x_step: Point::new(1, 0),
+            y_step: Point::new(0, 1),
+            width: self.width(),
+            height: self.height(),
+        }
+    }
+
+    /// View this entire map in the given orientation, without copying it.
+    ///
+    /// See [`Map::to_oriented`] for an owned copy.
+    pub fn oriented(&self, orientation: Orientation) -> MapView<'_, T> {
+        self.view().oriented(orientation)
+    }
+}
+
+impl<'a, T> MapView<'a, T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The underlying map.
+    pub fn map(&self) -> &'a Map<T> {
+        self.map
+    }
+
+    pub fn in_bounds(&self, point: Point) -> bool {
+        point.x >= 0
+            && point.y >= 0
+            && (point.x as usize) < self.width
+            && (point.y as usize) < self.height
+    }
+
+    /// The point in the underlying map which this view shows at `point`.
+    ///
+    /// Returns `None` if `point` is out of the view's bounds.
+    pub fn source_point(&self, point: Point) -> Option<Point> {
+        if !self.in_bounds(point) {
+            return None;
+        }
+        Some(self.origin + self.x_step * point.x + self.y_step * point.y)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&'a T> {
+        let map = self.map;
+        self.source_point(point).map(|source| &map[source])
+    }
+
+    /// Iterate over the points of this view, in the same order as [`Map::points`].
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.width * self.height).map(move |idx| Point::from((idx % width, idx / width)))
+    }
+
+    /// Iterate over the tiles of this view, in the same order as [`MapView::points`].
+    pub fn iter(&self) -> impl 'a + Iterator<Item = &'a T> {
+        let view = *self;
+        self.points()
+            .map(move |point| view.get(point).expect("points are in bounds; qed"))
+    }
+
+    /// A rectangular window of this view, whose lower left corner is at `corner`.
+    ///
+    /// Returns `None` unless the window lies entirely within this view.
+    pub fn window(&self, corner: Point, width: usize, height: usize) -> Option<MapView<'a, T>> {
+        let fits = |start: i32, len: usize, limit: usize| {
+            start >= 0
+                && (start as usize)
+                    .checked_add(len)
+                    .is_some_and(|end| end <= limit)
+        };
+        if !fits(corner.x, width, self.width) || !fits(corner.y, height, self.height) {
+            return None;
+        }
+        Some(MapView {
+            origin: self.origin + self.x_step * corner.x + self.y_step * corner.y,
+            width,
+            height,
+            ..*self
+        })
+    }
+
+    /// Every `x`th column and `y`th row of this view, starting from the origin.
+    ///
+    /// Panics if either step is 0.
+    pub fn step_by(&self, x: usize, y: usize) -> MapView<'a, T> {
+        assert!(x > 0 && y > 0, "steps must be positive");
+        MapView {
+            x_step: self.x_step * x as i32,
+            y_step: self.y_step * y as i32,
+            width: self.width.div_ceil(x),
+            height: self.height.div_ceil(y),
+            ..*self
+        }
+    }
+
+    /// This view flipped vertically: the axis of symmetry is horizontal.
+    pub fn flip_vertical(&self) -> MapView<'a, T> {
+        MapView {
+            origin: self.origin + self.y_step * (self.height as i32 - 1),
+            y_step: self.y_step * -1,
+            ..*self
+        }
+    }
+
+    /// This view flipped horizontally: the axis of symmetry is vertical.
+    pub fn flip_horizontal(&self) -> MapView<'a, T> {
+        MapView {
+            origin: self.origin + self.x_step * (self.width as i32 - 1),
+            x_step: self.x_step * -1,
+            ..*self
+        }
+    }
+
+    /// This view rotated counter-clockwise.
+    pub fn rotate_left(&self) -> MapView<'a, T> {
+        MapView {
+            origin: self.origin + self.y_step * (self.height as i32 - 1),
+            x_step: self.y_step * -1,
+            y_step: self.x_step,
+            width: self.height,
+            height: self.width,
+            ..*self
+        }
+    }
+
+    /// This view rotated clockwise.
+    pub fn rotate_right(&self) -> MapView<'a, T> {
+        MapView {
+            origin: self.origin + self.x_step * (self.width as i32 - 1),
+            x_step: self.y_step,
+            y_step: self.x_step * -1,
+            width: self.height,
+            height: self.width,
+            ..*self
+        }
+    }
+
+    /// This view in the given orientation, relative to its current one.
+    pub fn oriented(&self, orientation: Orientation) -> MapView<'a, T> {
+        let mut view = *self;
+        if orientation.flipped() {
+            view = view.flip_vertical();
+        }
+        for _ in 0..orientation.rotations() {
+            view = view.rotate_left();
+        }
+        view
+    }
+
+    /// The orientation of this view relative to the underlying map.
+    ///
+    /// However a view was derived, its flips and rotations reduce to a single orientation;
+    /// windows and strides don't affect it.
+    pub fn orientation(&self) -> Orientation {
+        let unit = |step: Point| Point::new(step.x.signum(), step.y.signum());
+        let (x_step, y_step) = (unit(self.x_step), unit(self.y_step));
+        // a view's steps are where the inverse orientation sends the unit vectors;
+        // that's independent of the dimensions, so any square will do
+        let source_step = |orientation: Orientation, step: Point| {
+            let inverse = orientation.inverse();
+            inverse.apply(step, 2, 2) - inverse.apply(Point::new(0, 0), 2, 2)
+        };
+        Orientation::iter()
+            .find(|&orientation| {
+                source_step(orientation, Point::new(1, 0)) == x_step
+                    && source_step(orientation, Point::new(0, 1)) == y_step
+            })
+            .expect("every view's steps are the image of some orientation; qed")
+    }
+
+    /// All eight rotations and reflections of this view.
+    ///
+    /// These are in the order of [`Orientation::iter`]: the four rotations counter-clockwise of
+    /// this view, followed by the four rotations counter-clockwise of this view flipped
+    /// vertically.
+    pub fn orientations(&self) -> impl Iterator<Item = MapView<'a, T>> {
+        let view = *self;
+        Orientation::iter().map(move |orientation| view.oriented(orientation))
+    }
+
+    /// Copy this view into a new map.
+    pub fn to_map(&self) -> Map<T>
+    where
+        T: Clone,
+    {
+        Map::from_tiles(self.width, self.height, self.iter().cloned().collect())
+    }
+}
+
+impl<'a, T> Index<Point> for MapView<'a, T> {
+    type Output = T;
+
+    /// Panics if the point is out of the view's bounds.
+    fn index(&self, point: Point) -> &T {
+        self.get(point).expect("point must be in the view's bounds")
+    }
+}
+
+impl<'a, T> fmt::Display for MapView<'a, T>
+where
+    T: fmt::Display + DisplayWidth,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in (0..self.height).rev() {
+            for x in 0..self.width {
+                write!(
+                    f,
+                    "{:width$}",
+                    self[Point::from((x, y))],
+                    width = T::DISPLAY_WIDTH
+                )?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::tile::Bool;
+    use std::convert::TryFrom;
+
+    fn example() -> Map<Bool> {
+        <Map<Bool> as TryFrom<&str>>::try_from("#..\n.#.\n##.\n...").unwrap()
+    }
+
+    #[test]
+    fn test_transforms_match_owned_copies() {
+        let map = example();
+        let view = map.view();
+        assert!(view.to_map() == map);
+        assert!(view.flip_vertical().to_map() == map.flip_vertical());
+        assert!(view.flip_horizontal().to_map() == map.flip_horizontal());
+        assert_eq!(view.rotate_left().to_string(), "....\n.##.\n#.#.\n");
+        assert_eq!(view.rotate_right().to_string(), ".#.#\n.##.\n....\n");
+        assert!(view.rotate_left().rotate_right().to_map() == map);
+        assert!(view.rotate_left().rotate_left().to_map() == map.flip_vertical().flip_horizontal());
+    }
+
+    #[test]
+    fn test_window_and_stride() {
+        let map = example();
+        let view = map.view();
+        assert!(view.window(Point::new(1, 1), 3, 1).is_none());
+
+        let window = view.window(Point::new(0, 1), 2, 2).unwrap();
+        assert_eq!(window.to_string(), ".#\n##\n");
+        assert_eq!(
+            window.source_point(Point::new(1, 1)),
+            Some(Point::new(1, 2))
+        );
+        assert_eq!(window.rotate_right().to_string(), "#.\n##\n");
+
+        let strided = view.step_by(2, 1);
+        assert_eq!((strided.width(), strided.height()), (2, 4));
+        assert_eq!(strided.to_string(), "#.\n..\n#.\n..\n");
+    }
+
+    #[test]
+    fn test_orientations_are_distinct() {
+        let map = example();
+        let orientations: Vec<_> = map
+            .view()
+            .orientations()
+            .map(|view| view.to_string())
+            .collect();
+        for (idx, orientation) in orientations.iter().enumerate() {
+            assert!(!orientations[..idx].contains(orientation));
+        }
+    }
+}
@@ -1,12 +1,41 @@
+pub mod aabox;
+pub mod automaton;
+pub mod compass;
 pub mod direction;
+pub mod hex;
+pub mod interval;
 pub mod line;
 pub mod line_segment;
 pub mod map;
+pub mod map_view;
+pub mod neighbors;
+pub mod orientation;
+pub mod pattern;
 pub mod point;
+pub mod polygon;
+pub mod rect;
+pub mod render;
+pub mod sparse_map;
 pub mod tile;
 pub mod vector3;
 pub mod vector4;
+pub mod vector_n;
+pub mod wrapping;
 
+pub use aabox::AaBox;
+pub use automaton::{Automaton, Termination};
+pub use compass::Compass8;
 pub use direction::Direction;
-pub use map::{Map, MapConversionErr};
+pub use hex::{Hex, HexDirection, HexMap};
+pub use interval::{Interval, IntervalSet};
+pub use map::{ComponentInfo, DistanceField, Map, MapConversionErr, WeightedPath};
+pub use map_view::MapView;
+pub use neighbors::{Moore, OffsetTable, VonNeumann};
+pub use orientation::Orientation;
+pub use pattern::{find_pattern, mark_matches, Pattern, PatternMatch};
 pub use point::Point;
+pub use polygon::Polygon;
+pub use rect::Rect;
+pub use sparse_map::SparseMap;
+pub use vector_n::VectorN;
+pub use wrapping::{Wrap, WrappingMap};
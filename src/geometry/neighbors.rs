@@ -0,0 +1,278 @@
+//! Neighborhoods of a point, iterated without allocating.
+//!
+//! The Moore neighborhood of a point includes diagonals: `3^D - 1` points in `D` dimensions.
+//! The von Neumann neighborhood includes only the orthogonal neighbors: `2 * D` points.
+//!
+//! [`Moore`] and [`VonNeumann`] compute the neighbors of a single point on the fly. When many
+//! points' neighborhoods are needed, an [`OffsetTable`] computes the offsets once, after which
+//! [`OffsetTable::moore_around`] only has to add them.
+
+use crate::geometry::{Point, VectorN};
+use std::{iter::FusedIterator, ops::Add, slice};
+
+/// The Moore neighborhood of the origin in 2 dimensions, in row-major order from the lower left.
+pub const MOORE_2D: [Point; 8] = [
+    Point::new(-1, -1),
+    Point::new(0, -1),
+    Point::new(1, -1),
+    Point::new(-1, 0),
+    Point::new(1, 0),
+    Point::new(-1, 1),
+    Point::new(0, 1),
+    Point::new(1, 1),
+];
+
+/// The von Neumann neighborhood of the origin in 2 dimensions, in the order of
+/// [`Direction::iter`][crate::geometry::Direction::iter].
+pub const VON_NEUMANN_2D: [Point; 4] = [
+    Point::new(0, 1),
+    Point::new(0, -1),
+    Point::new(-1, 0),
+    Point::new(1, 0),
+];
+
+/// Iterator over a center point displaced by each of a slice of offsets.
+#[derive(Debug, Clone)]
+pub struct OffsetIter<'a, P, O = P> {
+    center: P,
+    offsets: slice::Iter<'a, O>,
+}
+
+impl<'a, P, O> OffsetIter<'a, P, O> {
+    pub fn new(center: P, offsets: &'a [O]) -> Self {
+        OffsetIter {
+            center,
+            offsets: offsets.iter(),
+        }
+    }
+}
+
+impl<'a, P, O> Iterator for OffsetIter<'a, P, O>
+where
+    P: Copy + Add<O, Output = P>,
+    O: Copy,
+{
+    type Item = P;
+
+    fn next(&mut self) -> Option<P> {
+        self.offsets.next().map(|&offset| self.center + offset)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
+}
+
+impl<'a, P, O> DoubleEndedIterator for OffsetIter<'a, P, O>
+where
+    P: Copy + Add<O, Output = P>,
+    O: Copy,
+{
+    fn next_back(&mut self) -> Option<P> {
+        self.offsets.next_back().map(|&offset| self.center + offset)
+    }
+}
+
+impl<'a, P, O> ExactSizeIterator for OffsetIter<'a, P, O>
+where
+    P: Copy + Add<O, Output = P>,
+    O: Copy,
+{
+}
+
+impl<'a, P, O> FusedIterator for OffsetIter<'a, P, O>
+where
+    P: Copy + Add<O, Output = P>,
+    O: Copy,
+{
+}
+
+/// Iterator over the `3^D - 1` points of the Moore neighborhood of a point.
+///
+/// Points are produced in lexicographic order: the last axis varies fastest.
+#[derive(Debug, Clone)]
+pub struct Moore<const D: usize> {
+    center: VectorN<D>,
+    offset: [i32; D],
+    remaining: usize,
+}
+
+impl<const D: usize> Moore<D> {
+    pub fn new(center: VectorN<D>) -> Self {
+        Moore {
+            center,
+            offset: [-1; D],
+            remaining: 3_usize.pow(D as u32) - 1,
+        }
+    }
+}
+
+impl<const D: usize> Iterator for Moore<D> {
+    type Item = VectorN<D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.remaining == 0 {
+                return None;
+            }
+            let current = self.offset;
+
+            // advance like an odometer: the last axis turns fastest
+            for component in self.offset.iter_mut().rev() {
+                if *component < 1 {
+                    *component += 1;
+                    break;
+                }
+                *component = -1;
+            }
+
+            if current != [0; D] {
+                self.remaining -= 1;
+                return Some(self.center + VectorN(current));
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<const D: usize> ExactSizeIterator for Moore<D> {}
+
+impl<const D: usize> FusedIterator for Moore<D> {}
+
+/// Iterator over the `2 * D` points of the von Neumann neighborhood of a point.
+///
+/// For each axis in turn, the point below the center comes before the point above it.
+#[derive(Debug, Clone)]
+pub struct VonNeumann<const D: usize> {
+    center: VectorN<D>,
+    next: usize,
+}
+
+impl<const D: usize> VonNeumann<D> {
+    pub fn new(center: VectorN<D>) -> Self {
+        VonNeumann { center, next: 0 }
+    }
+}
+
+impl<const D: usize> Iterator for VonNeumann<D> {
+    type Item = VectorN<D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= 2 * D {
+            return None;
+        }
+        let mut neighbor = self.center;
+        neighbor[self.next / 2] += if self.next.is_multiple_of(2) { -1 } else { 1 };
+        self.next += 1;
+        Some(neighbor)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = 2 * D - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<const D: usize> ExactSizeIterator for VonNeumann<D> {}
+
+impl<const D: usize> FusedIterator for VonNeumann<D> {}
+
+/// Precomputed neighborhood offsets in `D` dimensions.
+///
+/// Building the table allocates once; iterating the neighbors of a point with it never does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffsetTable<const D: usize> {
+    moore: Vec<VectorN<D>>,
+    von_neumann: Vec<VectorN<D>>,
+}
+
+impl<const D: usize> Default for OffsetTable<D> {
+    fn default() -> Self {
+        OffsetTable::new()
+    }
+}
+
+impl<const D: usize> OffsetTable<D> {
+    pub fn new() -> Self {
+        OffsetTable {
+            moore: Moore::new(VectorN::default()).collect(),
+            von_neumann: VonNeumann::new(VectorN::default()).collect(),
+        }
+    }
+
+    /// The offsets of the Moore neighborhood, in the order produced by [`Moore`].
+    pub fn moore(&self) -> &[VectorN<D>] {
+        &self.moore
+    }
+
+    /// The offsets of the von Neumann neighborhood, in the order produced by [`VonNeumann`].
+    pub fn von_neumann(&self) -> &[VectorN<D>] {
+        &self.von_neumann
+    }
+
+    /// Iterate over the Moore neighborhood of `center`.
+    pub fn moore_around(&self, center: VectorN<D>) -> OffsetIter<'_, VectorN<D>> {
+        OffsetIter::new(center, &self.moore)
+    }
+
+    /// Iterate over the von Neumann neighborhood of `center`.
+    pub fn von_neumann_around(&self, center: VectorN<D>) -> OffsetIter<'_, VectorN<D>> {
+        OffsetIter::new(center, &self.von_neumann)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::Direction;
+
+    #[test]
+    fn test_moore_matches_inclusive_range() {
+        let center = VectorN([3, -2, 0, 7]);
+        let expect: Vec<_> = VectorN::inclusive_range(center.decr(), center.incr())
+            .filter(|&point| point != center)
+            .collect();
+        let moore = Moore::new(center);
+        assert_eq!(moore.len(), 80);
+        assert_eq!(moore.collect::<Vec<_>>(), expect);
+        assert_eq!(
+            OffsetTable::<4>::new()
+                .moore_around(center)
+                .collect::<Vec<_>>(),
+            expect
+        );
+        assert_eq!(Moore::new(VectorN([])).count(), 0);
+    }
+
+    #[test]
+    fn test_von_neumann() {
+        let mut neighbors = VonNeumann::new(VectorN([1, 1, 1]));
+        assert_eq!(neighbors.len(), 6);
+        assert_eq!(neighbors.next(), Some(VectorN([0, 1, 1])));
+        assert_eq!(neighbors.next(), Some(VectorN([2, 1, 1])));
+        assert_eq!(neighbors.len(), 4);
+        assert!(neighbors.all(|point| (point - VectorN([1, 1, 1])).abs_sum() == 1));
+
+        let table = OffsetTable::<3>::new();
+        assert_eq!(table.von_neumann().len(), 6);
+        assert!(table.moore().iter().all(|offset| offset.abs_sum() > 0));
+    }
+
+    #[test]
+    fn test_2d_tables() {
+        let origin = Point::new(0, 0);
+        let moore: Vec<_> = OffsetIter::new(origin, &MOORE_2D).collect();
+        let expect: Vec<_> = Moore::new(VectorN([0, 0]))
+            .map(|VectorN([x, y])| Point::new(y, x))
+            .collect();
+        assert_eq!(moore, expect);
+
+        let von_neumann: Vec<_> = Direction::iter()
+            .map(|direction| origin + direction)
+            .collect();
+        assert_eq!(von_neumann, VON_NEUMANN_2D);
+    }
+}
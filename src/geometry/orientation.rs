@@ -0,0 +1,191 @@
+//! The eight symmetries of a rectangle: rotations by quarter turns, and reflections.
+
+use crate::geometry::Point;
+
+/// One of the eight symmetries of the dihedral group D4.
+///
+/// An orientation is a vertical flip (or not), followed by some number of counter-clockwise
+/// quarter turns. Every combination of flips and rotations reduces to exactly one orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Orientation {
+    flipped: bool,
+    /// counter-clockwise quarter turns, in `0..4`
+    rotations: u8,
+}
+
+impl Orientation {
+    /// Leave everything as it was.
+    pub const IDENTITY: Orientation = Orientation {
+        flipped: false,
+        rotations: 0,
+    };
+
+    /// Flip vertically, if `flipped`, and then rotate counter-clockwise `rotations` times.
+    pub fn new(flipped: bool, rotations: u8) -> Orientation {
+        Orientation {
+            flipped,
+            rotations: rotations % 4,
+        }
+    }
+
+    /// A single counter-clockwise quarter turn.
+    pub fn rotate_left() -> Orientation {
+        Orientation::new(false, 1)
+    }
+
+    /// A single clockwise quarter turn.
+    pub fn rotate_right() -> Orientation {
+        Orientation::new(false, 3)
+    }
+
+    /// A flip whose axis of symmetry is horizontal.
+    pub fn flip_vertical() -> Orientation {
+        Orientation::new(true, 0)
+    }
+
+    /// A flip whose axis of symmetry is vertical.
+    pub fn flip_horizontal() -> Orientation {
+        Orientation::new(true, 2)
+    }
+
+    /// A reflection across the diagonal through the origin, exchanging x and y.
+    pub fn transpose() -> Orientation {
+        Orientation::new(true, 1)
+    }
+
+    /// Does this orientation include a flip?
+    pub fn flipped(self) -> bool {
+        self.flipped
+    }
+
+    /// The number of counter-clockwise quarter turns after the flip, if any.
+    pub fn rotations(self) -> u8 {
+        self.rotations
+    }
+
+    /// Does this orientation exchange width and height?
+    pub fn transposes(self) -> bool {
+        self.rotations % 2 == 1
+    }
+
+    /// All eight orientations: the four rotations without a flip, then the four with one.
+    pub fn iter() -> impl Iterator<Item = Orientation> {
+        [false, true]
+            .iter()
+            .flat_map(|&flipped| (0..4).map(move |rotations| Orientation::new(flipped, rotations)))
+    }
+
+    /// The orientation which applies `self`, and then `next`.
+    pub fn then(self, next: Orientation) -> Orientation {
+        // a flip reverses the sense of the rotations before it
+        let rotations = if next.flipped {
+            next.rotations + 4 - self.rotations
+        } else {
+            next.rotations + self.rotations
+        };
+        Orientation::new(self.flipped != next.flipped, rotations)
+    }
+
+    /// The orientation which undoes this one.
+    pub fn inverse(self) -> Orientation {
+        if self.flipped {
+            // every reflection is its own inverse
+            self
+        } else {
+            Orientation::new(false, 4 - self.rotations)
+        }
+    }
+
+    /// The dimensions of a `width` by `height` map after applying this orientation.
+    pub fn dimensions(self, width: usize, height: usize) -> (usize, usize) {
+        if self.transposes() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Where a point in a `width` by `height` map ends up after applying this orientation.
+    ///
+    /// Points in bounds stay in bounds of the map's new dimensions.
+    pub fn apply(self, point: Point, width: usize, height: usize) -> Point {
+        let (mut width, mut height) = (width as i32, height as i32);
+        let mut point = point;
+        if self.flipped {
+            point.y = height - 1 - point.y;
+        }
+        for _ in 0..self.rotations {
+            point = Point::new(height - 1 - point.y, point.x);
+            std::mem::swap(&mut width, &mut height);
+        }
+        point
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::{tile::Bool, Map};
+    use std::convert::TryFrom;
+
+    fn example() -> Map<Bool> {
+        <Map<Bool> as TryFrom<&str>>::try_from("#..\n.#.\n##.\n...").unwrap()
+    }
+
+    #[test]
+    fn test_group_laws() {
+        for a in Orientation::iter() {
+            assert_eq!(a.then(a.inverse()), Orientation::IDENTITY);
+            assert_eq!(a.inverse().then(a), Orientation::IDENTITY);
+            assert_eq!(Orientation::IDENTITY.then(a), a);
+            for b in Orientation::iter() {
+                for c in Orientation::iter() {
+                    assert_eq!(a.then(b).then(c), a.then(b.then(c)));
+                }
+            }
+        }
+        assert_eq!(Orientation::iter().count(), 8);
+        assert_eq!(
+            Orientation::flip_vertical().then(Orientation::flip_horizontal()),
+            Orientation::new(false, 2)
+        );
+    }
+
+    #[test]
+    fn test_composition_matches_views() {
+        let map = example();
+        for a in Orientation::iter() {
+            for b in Orientation::iter() {
+                let stepwise = map.oriented(a).oriented(b).to_map();
+                assert!(stepwise == map.oriented(a.then(b)).to_map());
+            }
+        }
+    }
+
+    #[test]
+    fn test_apply_matches_views() {
+        let map = example();
+        for orientation in Orientation::iter() {
+            let oriented = map.oriented(orientation);
+            assert_eq!(
+                orientation.dimensions(map.width(), map.height()),
+                (oriented.width(), oriented.height())
+            );
+            for point in map.points() {
+                let moved = orientation.apply(point, map.width(), map.height());
+                assert_eq!(oriented.source_point(moved), Some(point));
+            }
+        }
+    }
+
+    #[test]
+    fn test_orientation_is_recoverable() {
+        let map = example();
+        for orientation in Orientation::iter() {
+            assert_eq!(map.oriented(orientation).orientation(), orientation);
+        }
+        let view = map.view().rotate_left().flip_horizontal().rotate_right();
+        assert_eq!(view.orientation(), Orientation::flip_vertical());
+        assert!(view.to_map() == map.flip_vertical());
+    }
+}
@@ -0,0 +1,193 @@
+//! Searching a [`Map`] for a two-dimensional pattern, in any orientation.
+
+use crate::geometry::{map::MapConversionErr, tile::DisplayWidth, Map, Orientation, Point};
+use std::str::FromStr;
+
+/// A rectangular pattern of tiles, any of which may be a wildcard.
+///
+/// Wildcards are `None`; they match any tile.
+#[derive(Clone)]
+pub struct Pattern<T> {
+    cells: Map<Option<T>>,
+}
+
+impl<T> Pattern<T> {
+    pub fn new(cells: Map<Option<T>>) -> Self {
+        Pattern { cells }
+    }
+
+    pub fn cells(&self) -> &Map<Option<T>> {
+        &self.cells
+    }
+
+    /// The number of cells which are not wildcards.
+    pub fn len(&self) -> usize {
+        self.cells.iter().filter(|cell| cell.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Pattern<T>
+where
+    T: Clone + DisplayWidth + FromStr,
+    <T as FromStr>::Err: 'static + std::error::Error + Send + Sync,
+{
+    /// Parse a pattern; the input should be in natural graphical order.
+    ///
+    /// Chunks consisting entirely of the `wildcard` character are wildcards. Rows shorter than
+    /// the longest row are padded with wildcards, so trailing wildcards may be omitted.
+    pub fn parse(input: &str, wildcard: char) -> Result<Self, MapConversionErr> {
+        let mut rows = Vec::new();
+        for line in input.lines() {
+            let mut row = Vec::with_capacity(line.len() / T::DISPLAY_WIDTH);
+            for chunk in T::chunks(line) {
+                if chunk.chars().all(|ch| ch == wildcard) {
+                    row.push(None);
+                } else {
+                    row.push(Some(T::from_str(&chunk).map_err(|err| {
+                        MapConversionErr::TileConversion(Box::new(err))
+                    })?));
+                }
+            }
+            rows.push(row);
+        }
+
+        let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+        for row in rows.iter_mut() {
+            row.resize(width, None);
+        }
+
+        // shift the origin
+        rows.reverse();
+
+        Ok(Pattern::new(Map::from(rows.as_slice())))
+    }
+}
+
+/// A place where a [`Pattern`] was found by [`find_pattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternMatch {
+    /// The point in the map at which the lower left corner of the oriented pattern lies.
+    pub position: Point,
+    /// The orientation of the pattern which matched.
+    pub orientation: Orientation,
+    /// The points in the map matched by cells of the pattern which are not wildcards.
+    pub cells: Vec<Point>,
+}
+
+/// Find every place where the pattern matches the map, in any of its eight orientations.
+///
+/// Matches may overlap. Orientations of a symmetric pattern which are identical to an earlier
+/// orientation are skipped, so that each placement is reported once.
+pub fn find_pattern<T: PartialEq>(map: &Map<T>, pattern: &Pattern<T>) -> Vec<PatternMatch> {
+    let mut matches = Vec::new();
+    let orientations: Vec<_> = Orientation::iter()
+        .map(|orientation| (orientation, pattern.cells.oriented(orientation)))
+        .collect();
+
+    for (idx, &(orientation, oriented)) in orientations.iter().enumerate() {
+        let duplicate = orientations[..idx].iter().any(|(_, earlier)| {
+            earlier.width() == oriented.width()
+                && earlier.height() == oriented.height()
+                && earlier.iter().eq(oriented.iter())
+        });
+        if duplicate || oriented.width() > map.width() || oriented.height() > map.height() {
+            continue;
+        }
+
+        let fixed: Vec<(Point, &T)> = oriented
+            .points()
+            .filter_map(|point| oriented[point].as_ref().map(|tile| (point, tile)))
+            .collect();
+
+        for y in 0..=map.height() - oriented.height() {
+            for x in 0..=map.width() - oriented.width() {
+                let position = Point::from((x, y));
+                if fixed
+                    .iter()
+                    .all(|&(point, tile)| map[position + point] == *tile)
+                {
+                    matches.push(PatternMatch {
+                        position,
+                        orientation,
+                        cells: fixed.iter().map(|&(point, _)| position + point).collect(),
+                    });
+                }
+            }
+        }
+    }
+
+    matches
+}
+
+/// Apply `mark` to every tile matched by any of the given matches.
+///
+/// Tiles covered by more than one match are marked only once.
+pub fn mark_matches<T, F>(map: &mut Map<T>, matches: &[PatternMatch], mut mark: F)
+where
+    F: FnMut(&mut T),
+{
+    let mut marked = Map::<bool>::new(map.width(), map.height());
+    for &point in matches.iter().flat_map(|found| found.cells.iter()) {
+        if !marked[point] {
+            marked[point] = true;
+            mark(&mut map[point]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::tile::Bool;
+    use std::convert::TryFrom;
+
+    fn map(s: &str) -> Map<Bool> {
+        <Map<Bool> as TryFrom<&str>>::try_from(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_pads_wildcards() {
+        let pattern = Pattern::<Bool>::parse("#.\n #\n#", ' ').unwrap();
+        assert_eq!(pattern.cells().width(), 2);
+        assert_eq!(pattern.cells().height(), 3);
+        assert_eq!(pattern.len(), 4);
+        assert_eq!(pattern.cells()[Point::new(1, 0)], None);
+        assert_eq!(pattern.cells()[Point::new(1, 2)], Some(Bool::False));
+    }
+
+    #[test]
+    fn test_finds_all_orientations() {
+        // an L-shaped tromino, which has four distinct rotations and whose reflections
+        // coincide with its rotations
+        let pattern = Pattern::<Bool>::parse("#\n##", ' ').unwrap();
+        let image = map("##.\n#..\n...\n.##\n..#");
+        let mut found = find_pattern(&image, &pattern);
+        found.sort_by_key(|found| (found.position.y, found.position.x));
+
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].position, Point::new(1, 0));
+        assert_eq!(found[1].position, Point::new(0, 3));
+        assert!(found.iter().all(|found| !found.orientation.flipped()));
+    }
+
+    #[test]
+    fn test_overlapping_matches_and_marking() {
+        let pattern = Pattern::<Bool>::parse("##", ' ').unwrap();
+        let mut image = map("###");
+        let found = find_pattern(&image, &pattern);
+        // the pattern is symmetric under half its orientations; the vertical ones don't fit
+        assert_eq!(found.len(), 2);
+
+        let mut marks = 0;
+        mark_matches(&mut image, &found, |tile| {
+            marks += 1;
+            *tile = Bool::False;
+        });
+        assert_eq!(marks, 3);
+        assert_eq!(image.to_string(), "...\n");
+    }
+}
@@ -1,5 +1,8 @@
-use crate::geometry::{line_segment::LineSegment, Direction};
-use itertools::Itertools;
+use crate::geometry::{
+    line_segment::LineSegment,
+    neighbors::{OffsetIter, MOORE_2D},
+    Compass8, Direction,
+};
 use std::{
     convert::TryFrom,
     ops::{Add, AddAssign, Div, Mul, Sub},
@@ -71,6 +74,26 @@ impl Point {
     pub fn rotate_left(&self) -> Point {
         Point::new(-self.y, self.x)
     }
+
+    /// Rotate this point clockwise around the origin by `quarter_turns` quarter turns.
+    ///
+    /// Negative values rotate counterclockwise. Any number of turns takes constant time.
+    ///
+    /// ```
+    /// # use aoc2020::geometry::Point;
+    /// let point = Point::new(2, 1);
+    /// assert_eq!(point.rotate(1), point.rotate_right());
+    /// assert_eq!(point.rotate(-1), point.rotate_left());
+    /// assert_eq!(point.rotate(6), Point::new(-2, -1));
+    /// ```
+    pub fn rotate(&self, quarter_turns: i32) -> Point {
+        match quarter_turns.rem_euclid(4) {
+            0 => *self,
+            1 => self.rotate_right(),
+            2 => Point::new(-self.x, -self.y),
+            _ => self.rotate_left(),
+        }
+    }
 }
 
 impl From<(usize, usize)> for Point {
@@ -128,6 +151,21 @@ impl Add<Direction> for Point {
     }
 }
 
+impl AddAssign<Compass8> for Point {
+    fn add_assign(&mut self, compass: Compass8) {
+        *self += compass.deltas();
+    }
+}
+
+impl Add<Compass8> for Point {
+    type Output = Point;
+
+    fn add(mut self, compass: Compass8) -> Point {
+        self += compass;
+        self
+    }
+}
+
 impl AddAssign<LineSegment> for Point {
     fn add_assign(
         &mut self,
@@ -185,6 +223,41 @@ impl Div<i32> for Point {
     }
 }
 
+/// Iterator over the points of an inclusive range, row by row from the lower left.
+#[derive(Debug, Clone)]
+pub struct PointRange {
+    min: Point,
+    max: Point,
+    next: Option<Point>,
+}
+
+impl PointRange {
+    pub fn new(min: Point, max: Point) -> PointRange {
+        let empty = min.x > max.x || min.y > max.y;
+        PointRange {
+            min,
+            max,
+            next: if empty { None } else { Some(min) },
+        }
+    }
+}
+
+impl Iterator for PointRange {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        let current = self.next?;
+        self.next = if current.x < self.max.x {
+            Some(Point::new(current.x + 1, current.y))
+        } else if current.y < self.max.y {
+            Some(Point::new(self.min.x, current.y + 1))
+        } else {
+            None
+        };
+        Some(current)
+    }
+}
+
 pub trait PointTrait: Copy + Eq {
     /// Numeric type backing this point
     type N;
@@ -198,23 +271,21 @@ pub trait PointTrait: Copy + Eq {
     /// Increase all components of this point by 1.
     fn incr(self) -> Self;
 
+    /// Iterator over the points of an inclusive range.
+    type InclusiveRange: Iterator<Item = Self>;
+
+    /// Iterator over the points adjacent to a point.
+    type Adjacent: ExactSizeIterator<Item = Self>;
+
     /// Generate all points inclusively bounded by `min` and `max`.
-    fn inclusive_range(min: Self, max: Self) -> Box<dyn Iterator<Item = Self>>;
+    fn inclusive_range(min: Self, max: Self) -> Self::InclusiveRange;
 
     /// Iterate over points adjacent to this point.
     ///
-    /// This includes diagonals, and excludes the center.
-    ///
-    /// The implementation should always return a constant number of items, even if
-    /// for simplicity it does not implement `ExactSizeIterator`.
-    fn adjacent(self) -> Box<dyn Iterator<Item = Self>>
-    where
-        Self: 'static,
-    {
-        Box::new(
-            Self::inclusive_range(self.decr(), self.incr()).filter(move |&point| point != self),
-        )
-    }
+    /// For square grids, this includes diagonals, and excludes the center. It always returns a
+    /// constant number of items, and should not allocate: it is typically called for every point
+    /// of a simulation, every generation.
+    fn adjacent(self) -> Self::Adjacent;
 
     /// Return the boundary minimum between `self` and `other`.
     ///
@@ -247,12 +318,15 @@ impl PointTrait for Point {
         Point::new(self.x + 1, self.y + 1)
     }
 
-    fn inclusive_range(min: Self, max: Self) -> Box<dyn Iterator<Item = Self>> {
-        Box::new(
-            (min.y..=max.y)
-                .cartesian_product(min.x..=max.x)
-                .map(|(y, x)| Point::new(x, y)),
-        )
+    type InclusiveRange = PointRange;
+    type Adjacent = OffsetIter<'static, Point>;
+
+    fn inclusive_range(min: Self, max: Self) -> Self::InclusiveRange {
+        PointRange::new(min, max)
+    }
+
+    fn adjacent(self) -> Self::Adjacent {
+        OffsetIter::new(self, &MOORE_2D)
     }
 
     fn boundary_min(self, other: Self) -> Self {
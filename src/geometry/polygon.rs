@@ -0,0 +1,280 @@
+//! Simple polygons on the integer lattice.
+//!
+//! A polygon is usually traced as a closed path: see [`Polygon::from_path`] and
+//! [`Polygon::from_segments`]. Measurements are computed and reported in `i128`, which holds them
+//! exactly for any polygon whose vertices fit in a [`Point`].
+
+use crate::geometry::{
+    line::{cross, delta, Line},
+    line_segment::{follow, LineSegment},
+    Point,
+};
+use num::{integer::gcd, rational::Ratio};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("line {0} does not start where the previous line ends")]
+    Disconnected(usize),
+    #[error("path does not end where it starts")]
+    NotClosed,
+}
+
+/// The direction in which a polygon's vertices go around it, with `+y` up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Winding {
+    Counterclockwise,
+    Clockwise,
+    /// The polygon encloses no area.
+    Degenerate,
+}
+
+/// Where a point lies relative to a polygon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// A polygon, described by its vertices in order.
+///
+/// The last vertex is implicitly joined to the first. Most measurements assume that the polygon
+/// is simple: that its edges meet only at shared vertices.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    /// Create a polygon from its vertices.
+    ///
+    /// If the last vertex repeats the first, the repetition is dropped.
+    pub fn new(mut vertices: Vec<Point>) -> Polygon {
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        Polygon { vertices }
+    }
+
+    /// Create a polygon from a closed path of lines, each starting where the last one ended.
+    pub fn from_path(path: &[Line]) -> Result<Polygon, Error> {
+        for (idx, pair) in path.windows(2).enumerate() {
+            if pair[0].to != pair[1].from {
+                return Err(Error::Disconnected(idx + 1));
+            }
+        }
+        match (path.first(), path.last()) {
+            (Some(first), Some(last)) if first.from != last.to => Err(Error::NotClosed),
+            _ => Ok(Polygon::new(path.iter().map(|line| line.from).collect())),
+        }
+    }
+
+    /// Create a polygon by tracing a closed path from the origin.
+    pub fn from_segments(segments: &[LineSegment]) -> Result<Polygon, Error> {
+        Polygon::from_path(&follow(segments))
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    /// Iterate over the edges of this polygon, including the one closing it.
+    pub fn edges(&self) -> impl '_ + Iterator<Item = Line> {
+        let n = self.vertices.len();
+        (0..n).map(move |idx| Line::new(self.vertices[idx], self.vertices[(idx + 1) % n]))
+    }
+
+    /// Twice the signed area of this polygon, by the shoelace formula.
+    ///
+    /// This is always an integer. It is positive when the vertices run counterclockwise.
+    pub fn twice_signed_area(&self) -> i128 {
+        let origin = match self.vertices.first() {
+            Some(&origin) => origin,
+            None => return 0,
+        };
+        // measuring from a vertex keeps the products small
+        self.edges()
+            .map(|edge| cross(delta(origin, edge.from), delta(origin, edge.to)))
+            .sum()
+    }
+
+    /// The signed area of this polygon; positive when the vertices run counterclockwise.
+    pub fn signed_area(&self) -> Ratio<i128> {
+        Ratio::new(self.twice_signed_area(), 2)
+    }
+
+    pub fn area(&self) -> Ratio<i128> {
+        Ratio::new(self.twice_signed_area().abs(), 2)
+    }
+
+    pub fn winding(&self) -> Winding {
+        match self.twice_signed_area() {
+            area if area > 0 => Winding::Counterclockwise,
+            area if area < 0 => Winding::Clockwise,
+            _ => Winding::Degenerate,
+        }
+    }
+
+    /// The number of lattice points on the boundary of this polygon.
+    pub fn boundary_points(&self) -> i128 {
+        self.edges()
+            .map(|edge| {
+                let (dx, dy) = delta(edge.from, edge.to);
+                gcd(dx, dy)
+            })
+            .sum()
+    }
+
+    /// The number of lattice points strictly inside this polygon, by Pick's theorem.
+    ///
+    /// This requires the polygon to be simple. A degenerate polygon has no interior.
+    pub fn interior_points(&self) -> i128 {
+        if self.winding() == Winding::Degenerate {
+            return 0;
+        }
+        // A = I + B/2 - 1
+        (self.twice_signed_area().abs() - self.boundary_points() + 2) / 2
+    }
+
+    /// How many times the boundary of this polygon winds counterclockwise around `point`.
+    ///
+    /// Points on the boundary have an unspecified winding number.
+    pub fn winding_number(&self, point: Point) -> i32 {
+        let mut winding = 0;
+        for edge in self.edges() {
+            let side = cross(delta(edge.from, edge.to), delta(edge.from, point));
+            if edge.from.y <= point.y {
+                if edge.to.y > point.y && side > 0 {
+                    winding += 1;
+                }
+            } else if edge.to.y <= point.y && side < 0 {
+                winding -= 1;
+            }
+        }
+        winding
+    }
+
+    /// Locate `point` relative to this polygon.
+    ///
+    /// Points with a nonzero winding number are inside, so a self-intersecting polygon's
+    /// overlapping regions count as inside.
+    pub fn locate(&self, point: Point) -> Location {
+        if self.edges().any(|edge| edge.contains(point)) {
+            Location::Boundary
+        } else if self.winding_number(point) != 0 {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+
+    /// Is `point` inside this polygon or on its boundary?
+    pub fn contains(&self, point: Point) -> bool {
+        self.locate(point) != Location::Outside
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn trace(path: &str) -> Result<Polygon, Error> {
+        let segments: Vec<LineSegment> = path.split(',').map(|s| s.parse().unwrap()).collect();
+        Polygon::from_segments(&segments)
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let rect = trace("R4,U3,L4,D3").unwrap();
+        assert_eq!(rect.vertices().len(), 4);
+        assert_eq!(rect.area(), Ratio::from_integer(12));
+        assert_eq!(rect.winding(), Winding::Counterclockwise);
+        assert_eq!(rect.boundary_points(), 14);
+        assert_eq!(rect.interior_points(), 6);
+
+        assert_eq!(rect.locate(Point::new(2, 1)), Location::Inside);
+        assert_eq!(rect.locate(Point::new(4, 2)), Location::Boundary);
+        assert_eq!(rect.locate(Point::new(5, 2)), Location::Outside);
+        assert_eq!(rect.winding_number(Point::new(1, 1)), 1);
+
+        let reversed = trace("U3,R4,D3,L4").unwrap();
+        assert_eq!(reversed.winding(), Winding::Clockwise);
+        assert_eq!(reversed.signed_area(), Ratio::from_integer(-12));
+        assert_eq!(reversed.winding_number(Point::new(1, 1)), -1);
+    }
+
+    #[test]
+    fn test_pick_matches_counting() {
+        // an L shape, and a triangle with a half-integer area
+        for polygon in [
+            trace("R6,U2,L3,U4,L3,D6").unwrap(),
+            Polygon::new(vec![Point::new(0, 0), Point::new(5, 2), Point::new(1, 4)]),
+        ]
+        .iter()
+        {
+            let (mut interior, mut boundary) = (0, 0);
+            for x in -1..8 {
+                for y in -1..8 {
+                    match polygon.locate(Point::new(x, y)) {
+                        Location::Inside => interior += 1,
+                        Location::Boundary => boundary += 1,
+                        Location::Outside => {}
+                    }
+                }
+            }
+            assert_eq!(polygon.interior_points(), interior);
+            assert_eq!(polygon.boundary_points(), boundary);
+        }
+    }
+
+    #[test]
+    fn test_degenerate_has_no_interior() {
+        for polygon in [
+            Polygon::default(),
+            Polygon::new(vec![Point::new(3, 3)]),
+            Polygon::new(vec![Point::new(0, 0), Point::new(4, 0)]),
+            Polygon::new(vec![Point::new(0, 0), Point::new(2, 2), Point::new(4, 4)]),
+        ]
+        .iter()
+        {
+            assert_eq!(polygon.winding(), Winding::Degenerate);
+            assert_eq!(polygon.interior_points(), 0);
+        }
+    }
+
+    #[test]
+    fn test_large_and_malformed() {
+        let big = 2_000_000_000;
+        let square = Polygon::new(vec![
+            Point::new(-big / 2, -big / 2),
+            Point::new(big / 2, -big / 2),
+            Point::new(big / 2, big / 2),
+            Point::new(-big / 2, big / 2),
+            Point::new(-big / 2, -big / 2),
+        ]);
+        assert_eq!(square.vertices().len(), 4);
+        let big = big as i128;
+        assert_eq!(square.area(), Ratio::from_integer(big * big));
+        assert_eq!(square.interior_points(), (big - 1) * (big - 1));
+
+        // spanning the whole range of a Point
+        let (min, max) = (i32::MIN, i32::MAX);
+        let huge = Polygon::new(vec![
+            Point::new(min, min),
+            Point::new(max, min),
+            Point::new(max, max),
+            Point::new(min, max),
+        ]);
+        let side = max as i128 - min as i128;
+        assert_eq!(huge.twice_signed_area(), 2 * side * side);
+        assert_eq!(huge.boundary_points(), 4 * side);
+        assert_eq!(huge.interior_points(), (side - 1) * (side - 1));
+
+        assert_eq!(trace("R4,U3,L4"), Err(Error::NotClosed));
+        let gap = vec![
+            Line::new(Point::new(0, 0), Point::new(1, 0)),
+            Line::new(Point::new(2, 0), Point::new(0, 0)),
+        ];
+        assert_eq!(Polygon::from_path(&gap), Err(Error::Disconnected(1)));
+    }
+}
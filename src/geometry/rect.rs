@@ -0,0 +1,69 @@
+use crate::geometry::Point;
+
+/// An axis-aligned rectangle of points, described by its lower left corner and its dimensions.
+///
+/// A rectangle with no width or no height is empty: it contains no points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rect {
+    pub corner: Point,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub fn new(corner: Point, width: usize, height: usize) -> Rect {
+        Rect {
+            corner,
+            width,
+            height,
+        }
+    }
+
+    /// The smallest rectangle which contains both points.
+    pub fn from_corners(a: Point, b: Point) -> Rect {
+        let corner = Point::new(a.x.min(b.x), a.y.min(b.y));
+        Rect {
+            corner,
+            width: (a.x.max(b.x) - corner.x) as usize + 1,
+            height: (a.y.max(b.y) - corner.y) as usize + 1,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        let offset = point - self.corner;
+        offset.x >= 0
+            && offset.y >= 0
+            && (offset.x as usize) < self.width
+            && (offset.y as usize) < self.height
+    }
+
+    /// Iterate over the points of this rectangle, row by row from the lower left.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let Rect {
+            corner,
+            width,
+            height,
+        } = *self;
+        (0..width * height).map(move |idx| corner + Point::from((idx % width, idx / width)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_corners() {
+        let rect = Rect::from_corners(Point::new(3, -1), Point::new(1, 2));
+        assert_eq!(rect, Rect::new(Point::new(1, -1), 3, 4));
+        assert!(rect.contains(Point::new(3, 2)));
+        assert!(!rect.contains(Point::new(4, 2)));
+        assert_eq!(rect.points().count(), 12);
+        assert_eq!(rect.points().next(), Some(Point::new(1, -1)));
+        assert!(Rect::new(Point::new(0, 0), 0, 5).is_empty());
+    }
+}
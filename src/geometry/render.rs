@@ -0,0 +1,390 @@
+//! Rendering maps to image files.
+//!
+//! Tiles are mapped to colors through the [`Color`] trait, or through an arbitrary function.
+//! The resulting [`Image`] can be written as a PPM, which needs no dependencies, or as a PNG.
+//! Sequences of frames, such as the generations of an [`Automaton`][super::Automaton], can be
+//! written as an animated GIF with [`Animation`].
+//!
+//! Nothing here needs a display: everything is written to a file or other [`Write`]r.
+
+use crate::geometry::{tile::Bool, Map};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// A 24-bit color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb::new(0, 0, 0);
+    pub const WHITE: Rgb = Rgb::new(255, 255, 255);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Rgb {
+        Rgb { r, g, b }
+    }
+}
+
+impl From<(u8, u8, u8)> for Rgb {
+    fn from((r, g, b): (u8, u8, u8)) -> Self {
+        Rgb::new(r, g, b)
+    }
+}
+
+/// A tile which knows how it should be drawn.
+pub trait Color {
+    fn color(&self) -> Rgb;
+}
+
+impl Color for Rgb {
+    fn color(&self) -> Rgb {
+        *self
+    }
+}
+
+impl Color for bool {
+    fn color(&self) -> Rgb {
+        if *self {
+            Rgb::WHITE
+        } else {
+            Rgb::BLACK
+        }
+    }
+}
+
+impl Color for Bool {
+    fn color(&self) -> Rgb {
+        bool::from(*self).color()
+    }
+}
+
+/// An image format, as chosen by a file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ppm,
+    Png,
+    Gif,
+}
+
+impl Format {
+    /// Choose a format by the extension of `path`.
+    pub fn from_path(path: &Path) -> Result<Format, Error> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("ppm") => Ok(Format::Ppm),
+            Some("png") => Ok(Format::Png),
+            Some("gif") => Ok(Format::Gif),
+            _ => Err(Error::UnknownFormat(path.to_owned())),
+        }
+    }
+}
+
+/// A rectangular grid of pixels.
+///
+/// Unlike a [`Map`], an image's origin is at its top left, and rows proceed downwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Create an image filled with a single color.
+    pub fn new(width: usize, height: usize, background: Rgb) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    /// Draw a map, with each tile occupying a square of `scale` pixels on a side.
+    pub fn from_map<T: Color>(map: &Map<T>, scale: usize) -> Image {
+        Image::from_map_with(map, scale, Color::color)
+    }
+
+    /// Draw a map, choosing the color of each tile with `color`.
+    ///
+    /// Each tile occupies a square of `scale` pixels on a side.
+    pub fn from_map_with<T, F>(map: &Map<T>, scale: usize, color: F) -> Image
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        let mut image = Image::new(map.width() * scale, map.height() * scale, Rgb::BLACK);
+        map.for_each_point(|tile, point| {
+            let column = point.x as usize;
+            // the map's origin is in the lower left
+            let row = map.height() - 1 - point.y as usize;
+            image.fill_rect(column * scale, row * scale, scale, scale, color(tile));
+        });
+        image
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The pixels of this image, row by row from the top left.
+    pub fn pixels(&self) -> &[Rgb] {
+        &self.pixels
+    }
+
+    /// The color of the pixel `x` pixels from the left and `y` pixels from the top.
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb> {
+        if x < self.width && y < self.height {
+            Some(self.pixels[x + y * self.width])
+        } else {
+            None
+        }
+    }
+
+    /// Set the color of a pixel; pixels out of bounds are ignored.
+    pub fn set(&mut self, x: usize, y: usize, color: Rgb) {
+        if x < self.width && y < self.height {
+            self.pixels[x + y * self.width] = color;
+        }
+    }
+
+    /// Fill a rectangle whose top left corner is at `(x, y)`.
+    ///
+    /// The rectangle is clipped to the bounds of the image.
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Rgb) {
+        let right = x.saturating_add(width).min(self.width);
+        let bottom = y.saturating_add(height).min(self.height);
+        for row in y.min(bottom)..bottom {
+            let start = row * self.width;
+            for pixel in &mut self.pixels[start + x.min(right)..start + right] {
+                *pixel = color;
+            }
+        }
+    }
+
+    fn rgb_bytes(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|pixel| [pixel.r, pixel.g, pixel.b])
+            .collect()
+    }
+
+    /// Write this image in binary PPM (`P6`) format.
+    pub fn write_ppm<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.rgb_bytes())?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Write this image in PNG format.
+    pub fn write_png<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let (width, height) = self.dimensions_within(u32::MAX as usize)?;
+        let mut encoder = png::Encoder::new(writer, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.rgb_bytes())?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// Write this image to a file, choosing the format by its extension.
+    ///
+    /// A `.gif` is written as an animation of a single frame.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let format = Format::from_path(path)?;
+        let writer = BufWriter::new(File::create(path)?);
+        match format {
+            Format::Ppm => self.write_ppm(writer),
+            Format::Png => self.write_png(writer),
+            Format::Gif => {
+                let mut animation = Animation::new(writer, self.width, self.height, 0)?;
+                animation.push(self)?;
+                animation.finish().map(drop)
+            }
+        }
+    }
+
+    fn dimensions_within(&self, limit: usize) -> Result<(usize, usize), Error> {
+        if self.width > limit || self.height > limit {
+            return Err(Error::TooLarge {
+                width: self.width,
+                height: self.height,
+            });
+        }
+        Ok((self.width, self.height))
+    }
+}
+
+/// An animated GIF, written one frame at a time.
+///
+/// Each frame is encoded with an exact palette when it has at most 256 distinct colors;
+/// otherwise its colors are quantized.
+pub struct Animation<W: Write> {
+    encoder: gif::Encoder<W>,
+    width: usize,
+    height: usize,
+    delay: u16,
+}
+
+impl Animation<BufWriter<File>> {
+    /// Create an animation file; each frame is shown for `delay` hundredths of a second.
+    pub fn create(
+        path: &Path,
+        width: usize,
+        height: usize,
+        delay: u16,
+    ) -> Result<Animation<BufWriter<File>>, Error> {
+        Animation::new(BufWriter::new(File::create(path)?), width, height, delay)
+    }
+}
+
+impl<W: Write> Animation<W> {
+    /// Start an animation which loops forever; each frame is shown for `delay` hundredths
+    /// of a second.
+    pub fn new(writer: W, width: usize, height: usize, delay: u16) -> Result<Self, Error> {
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(Error::TooLarge { width, height });
+        }
+        let mut encoder = gif::Encoder::new(writer, width as u16, height as u16, &[])?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        Ok(Animation {
+            encoder,
+            width,
+            height,
+            delay,
+        })
+    }
+
+    /// Append a frame, which must have the dimensions of the animation.
+    pub fn push(&mut self, image: &Image) -> Result<(), Error> {
+        if (image.width, image.height) != (self.width, self.height) {
+            return Err(Error::FrameSize {
+                expected: (self.width, self.height),
+                found: (image.width, image.height),
+            });
+        }
+        let (width, height) = (self.width as u16, self.height as u16);
+
+        let mut palette: Vec<Rgb> = Vec::new();
+        let mut indices = Vec::with_capacity(image.pixels.len());
+        for &pixel in &image.pixels {
+            // maps rarely have more than a handful of colors, so a linear search is fine
+            let idx = match palette.iter().position(|&color| color == pixel) {
+                Some(idx) => idx,
+                None => {
+                    palette.push(pixel);
+                    palette.len() - 1
+                }
+            };
+            if idx > u8::MAX as usize {
+                break;
+            }
+            indices.push(idx as u8);
+        }
+
+        let mut frame = if palette.len() <= 256 {
+            let palette: Vec<u8> = palette
+                .iter()
+                .flat_map(|color| [color.r, color.g, color.b])
+                .collect();
+            gif::Frame::from_palette_pixels(width, height, indices, palette, None)
+        } else {
+            gif::Frame::from_rgb_speed(width, height, &image.rgb_bytes(), 10)
+        };
+        frame.delay = self.delay;
+        self.encoder.write_frame(&frame)?;
+        Ok(())
+    }
+
+    /// Finish the animation, returning the underlying writer.
+    pub fn finish(self) -> Result<W, Error> {
+        let mut writer = self.encoder.into_inner()?;
+        writer.flush()?;
+        Ok(writer)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("writing image")]
+    Io(#[from] std::io::Error),
+    #[error("encoding png")]
+    Png(#[from] png::EncodingError),
+    #[error("encoding gif")]
+    Gif(#[from] gif::EncodingError),
+    #[error("no image format is known for {0:?}")]
+    UnknownFormat(PathBuf),
+    #[error("an image of {width}x{height} pixels is too large for the format")]
+    TooLarge { width: usize, height: usize },
+    #[error("frame is {found:?} pixels, but the animation is {expected:?}")]
+    FrameSize {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::convert::TryFrom;
+
+    fn example() -> Image {
+        let map = <Map<Bool> as TryFrom<&str>>::try_from("#.\n..\n.#").unwrap();
+        Image::from_map(&map, 2)
+    }
+
+    #[test]
+    fn test_from_map_puts_the_origin_at_the_bottom() {
+        let image = example();
+        assert_eq!((image.width(), image.height()), (4, 6));
+        assert_eq!(image.get(0, 0), Some(Rgb::WHITE));
+        assert_eq!(image.get(1, 1), Some(Rgb::WHITE));
+        assert_eq!(image.get(2, 0), Some(Rgb::BLACK));
+        assert_eq!(image.get(3, 5), Some(Rgb::WHITE));
+        assert_eq!(image.get(4, 0), None);
+    }
+
+    #[test]
+    fn test_ppm() {
+        let mut out = Vec::new();
+        example().write_ppm(&mut out).unwrap();
+        let header = b"P6\n4 6\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        assert_eq!(out.len(), header.len() + 4 * 6 * 3);
+        assert_eq!(&out[header.len()..header.len() + 6], &[255; 6]);
+    }
+
+    #[test]
+    fn test_png_and_gif_signatures() {
+        let mut png = Vec::new();
+        example().write_png(&mut png).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+        let mut animation = Animation::new(Vec::new(), 4, 6, 10).unwrap();
+        animation.push(&example()).unwrap();
+        animation
+            .push(&Image::new(4, 6, Rgb::new(0, 0, 255)))
+            .unwrap();
+        assert!(matches!(
+            animation.push(&Image::new(1, 1, Rgb::BLACK)),
+            Err(Error::FrameSize { .. })
+        ));
+        let gif = animation.finish().unwrap();
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(gif.last(), Some(&0x3b));
+    }
+}
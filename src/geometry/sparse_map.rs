@@ -0,0 +1,421 @@
+//! An unbounded grid which stores only the tiles which have been written.
+
+use crate::geometry::{
+    map::MapConversionErr,
+    point::PointTrait,
+    tile::{Bool, DisplayWidth},
+    Direction, Map, Point,
+};
+use std::{
+    collections::HashMap,
+    convert::{TryFrom, TryInto},
+    fmt,
+    hash::Hash,
+    iter::FromIterator,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+/// A SparseMap keeps track of tiles at arbitrary points, including negative ones.
+///
+/// Unlike [`Map`], it has no fixed size: writing to a point grows it, and its bounding box is
+/// tracked automatically. Points which have never been written hold a default tile.
+///
+/// It is generic over the point type, so it works just as well for higher-dimensional spaces.
+/// Rendering and parsing are available for 2d maps, by way of a dense [`Map`].
+#[derive(Clone, Debug)]
+pub struct SparseMap<P, T> {
+    tiles: HashMap<P, T>,
+    /// The tile at every point which has not been written.
+    default: T,
+    /// The inclusive bounding box of the stored points, or `None` if there are none.
+    ///
+    /// When `stale` is set, this contains the stored points but may not be tight.
+    bounds: Option<(P, P)>,
+    stale: bool,
+}
+
+impl<P, T: Default> Default for SparseMap<P, T> {
+    fn default() -> Self {
+        SparseMap::with_default(T::default())
+    }
+}
+
+impl<P, T> SparseMap<P, T> {
+    /// Create an empty map whose unwritten points hold `default`.
+    pub fn with_default(default: T) -> Self {
+        SparseMap {
+            tiles: HashMap::new(),
+            default,
+            bounds: None,
+            stale: false,
+        }
+    }
+
+    /// The number of stored tiles.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// The tile held by every point which has not been written.
+    pub fn default_tile(&self) -> &T {
+        &self.default
+    }
+}
+
+impl<P, T> SparseMap<P, T>
+where
+    P: PointTrait + Hash,
+{
+    /// The tile stored at `point`, if any.
+    pub fn get(&self, point: P) -> Option<&T> {
+        self.tiles.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: P) -> Option<&mut T> {
+        self.tiles.get_mut(&point)
+    }
+
+    /// Is a tile stored at `point`?
+    pub fn contains(&self, point: P) -> bool {
+        self.tiles.contains_key(&point)
+    }
+
+    /// Store a tile, returning the tile previously stored there.
+    pub fn insert(&mut self, point: P, tile: T) -> Option<T> {
+        self.grow_bounds(point);
+        self.tiles.insert(point, tile)
+    }
+
+    /// Remove the tile at `point`, returning it.
+    ///
+    /// Removing a tile from the edge of the bounding box marks the bounds as stale, so that
+    /// removal always takes constant time; see [`bounds`][Self::bounds].
+    pub fn remove(&mut self, point: P) -> Option<T> {
+        let removed = self.tiles.remove(&point)?;
+        let interior = self.bounds.is_some_and(|(min, max)| {
+            min.boundary_min(point.decr()) == min && max.boundary_max(point.incr()) == max
+        });
+        if !interior {
+            self.stale = true;
+        }
+        Some(removed)
+    }
+
+    /// Keep only the tiles for which `keep` returns true.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(P, &mut T) -> bool,
+    {
+        self.tiles.retain(|&point, tile| keep(point, tile));
+        self.bounds = self.tight_bounds();
+        self.stale = false;
+    }
+
+    /// The inclusive bounding box `(min, max)` of the stored points.
+    ///
+    /// Returns `None` if no tiles are stored. After a tile is removed from the edge of the
+    /// bounding box, this takes time linear in the number of tiles until the bounds are refreshed
+    /// by [`shrink_bounds`][Self::shrink_bounds] or [`retain`][Self::retain].
+    pub fn bounds(&self) -> Option<(P, P)> {
+        if self.stale {
+            self.tight_bounds()
+        } else {
+            self.bounds
+        }
+    }
+
+    /// Recompute the bounding box after removals, so that [`bounds`][Self::bounds] is cheap again.
+    pub fn shrink_bounds(&mut self) {
+        if self.stale {
+            self.bounds = self.tight_bounds();
+            self.stale = false;
+        }
+    }
+
+    /// Growing stale bounds keeps them stale: they still contain every stored point.
+    fn grow_bounds(&mut self, point: P) {
+        self.bounds = Some(match self.bounds {
+            None => (point, point),
+            Some((min, max)) => (min.boundary_min(point), max.boundary_max(point)),
+        });
+    }
+
+    fn tight_bounds(&self) -> Option<(P, P)> {
+        let mut points = self.tiles.keys().copied();
+        points.next().map(|first| {
+            points.fold((first, first), |(min, max), point| {
+                (min.boundary_min(point), max.boundary_max(point))
+            })
+        })
+    }
+
+    /// Iterate over the stored tiles, in arbitrary order.
+    pub fn iter(&self) -> impl '_ + Iterator<Item = (P, &T)> {
+        self.tiles.iter().map(|(&point, tile)| (point, tile))
+    }
+
+    /// Iterate over the points at which tiles are stored, in arbitrary order.
+    pub fn points(&self) -> impl '_ + Iterator<Item = P> {
+        self.tiles.keys().copied()
+    }
+
+    /// Return an iterator of all points adjacent to the given point.
+    ///
+    /// Every point is legal, so this always returns the same number of items; it includes
+    /// diagonals.
+    pub fn adjacencies(&self, point: P) -> P::Adjacent {
+        point.adjacent()
+    }
+}
+
+impl<P> SparseMap<P, Bool>
+where
+    P: PointTrait + Hash,
+{
+    /// Compute one generation of a life-like cellular automaton, in which true tiles are live.
+    ///
+    /// `rule(live, n)` decides whether a point with `n` live neighbors is live in the next
+    /// generation. Only live points and their neighbors are considered, so dead points with no
+    /// live neighbors always stay dead. The successor stores only its live points.
+    pub fn life_step<F>(&self, rule: F) -> Self
+    where
+        F: Fn(bool, usize) -> bool,
+    {
+        let live = |point| self.get(point).is_some_and(|&tile| tile.into());
+        let mut counts: HashMap<P, usize> = HashMap::with_capacity(self.len() * 4);
+        for (point, _) in self.iter().filter(|(_, &tile)| tile.into()) {
+            counts.entry(point).or_insert(0);
+            for neighbor in point.adjacent() {
+                *counts.entry(neighbor).or_insert(0) += 1;
+            }
+        }
+        counts
+            .into_iter()
+            .filter(|&(point, n)| rule(live(point), n))
+            .map(|(point, _)| (point, Bool::True))
+            .collect()
+    }
+}
+
+impl<T> SparseMap<Point, T> {
+    /// Return an iterator of the 4 points orthogonally adjacent to the given point.
+    pub fn orthogonal_adjacencies(&self, point: Point) -> impl Iterator<Item = Point> {
+        Direction::iter().map(move |direction| point + direction)
+    }
+
+    /// Render the bounding box of this map into a dense [`Map`].
+    ///
+    /// The lower left corner of the bounding box becomes the origin of the dense map.
+    /// Unwritten points hold the default tile.
+    pub fn to_map(&self) -> Map<T>
+    where
+        T: Clone + Default,
+    {
+        let (min, max) = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return Map::new(0, 0),
+        };
+        let mut map = Map::new((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize);
+        map.for_each_mut(|tile| *tile = self.default.clone());
+        for (&point, tile) in self.tiles.iter() {
+            map[point - min] = tile.clone();
+        }
+        map
+    }
+}
+
+impl<T> From<&Map<T>> for SparseMap<Point, T>
+where
+    T: Clone + Default + PartialEq,
+{
+    /// Store every tile of the dense map which differs from the default, at the same point.
+    ///
+    /// Default tiles are not stored, so the sparse map's bounds cover only the other tiles. Rows
+    /// and columns of default tiles at the edges of the dense map are lost: converting back with
+    /// [`SparseMap::to_map`] may produce a smaller map.
+    fn from(map: &Map<T>) -> Self {
+        let mut sparse = SparseMap::default();
+        map.for_each_point(|tile, point| {
+            if *tile != sparse.default {
+                sparse.insert(point, tile.clone());
+            }
+        });
+        sparse
+    }
+}
+
+impl<P, T> Index<P> for SparseMap<P, T>
+where
+    P: PointTrait + Hash,
+{
+    type Output = T;
+
+    /// Unwritten points hold the default tile.
+    fn index(&self, point: P) -> &T {
+        self.tiles.get(&point).unwrap_or(&self.default)
+    }
+}
+
+impl<P, T> IndexMut<P> for SparseMap<P, T>
+where
+    P: PointTrait + Hash,
+    T: Clone,
+{
+    /// Writing to an unwritten point stores the default tile there first.
+    fn index_mut(&mut self, point: P) -> &mut T {
+        self.grow_bounds(point);
+        let default = &self.default;
+        self.tiles.entry(point).or_insert_with(|| default.clone())
+    }
+}
+
+impl<P, T> FromIterator<(P, T)> for SparseMap<P, T>
+where
+    P: PointTrait + Hash,
+    T: Default,
+{
+    fn from_iter<I: IntoIterator<Item = (P, T)>>(iter: I) -> Self {
+        let mut map = SparseMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<P, T> Extend<(P, T)> for SparseMap<P, T>
+where
+    P: PointTrait + Hash,
+{
+    fn extend<I: IntoIterator<Item = (P, T)>>(&mut self, iter: I) {
+        for (point, tile) in iter {
+            self.insert(point, tile);
+        }
+    }
+}
+
+impl<T> fmt::Display for SparseMap<Point, T>
+where
+    T: Clone + Default + fmt::Display + DisplayWidth,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_map().fmt(f)
+    }
+}
+
+impl<T> TryFrom<&str> for SparseMap<Point, T>
+where
+    T: Clone + Default + PartialEq + DisplayWidth + FromStr,
+    <T as FromStr>::Err: 'static + std::error::Error + Send + Sync,
+{
+    type Error = MapConversionErr;
+
+    /// the input should be in natural graphical order:
+    /// its first characters are the top left.
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let map: Map<T> = input.try_into()?;
+        Ok(SparseMap::from(&map))
+    }
+}
+
+impl<T> TryFrom<&std::path::Path> for SparseMap<Point, T>
+where
+    T: Clone + Default + PartialEq + DisplayWidth + FromStr,
+    <T as FromStr>::Err: 'static + std::error::Error + Send + Sync,
+{
+    type Error = std::io::Error;
+
+    /// the input should be in natural graphical order:
+    /// its first characters are the top left.
+    fn try_from(path: &std::path::Path) -> Result<Self, Self::Error> {
+        let map: Map<T> = path.try_into()?;
+        Ok(SparseMap::from(&map))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::{tile::Bool, vector3::Vector3};
+
+    #[test]
+    fn test_grows_in_every_direction() {
+        let mut map = SparseMap::<Point, Bool>::default();
+        assert_eq!(map.bounds(), None);
+        assert_eq!(map.to_map().width(), 0);
+
+        map[Point::new(-2, 1)] = Bool::True;
+        map.insert(Point::new(1, -1), Bool::True);
+        assert_eq!(map.bounds(), Some((Point::new(-2, -1), Point::new(1, 1))));
+        assert_eq!(map[Point::new(0, 0)], Bool::False);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.to_string(), "#...\n....\n...#\n");
+
+        assert_eq!(map.remove(Point::new(-2, 1)), Some(Bool::True));
+        assert_eq!(map.bounds(), Some((Point::new(1, -1), Point::new(1, -1))));
+    }
+
+    #[test]
+    fn test_bounds_shrink_after_removal_and_regrowth() {
+        let mut map = SparseMap::<Point, Bool>::default();
+        map.insert(Point::new(0, 0), Bool::True);
+        map.insert(Point::new(5, 5), Bool::True);
+        map.remove(Point::new(5, 5));
+        map.insert(Point::new(1, 1), Bool::True);
+        assert_eq!(map.bounds(), Some((Point::new(0, 0), Point::new(1, 1))));
+
+        map.retain(|point, _| point == Point::new(1, 1));
+        assert_eq!(map.bounds(), Some((Point::new(1, 1), Point::new(1, 1))));
+
+        map.insert(Point::new(-3, 0), Bool::True);
+        map.remove(Point::new(-3, 0));
+        map.shrink_bounds();
+        assert_eq!(map.bounds(), Some((Point::new(1, 1), Point::new(1, 1))));
+    }
+
+    #[test]
+    fn test_dense_round_trip_drops_default_edges() {
+        const DENSE: &str = "...\n.#.\n...\n";
+        let dense = <Map<Bool> as TryFrom<&str>>::try_from(DENSE).unwrap();
+        let sparse = SparseMap::from(&dense);
+        assert_eq!(sparse.len(), 1);
+        assert_eq!(sparse.to_map().to_string(), "#\n");
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        const EXAMPLE: &str = "#..\n.#.\n..#\n";
+        let map = SparseMap::<Point, Bool>::try_from(EXAMPLE).unwrap();
+        assert_eq!(map.len(), 3);
+        assert!(map.contains(Point::new(0, 2)));
+        assert_eq!(map.orthogonal_adjacencies(Point::new(1, 1)).count(), 4);
+        assert_eq!(map.to_string(), EXAMPLE);
+    }
+
+    #[test]
+    fn test_higher_dimensions() {
+        let map: SparseMap<Vector3, u8> =
+            vec![(Vector3::new(-1, 0, 4), 1), (Vector3::new(2, -3, 0), 2)]
+                .into_iter()
+                .collect();
+        assert_eq!(
+            map.bounds(),
+            Some((Vector3::new(-1, -3, 0), Vector3::new(2, 0, 4)))
+        );
+        assert_eq!(map.adjacencies(Vector3::new(0, 0, 0)).count(), 26);
+        assert_eq!(map[Vector3::new(0, 0, 0)], 0);
+    }
+
+    #[test]
+    fn test_life_step() {
+        let blinker = SparseMap::<Point, Bool>::try_from(".#.\n.#.\n.#.").unwrap();
+        let life = |live, n| matches!((live, n), (true, 2) | (_, 3));
+        let step = blinker.life_step(life);
+        assert_eq!(step.to_string(), "###\n");
+        assert_eq!(step.bounds(), Some((Point::new(0, 1), Point::new(2, 1))));
+        assert_eq!(step.life_step(life).to_string(), blinker.to_string());
+    }
+}
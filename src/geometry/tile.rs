@@ -46,20 +46,16 @@ impl<'a, T: DisplayWidth> Iterator for Chunks<'a, T> {
     Hash,
     parse_display::Display,
     parse_display::FromStr,
+    Default,
 )]
 pub enum Bool {
     #[display("#")]
     True,
     #[display(".")]
+    #[default]
     False,
 }
 
-impl Default for Bool {
-    fn default() -> Self {
-        Bool::False
-    }
-}
-
 impl DisplayWidth for Bool {
     const DISPLAY_WIDTH: usize = 1;
 }
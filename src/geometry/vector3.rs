@@ -1,5 +1,8 @@
-use crate::geometry::point::PointTrait;
-use itertools::Itertools;
+use crate::geometry::{
+    neighbors::Moore,
+    point::PointTrait,
+    vector_n::{InclusiveRange, VectorN},
+};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::{
@@ -23,32 +26,29 @@ impl Vector3 {
 
     /// Return the manhattan distance of this vector from the origin
     pub fn abs_sum(self) -> i32 {
-        self.x.abs() + self.y.abs() + self.z.abs()
+        VectorN::from(self).abs_sum()
     }
 
     /// Return this point with all dimensions decremented by 1
     pub fn decr(self) -> Vector3 {
-        Vector3::new(self.x - 1, self.y - 1, self.z - 1)
+        VectorN::from(self).decr().into()
     }
 
     /// Return this point with all dimensions incremented by 1
     pub fn incr(self) -> Vector3 {
-        Vector3::new(self.x + 1, self.y + 1, self.z + 1)
+        VectorN::from(self).incr().into()
     }
 
     /// Return all points that lie within the minimum and maximum bounds, inclusive
     pub fn inclusive_range(min: Vector3, max: Vector3) -> impl Iterator<Item = Vector3> {
-        (min.x..=max.x)
-            .cartesian_product(min.y..=max.y)
-            .cartesian_product(min.z..=max.z)
-            .map(|((x, y), z)| Vector3::new(x, y, z))
+        VectorN::inclusive_range(min.into(), max.into()).map(Vector3::from)
     }
 
     /// Iterate over points in 3d space adjacent to this point
     ///
     /// This includes diagonals, and excludes the center. It always returns 26 items.
     pub fn adjacent(self) -> impl Iterator<Item = Vector3> {
-        Vector3::inclusive_range(self.decr(), self.incr()).filter(move |&v| v != self)
+        VectorN::from(self).adjacent().map(Vector3::from)
     }
 
     /// Return the boundary minimum between `self` and `other`.
@@ -73,11 +73,7 @@ impl Vector3 {
     /// assert_eq!(a.boundary_min(b), Vector3::new(-1, -3, -1));
     /// ```
     pub fn boundary_min(self, other: Vector3) -> Vector3 {
-        Vector3::new(
-            self.x.min(other.x),
-            self.y.min(other.y),
-            self.z.min(other.z),
-        )
+        VectorN::from(self).boundary_min(other.into()).into()
     }
 
     /// Return the boundary maximum between `self` and `other`.
@@ -102,11 +98,7 @@ impl Vector3 {
     /// assert_eq!(a.boundary_max(b), Vector3::new(1, 3, 1));
     /// ```
     pub fn boundary_max(self, other: Vector3) -> Vector3 {
-        Vector3::new(
-            self.x.max(other.x),
-            self.y.max(other.y),
-            self.z.max(other.z),
-        )
+        VectorN::from(self).boundary_max(other.into()).into()
     }
 
     /// Return the volume of the space defined between this point and the origin.
@@ -114,10 +106,7 @@ impl Vector3 {
     where
         T: From<i32> + Mul<Output = T>,
     {
-        let x: T = self.x.abs().into();
-        let y: T = self.y.abs().into();
-        let z: T = self.z.abs().into();
-        x * y * z
+        VectorN::from(self).volume()
     }
 }
 
@@ -132,7 +121,7 @@ impl FromStr for Vector3 {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let captures = VEC3_RE.captures(s).ok_or(format!("no regex match"))?;
+        let captures = VEC3_RE.captures(s).ok_or("no regex match".to_string())?;
         Ok(Vector3 {
             x: captures
                 .name("x")
@@ -196,6 +185,18 @@ impl Sub for Vector3 {
     }
 }
 
+impl From<Vector3> for VectorN<3> {
+    fn from(v: Vector3) -> Self {
+        VectorN([v.x, v.y, v.z])
+    }
+}
+
+impl From<VectorN<3>> for Vector3 {
+    fn from(vector: VectorN<3>) -> Self {
+        Vector3::new(vector[0], vector[1], vector[2])
+    }
+}
+
 impl PointTrait for Vector3 {
     type N = i32;
 
@@ -211,8 +212,15 @@ impl PointTrait for Vector3 {
         <Self>::incr(self)
     }
 
-    fn inclusive_range(min: Self, max: Self) -> Box<dyn Iterator<Item = Self>> {
-        Box::new(<Self>::inclusive_range(min, max))
+    type InclusiveRange = std::iter::Map<InclusiveRange<3>, fn(VectorN<3>) -> Self>;
+    type Adjacent = std::iter::Map<Moore<3>, fn(VectorN<3>) -> Self>;
+
+    fn inclusive_range(min: Self, max: Self) -> Self::InclusiveRange {
+        VectorN::inclusive_range(min.into(), max.into()).map(Self::from)
+    }
+
+    fn adjacent(self) -> Self::Adjacent {
+        VectorN::from(self).adjacent().map(Self::from)
     }
 
     fn boundary_min(self, other: Self) -> Self {
@@ -1,5 +1,8 @@
-use crate::geometry::point::PointTrait;
-use itertools::Itertools;
+use crate::geometry::{
+    neighbors::Moore,
+    point::PointTrait,
+    vector_n::{InclusiveRange, VectorN},
+};
 use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
 
 /// A point in 4-dimensional space
@@ -18,33 +21,29 @@ impl Vector4 {
 
     /// Return the manhattan distance of this vector from the origin
     pub fn abs_sum(self) -> i32 {
-        self.x.abs() + self.y.abs() + self.z.abs() + self.w.abs()
+        VectorN::from(self).abs_sum()
     }
 
     /// Return this point with all dimensions decremented by 1
     pub fn decr(self) -> Vector4 {
-        Vector4::new(self.x - 1, self.y - 1, self.z - 1, self.w - 1)
+        VectorN::from(self).decr().into()
     }
 
     /// Return this point with all dimensions incremented by 1
     pub fn incr(self) -> Vector4 {
-        Vector4::new(self.x + 1, self.y + 1, self.z + 1, self.w + 1)
+        VectorN::from(self).incr().into()
     }
 
     /// Return all points that lie within the minimum and maximum bounds, inclusive
     pub fn inclusive_range(min: Vector4, max: Vector4) -> impl Iterator<Item = Vector4> {
-        (min.x..=max.x)
-            .cartesian_product(min.y..=max.y)
-            .cartesian_product(min.z..=max.z)
-            .cartesian_product(min.w..=max.w)
-            .map(|(((x, y), z), w)| Vector4::new(x, y, z, w))
+        VectorN::inclusive_range(min.into(), max.into()).map(Vector4::from)
     }
 
-    /// Iterate over points in 3d space adjacent to this point
+    /// Iterate over points in 4d space adjacent to this point
     ///
-    /// This includes diagonals, and excludes the center. It always returns 26 items.
+    /// This includes diagonals, and excludes the center. It always returns 80 items.
     pub fn adjacent(self) -> impl Iterator<Item = Vector4> {
-        Vector4::inclusive_range(self.decr(), self.incr()).filter(move |&v| v != self)
+        VectorN::from(self).adjacent().map(Vector4::from)
     }
 
     /// Return the boundary minimum between `self` and `other`.
@@ -53,7 +52,7 @@ impl Vector4 {
     /// help for computing an inclusive range. For example, it is true that
     ///
     /// ```rust
-    /// # use aoc2020::geometry::vector3::Vector4;
+    /// # use aoc2020::geometry::vector4::Vector4;
     /// let a = Vector4::new(-1, -1, -1, 0);
     /// let b = Vector4::new(0, -3, -1, 0);
     /// assert!(a < b);
@@ -63,18 +62,13 @@ impl Vector4 {
     /// contains both `self` and `other`:
     ///
     /// ```rust
-    /// # use aoc2020::geometry::vector3::Vector4;
+    /// # use aoc2020::geometry::vector4::Vector4;
     /// let a = Vector4::new(-1, -1, -1, 0);
     /// let b = Vector4::new(0, -3, -1, 0);
     /// assert_eq!(a.boundary_min(b), Vector4::new(-1, -3, -1, 0));
     /// ```
     pub fn boundary_min(self, other: Vector4) -> Vector4 {
-        Vector4::new(
-            self.x.min(other.x),
-            self.y.min(other.y),
-            self.z.min(other.z),
-            self.w.min(other.w),
-        )
+        VectorN::from(self).boundary_min(other.into()).into()
     }
 
     /// Return the boundary maximum between `self` and `other`.
@@ -83,7 +77,7 @@ impl Vector4 {
     /// help for computing an inclusive range. For example, it is true that
     ///
     /// ```rust
-    /// # use aoc2020::geometry::vector3::Vector4;
+    /// # use aoc2020::geometry::vector4::Vector4;
     /// let a = Vector4::new(1, 1, 1, 0);
     /// let b = Vector4::new(0, 3, 1, 0);
     /// assert!(a > b);
@@ -93,18 +87,13 @@ impl Vector4 {
     /// contains both `self` and `other`:
     ///
     /// ```rust
-    /// # use aoc2020::geometry::vector3::Vector4;
+    /// # use aoc2020::geometry::vector4::Vector4;
     /// let a = Vector4::new(1, 1, 1, 0);
     /// let b = Vector4::new(0, 3, 1, 0);
     /// assert_eq!(a.boundary_max(b), Vector4::new(1, 3, 1, 0));
     /// ```
     pub fn boundary_max(self, other: Vector4) -> Vector4 {
-        Vector4::new(
-            self.x.max(other.x),
-            self.y.max(other.y),
-            self.z.max(other.z),
-            self.w.max(other.w),
-        )
+        VectorN::from(self).boundary_max(other.into()).into()
     }
 
     /// Return the volume of the space defined between this point and the origin.
@@ -112,11 +101,7 @@ impl Vector4 {
     where
         T: From<i32> + Mul<Output = T>,
     {
-        let x: T = self.x.abs().into();
-        let y: T = self.y.abs().into();
-        let z: T = self.z.abs().into();
-        let w: T = self.w.abs().into();
-        x * y * z * w
+        VectorN::from(self).volume()
     }
 }
 
@@ -156,6 +141,18 @@ impl Sub for Vector4 {
     }
 }
 
+impl From<Vector4> for VectorN<4> {
+    fn from(v: Vector4) -> Self {
+        VectorN([v.x, v.y, v.z, v.w])
+    }
+}
+
+impl From<VectorN<4>> for Vector4 {
+    fn from(vector: VectorN<4>) -> Self {
+        Vector4::new(vector[0], vector[1], vector[2], vector[3])
+    }
+}
+
 impl PointTrait for Vector4 {
     type N = i32;
 
@@ -171,8 +168,15 @@ impl PointTrait for Vector4 {
         <Self>::incr(self)
     }
 
-    fn inclusive_range(min: Self, max: Self) -> Box<dyn Iterator<Item = Self>> {
-        Box::new(<Self>::inclusive_range(min, max))
+    type InclusiveRange = std::iter::Map<InclusiveRange<4>, fn(VectorN<4>) -> Self>;
+    type Adjacent = std::iter::Map<Moore<4>, fn(VectorN<4>) -> Self>;
+
+    fn inclusive_range(min: Self, max: Self) -> Self::InclusiveRange {
+        VectorN::inclusive_range(min.into(), max.into()).map(Self::from)
+    }
+
+    fn adjacent(self) -> Self::Adjacent {
+        VectorN::from(self).adjacent().map(Self::from)
     }
 
     fn boundary_min(self, other: Self) -> Self {
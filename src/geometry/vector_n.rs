@@ -0,0 +1,427 @@
+use crate::geometry::{
+    neighbors::{Moore, VonNeumann},
+    point::PointTrait,
+    Point,
+};
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+/// Names of the first few axes, for display and parsing.
+///
+/// Components beyond these are named by their index: `d4`, `d5`, and so on.
+const AXIS_NAMES: [&str; 4] = ["x", "y", "z", "w"];
+
+fn axis_name(axis: usize) -> String {
+    match AXIS_NAMES.get(axis) {
+        Some(name) => name.to_string(),
+        None => format!("d{}", axis),
+    }
+}
+
+/// A point in `D`-dimensional space
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VectorN<const D: usize>(pub [i32; D]);
+
+impl<const D: usize> Default for VectorN<D> {
+    fn default() -> Self {
+        VectorN([0; D])
+    }
+}
+
+impl<const D: usize> From<[i32; D]> for VectorN<D> {
+    fn from(components: [i32; D]) -> Self {
+        VectorN(components)
+    }
+}
+
+impl<const D: usize> VectorN<D> {
+    /// The components of this vector, in axis order.
+    pub fn components(&self) -> &[i32; D] {
+        &self.0
+    }
+
+    /// Apply `f` to each component of this vector.
+    pub fn map(self, mut f: impl FnMut(i32) -> i32) -> Self {
+        let mut out = self;
+        for component in out.0.iter_mut() {
+            *component = f(*component);
+        }
+        out
+    }
+
+    /// Combine this vector with another, component by component.
+    pub fn zip_with(self, other: Self, mut f: impl FnMut(i32, i32) -> i32) -> Self {
+        let mut out = self;
+        for (component, other) in out.0.iter_mut().zip(other.0.iter()) {
+            *component = f(*component, *other);
+        }
+        out
+    }
+
+    /// Return the manhattan distance of this vector from the origin
+    pub fn abs_sum(self) -> i32 {
+        self.0.iter().map(|component| component.abs()).sum()
+    }
+
+    /// Return this point with all dimensions decremented by 1
+    pub fn decr(self) -> Self {
+        self.map(|component| component - 1)
+    }
+
+    /// Return this point with all dimensions incremented by 1
+    pub fn incr(self) -> Self {
+        self.map(|component| component + 1)
+    }
+
+    /// Return all points that lie within the minimum and maximum bounds, inclusive
+    ///
+    /// Points are produced in lexicographic order: the last axis varies fastest.
+    pub fn inclusive_range(min: Self, max: Self) -> InclusiveRange<D> {
+        let empty = min.0.iter().zip(max.0.iter()).any(|(min, max)| min > max);
+        InclusiveRange {
+            min,
+            max,
+            next: if empty { None } else { Some(min) },
+        }
+    }
+
+    /// Iterate over points adjacent to this point
+    ///
+    /// This includes diagonals, and excludes the center. It always returns `3^D - 1` items.
+    pub fn adjacent(self) -> Moore<D> {
+        Moore::new(self)
+    }
+
+    /// Iterate over points orthogonally adjacent to this point.
+    ///
+    /// It always returns `2 * D` items.
+    pub fn orthogonal(self) -> VonNeumann<D> {
+        VonNeumann::new(self)
+    }
+
+    /// Return the boundary minimum between `self` and `other`.
+    ///
+    /// The standard `.min` function computes a total ordering between two vectors, but it doesn't
+    /// help for computing an inclusive range. For example, it is true that
+    ///
+    /// ```rust
+    /// # use aoc2020::geometry::VectorN;
+    /// let a = VectorN([-1, -1, -1, 0, 2]);
+    /// let b = VectorN([0, -3, -1, 0, 1]);
+    /// assert!(a < b);
+    /// ```
+    ///
+    /// The boundary minimum, on the other hand, computes the minimal bounded point which
+    /// contains both `self` and `other`:
+    ///
+    /// ```rust
+    /// # use aoc2020::geometry::VectorN;
+    /// let a = VectorN([-1, -1, -1, 0, 2]);
+    /// let b = VectorN([0, -3, -1, 0, 1]);
+    /// assert_eq!(a.boundary_min(b), VectorN([-1, -3, -1, 0, 1]));
+    /// ```
+    pub fn boundary_min(self, other: Self) -> Self {
+        self.zip_with(other, i32::min)
+    }
+
+    /// Return the boundary maximum between `self` and `other`.
+    ///
+    /// This is the counterpart of [`boundary_min`][Self::boundary_min]:
+    ///
+    /// ```rust
+    /// # use aoc2020::geometry::VectorN;
+    /// let a = VectorN([1, 1, 1, 0, 2]);
+    /// let b = VectorN([0, 3, 1, 0, 1]);
+    /// assert!(a > b);
+    /// assert_eq!(a.boundary_max(b), VectorN([1, 3, 1, 0, 2]));
+    /// ```
+    pub fn boundary_max(self, other: Self) -> Self {
+        self.zip_with(other, i32::max)
+    }
+
+    /// Return the volume of the space defined between this point and the origin.
+    pub fn volume<T>(self) -> T
+    where
+        T: From<i32> + Mul<Output = T>,
+    {
+        self.0.iter().fold(T::from(1), |volume, component| {
+            volume * component.abs().into()
+        })
+    }
+}
+
+/// Iterator over the points of an inclusive box, produced by [`VectorN::inclusive_range`].
+#[derive(Debug, Clone)]
+pub struct InclusiveRange<const D: usize> {
+    min: VectorN<D>,
+    max: VectorN<D>,
+    next: Option<VectorN<D>>,
+}
+
+impl<const D: usize> Iterator for InclusiveRange<D> {
+    type Item = VectorN<D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+
+        // advance like an odometer: the last axis turns fastest
+        let mut next = current;
+        self.next = None;
+        for axis in (0..D).rev() {
+            if next[axis] < self.max[axis] {
+                next[axis] += 1;
+                self.next = Some(next);
+                break;
+            }
+            next[axis] = self.min[axis];
+        }
+
+        Some(current)
+    }
+}
+
+impl<const D: usize> Index<usize> for VectorN<D> {
+    type Output = i32;
+
+    fn index(&self, axis: usize) -> &i32 {
+        &self.0[axis]
+    }
+}
+
+impl<const D: usize> IndexMut<usize> for VectorN<D> {
+    fn index_mut(&mut self, axis: usize) -> &mut i32 {
+        &mut self.0[axis]
+    }
+}
+
+impl<const D: usize> FromStr for VectorN<D> {
+    type Err = String;
+
+    /// Parse a vector like `<x=1, y=-2, z=3>`.
+    ///
+    /// Axis names are optional, but if present must be correct.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let inner = s
+            .trim()
+            .strip_prefix('<')
+            .and_then(|s| s.strip_suffix('>'))
+            .ok_or_else(|| "vector must be enclosed in angle brackets".to_string())?;
+
+        let mut vector = VectorN::default();
+        let mut parts = inner.split(',');
+        for axis in 0..D {
+            let name = axis_name(axis);
+            let part = parts
+                .next()
+                .ok_or_else(|| format!("{}: missing", name))?
+                .trim();
+            let value = match part.split_once('=') {
+                Some((given, value)) if given.trim().eq_ignore_ascii_case(&name) => value,
+                Some((given, _)) => return Err(format!("{}: wrong axis name {:?}", name, given)),
+                None => part,
+            };
+            vector[axis] = value
+                .trim()
+                .parse()
+                .map_err(|err| format!("{}: {}", name, err))?;
+        }
+        if parts.next().is_some() {
+            return Err(format!("too many components for {} dimensions", D));
+        }
+        Ok(vector)
+    }
+}
+
+impl<const D: usize> fmt::Display for VectorN<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<")?;
+        for (axis, component) in self.0.iter().enumerate() {
+            if axis > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}={:3}", axis_name(axis), component)?;
+        }
+        write!(f, ">")
+    }
+}
+
+impl<const D: usize> AddAssign for VectorN<D> {
+    fn add_assign(&mut self, other: Self) {
+        *self = self.zip_with(other, |a, b| a + b);
+    }
+}
+
+impl<const D: usize> Add for VectorN<D> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl<const D: usize> SubAssign for VectorN<D> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = self.zip_with(other, |a, b| a - b);
+    }
+}
+
+impl<const D: usize> Sub for VectorN<D> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        self -= other;
+        self
+    }
+}
+
+impl<const D: usize> MulAssign<i32> for VectorN<D> {
+    fn mul_assign(&mut self, scalar: i32) {
+        *self = self.map(|component| component * scalar);
+    }
+}
+
+impl<const D: usize> Mul<i32> for VectorN<D> {
+    type Output = Self;
+
+    fn mul(mut self, scalar: i32) -> Self {
+        self *= scalar;
+        self
+    }
+}
+
+impl<const D: usize> Neg for VectorN<D> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.map(|component| -component)
+    }
+}
+
+impl From<Point> for VectorN<2> {
+    fn from(point: Point) -> Self {
+        VectorN([point.x, point.y])
+    }
+}
+
+impl From<VectorN<2>> for Point {
+    fn from(vector: VectorN<2>) -> Self {
+        Point::new(vector[0], vector[1])
+    }
+}
+
+impl<const D: usize> PointTrait for VectorN<D> {
+    type N = i32;
+
+    fn manhattan(self) -> Self::N {
+        <Self>::abs_sum(self)
+    }
+
+    fn decr(self) -> Self {
+        <Self>::decr(self)
+    }
+
+    fn incr(self) -> Self {
+        <Self>::incr(self)
+    }
+
+    type InclusiveRange = InclusiveRange<D>;
+    type Adjacent = Moore<D>;
+
+    fn inclusive_range(min: Self, max: Self) -> Self::InclusiveRange {
+        <Self>::inclusive_range(min, max)
+    }
+
+    fn adjacent(self) -> Self::Adjacent {
+        <Self>::adjacent(self)
+    }
+
+    fn boundary_min(self, other: Self) -> Self {
+        <Self>::boundary_min(self, other)
+    }
+
+    fn boundary_max(self, other: Self) -> Self {
+        <Self>::boundary_max(self, other)
+    }
+
+    fn volume<T>(self) -> T
+    where
+        T: From<Self::N> + Mul<Output = T>,
+    {
+        <Self>::volume(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_inclusive_range_order() {
+        let points: Vec<_> =
+            VectorN::inclusive_range(VectorN([0, 0, 0]), VectorN([1, 0, 1])).collect();
+        assert_eq!(
+            points,
+            vec![
+                VectorN([0, 0, 0]),
+                VectorN([0, 0, 1]),
+                VectorN([1, 0, 0]),
+                VectorN([1, 0, 1]),
+            ]
+        );
+        assert_eq!(
+            VectorN::inclusive_range(VectorN([0, 1]), VectorN([1, 0])).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_adjacent_counts() {
+        assert_eq!(VectorN([0; 2]).adjacent().count(), 8);
+        assert_eq!(VectorN([0; 5]).adjacent().count(), 242);
+        assert_eq!(VectorN([1, 2, 3, 4]).volume::<i64>(), 24);
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let v: VectorN<3> = "<x=-1, y=  2, z=3>".parse().unwrap();
+        assert_eq!(v, VectorN([-1, 2, 3]));
+        assert_eq!(v.to_string(), "<x= -1, y=  2, z=  3>");
+        assert_eq!("<4,5>".parse::<VectorN<2>>(), Ok(VectorN([4, 5])));
+
+        let v6 = VectorN([1, 2, 3, 4, 5, 6]);
+        assert_eq!(v6.to_string().parse::<VectorN<6>>(), Ok(v6));
+        assert!("<x=1, z=2>".parse::<VectorN<2>>().is_err());
+        assert!("<1, 2, 3>".parse::<VectorN<2>>().is_err());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = VectorN([1, -2, 3]);
+        let b = VectorN([4, 5, -6]);
+        assert_eq!(a + b, VectorN([5, 3, -3]));
+        assert_eq!(a - b, VectorN([-3, -7, 9]));
+        assert_eq!(-a * 2, VectorN([-2, 4, -6]));
+        assert_eq!(
+            Point::from(VectorN::from(Point::new(7, 8))),
+            Point::new(7, 8)
+        );
+    }
+
+    #[test]
+    fn test_named_vector_conversions() {
+        use crate::geometry::{vector3::Vector3, vector4::Vector4};
+
+        let v3 = Vector3::new(1, -2, 3);
+        assert_eq!(VectorN::from(v3), VectorN([1, -2, 3]));
+        assert_eq!(Vector3::from(VectorN::from(v3)), v3);
+
+        let v4 = Vector4::new(1, -2, 3, -4);
+        assert_eq!(VectorN::from(v4), VectorN([1, -2, 3, -4]));
+        assert_eq!(Vector4::from(VectorN::from(v4)), v4);
+        assert_eq!(PointTrait::adjacent(v4).count(), 80);
+    }
+}
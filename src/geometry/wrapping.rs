@@ -0,0 +1,338 @@
+//! Toroidal views of a [`Map`].
+//!
+//! A [`WrappingMap`] borrows a map and treats some of its edges as joined: stepping off the right
+//! edge of a map which wraps on the X axis arrives at the left edge of the same row, and so on.
+//! Points are brought into bounds with the Euclidean remainder, so negative coordinates wrap too.
+
+use super::{
+    map::{ContextInto, Traversable},
+    Direction, DistanceField, Map, Point, WeightedPath,
+};
+use std::ops::Index;
+
+/// The axes along which a [`WrappingMap`] wraps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Wrap {
+    /// The left and right edges are joined.
+    X,
+    /// The top and bottom edges are joined.
+    Y,
+    /// Both pairs of edges are joined: the map is a torus.
+    Both,
+}
+
+impl Wrap {
+    pub fn wraps_x(self) -> bool {
+        matches!(self, Wrap::X | Wrap::Both)
+    }
+
+    pub fn wraps_y(self) -> bool {
+        matches!(self, Wrap::Y | Wrap::Both)
+    }
+}
+
+impl<T> Map<T> {
+    /// View this map with some of its edges joined.
+    pub fn wrapping(&self, wrap: Wrap) -> WrappingMap<'_, T> {
+        WrappingMap { map: self, wrap }
+    }
+}
+
+/// A borrowed view of a [`Map`] whose edges wrap along one or both axes.
+///
+/// Created by [`Map::wrapping`]. Every point along a wrapping axis is in bounds. Points returned
+/// by this view are always normalized into the bounds of the underlying map, so they can index
+/// either.
+///
+/// On a map only one or two tiles across a wrapping axis, a tile may be adjacent to itself or to
+/// the same neighbor more than once.
+pub struct WrappingMap<'a, T> {
+    map: &'a Map<T>,
+    wrap: Wrap,
+}
+
+impl<'a, T> Clone for WrappingMap<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for WrappingMap<'a, T> {}
+
+impl<'a, T> WrappingMap<'a, T> {
+    /// The underlying map.
+    pub fn map(&self) -> &'a Map<T> {
+        self.map
+    }
+
+    pub fn wrap(&self) -> Wrap {
+        self.wrap
+    }
+
+    /// Bring a point into the bounds of the underlying map.
+    ///
+    /// Returns `None` if the point is out of bounds along an axis which does not wrap.
+    pub fn normalize(&self, point: Point) -> Option<Point> {
+        self.map.wrap_point(Some(self.wrap), point)
+    }
+
+    pub fn in_bounds(&self, point: Point) -> bool {
+        self.normalize(point).is_some()
+    }
+
+    pub fn get(&self, point: Point) -> Option<&'a T> {
+        let map = self.map;
+        self.normalize(point).map(|point| &map[point])
+    }
+
+    /// The number of orthogonal steps between two points, taking the shorter way around each
+    /// wrapping axis.
+    ///
+    /// Returns `None` if either point is out of bounds.
+    pub fn manhattan(&self, a: Point, b: Point) -> Option<i32> {
+        Some(
+            self.map
+                .wrapped_manhattan(Some(self.wrap), self.normalize(a)?, self.normalize(b)?),
+        )
+    }
+
+    /// Return an iterator of all legal points adjacent to the given point.
+    ///
+    /// This iterator will return up to 8 elements; it includes diagonals.
+    pub fn adjacencies(&self, point: Point) -> impl 'a + Iterator<Item = Point> {
+        let view = *self;
+        self.orthogonal_adjacencies(point)
+            .chain(
+                Direction::iter_diag().filter_map(move |(vertical, horizontal)| {
+                    view.normalize(point + vertical + horizontal)
+                }),
+            )
+    }
+
+    /// Return an iterator of all legal points orthogonally adjacent to the given point.
+    ///
+    /// This iterator will return up to 4 elements; it does not include diagonals.
+    pub fn orthogonal_adjacencies(&self, point: Point) -> impl 'a + Iterator<Item = Point> {
+        let view = *self;
+        Direction::iter().filter_map(move |direction| view.normalize(point + direction))
+    }
+
+    /// Return an iterator of all legal points arrived at by applying the given deltas to the origin.
+    ///
+    /// The origin point is always the first item in this iteration, if it is in bounds. Along
+    /// wrapping axes the projection continues around the map; it stops at an edge which does not
+    /// wrap, or just before it would return to the origin.
+    pub fn project(&self, origin: Point, dx: i32, dy: i32) -> impl 'a + Iterator<Item = Point> {
+        let view = *self;
+        let origin = self.normalize(origin);
+        std::iter::successors(origin, move |&current| {
+            view.normalize(current + (dx, dy))
+                .filter(|&next| Some(next) != origin)
+        })
+    }
+}
+
+impl<'a, T> Index<Point> for WrappingMap<'a, T> {
+    type Output = T;
+
+    /// Panics if the point is out of bounds along an axis which does not wrap.
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .expect("point out of bounds along a non-wrapping axis")
+    }
+}
+
+impl<'a, T> WrappingMap<'a, T>
+where
+    T: Clone + ContextInto<Traversable, Context = ()>,
+{
+    /// Visit every non-obstructed tile reachable from the initial point.
+    ///
+    /// See [`Map::reachable_from`].
+    pub fn reachable_from<F>(&self, point: Point, visit: F)
+    where
+        F: FnMut(&T, Point) -> bool,
+    {
+        self.reachable_from_ctx(&(), point, visit)
+    }
+
+    /// Navigate between the given points, possibly across wrapping edges.
+    pub fn navigate(&self, from: Point, to: Point) -> Option<Vec<Direction>> {
+        self.navigate_ctx(&(), from, to)
+    }
+
+    /// Count the steps from the initial point to every tile reachable from it.
+    pub fn distances_from(&self, point: Point) -> Map<Option<u32>> {
+        self.distances_from_ctx(&(), point)
+    }
+
+    /// Find the distance from each tile to its nearest source.
+    ///
+    /// See [`Map::distance_field`].
+    pub fn distance_field(&self, sources: &[Point]) -> DistanceField {
+        self.distance_field_ctx(&(), sources)
+    }
+}
+
+impl<'a, T: Clone + ContextInto<Traversable>> WrappingMap<'a, T> {
+    /// Visit every non-obstructed tile reachable from the initial point.
+    ///
+    /// See [`Map::reachable_from_ctx`].
+    pub fn reachable_from_ctx<F>(
+        &self,
+        context: &<T as ContextInto<Traversable>>::Context,
+        point: Point,
+        visit: F,
+    ) where
+        F: FnMut(&T, Point) -> bool,
+    {
+        self.map
+            .reachable_from_wrapped(Some(self.wrap), context, point, visit)
+    }
+
+    /// Navigate between the given points, possibly across wrapping edges.
+    pub fn navigate_ctx(
+        &self,
+        context: &<T as ContextInto<Traversable>>::Context,
+        from: Point,
+        to: Point,
+    ) -> Option<Vec<Direction>> {
+        self.map
+            .navigate_wrapped(Some(self.wrap), context, from, to)
+    }
+
+    /// Count the steps from the initial point to every tile reachable from it.
+    pub fn distances_from_ctx(
+        &self,
+        context: &<T as ContextInto<Traversable>>::Context,
+        point: Point,
+    ) -> Map<Option<u32>> {
+        self.distance_field_ctx(context, &[point]).distances
+    }
+
+    /// Find the distance from each tile to its nearest source.
+    ///
+    /// See [`Map::distance_field_ctx`].
+    pub fn distance_field_ctx(
+        &self,
+        context: &<T as ContextInto<Traversable>>::Context,
+        sources: &[Point],
+    ) -> DistanceField {
+        self.map
+            .distance_field_wrapped(Some(self.wrap), context, sources)
+    }
+}
+
+impl<'a, T> WrappingMap<'a, T>
+where
+    T: Clone + ContextInto<Option<u32>, Context = ()>,
+{
+    /// Find the cheapest path between the given points, possibly across wrapping edges.
+    ///
+    /// See [`Map::dijkstra`].
+    pub fn dijkstra(&self, from: Point, to: Point) -> Option<WeightedPath> {
+        self.dijkstra_ctx(&(), from, to)
+    }
+}
+
+impl<'a, T: Clone + ContextInto<Option<u32>>> WrappingMap<'a, T> {
+    /// Find the cheapest path between the given points, possibly across wrapping edges.
+    ///
+    /// See [`Map::dijkstra_ctx`].
+    pub fn dijkstra_ctx(
+        &self,
+        context: &<T as ContextInto<Option<u32>>>::Context,
+        from: Point,
+        to: Point,
+    ) -> Option<WeightedPath> {
+        self.map
+            .dijkstra_wrapped(Some(self.wrap), context, from, to)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geometry::tile::Bool;
+    use std::convert::TryFrom;
+
+    impl From<Bool> for Traversable {
+        fn from(tile: Bool) -> Self {
+            match tile {
+                Bool::True => Traversable::Obstructed,
+                Bool::False => Traversable::Free,
+            }
+        }
+    }
+
+    fn example() -> Map<Bool> {
+        <Map<Bool> as TryFrom<&str>>::try_from("..#..\n..#..\n..#..").unwrap()
+    }
+
+    #[test]
+    fn test_normalize() {
+        let map = example();
+        let wrapped = map.wrapping(Wrap::X);
+        assert_eq!(wrapped.normalize(Point::new(-1, 0)), Some(Point::new(4, 0)));
+        assert_eq!(wrapped.normalize(Point::new(-6, 2)), Some(Point::new(4, 2)));
+        assert_eq!(wrapped.normalize(Point::new(12, 1)), Some(Point::new(2, 1)));
+        assert_eq!(wrapped.normalize(Point::new(0, -1)), None);
+        assert_eq!(wrapped[Point::new(-3, 0)], Bool::True);
+
+        let torus = map.wrapping(Wrap::Both);
+        assert_eq!(torus.normalize(Point::new(-1, -1)), Some(Point::new(4, 2)));
+        assert_eq!(torus.adjacencies(Point::new(0, 0)).count(), 8);
+        assert_eq!(
+            map.wrapping(Wrap::Y).adjacencies(Point::new(0, 0)).count(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_project() {
+        let map = example();
+        let points: Vec<_> = map
+            .wrapping(Wrap::X)
+            .project(Point::new(0, 2), 3, -1)
+            .collect();
+        assert_eq!(
+            points,
+            vec![Point::new(0, 2), Point::new(3, 1), Point::new(1, 0)]
+        );
+
+        // a projection around a torus stops before returning to its origin
+        assert_eq!(
+            map.wrapping(Wrap::Both)
+                .project(Point::new(0, 0), 1, 0)
+                .count(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_traversals_cross_edges() {
+        let map = example();
+        assert_eq!(map.navigate(Point::new(0, 0), Point::new(4, 0)), None);
+
+        let wrapped = map.wrapping(Wrap::X);
+        assert_eq!(
+            wrapped.navigate(Point::new(0, 0), Point::new(4, 0)),
+            Some(vec![Direction::Left])
+        );
+
+        let mut reached = 0;
+        wrapped.reachable_from(Point::new(-1, 1), |_, _| {
+            reached += 1;
+            false
+        });
+        assert_eq!(reached, 12);
+
+        let field = wrapped.distance_field(&[Point::new(0, 0)]);
+        assert_eq!(field.distances[Point::new(3, 0)], Some(2));
+        assert_eq!(field.distances[Point::new(2, 0)], None);
+        assert_eq!(
+            field.path_to(Point::new(-2, 0)),
+            Some((Point::new(0, 0), vec![Direction::Left, Direction::Left]))
+        );
+    }
+}
@@ -26,7 +26,7 @@ where
             if buf.is_empty() {
                 None
             } else {
-                match T::from_str(&buf.trim()) {
+                match T::from_str(buf.trim()) {
                     Ok(t) => Some(t),
                     Err(e) => {
                         eprintln!(
@@ -108,6 +108,32 @@ where
     .fuse())
 }
 
+/// Parse a string into a stream of `T`, one record per line.
+///
+/// This mirrors [`parse`] for in-memory data, except that every record's result is yielded
+/// instead of printing the first error and stopping.
+pub fn parse_str<'a, T>(s: &'a str) -> impl 'a + Iterator<Item = Result<T, <T as FromStr>::Err>>
+where
+    T: 'a + FromStr,
+{
+    s.lines().map(|line| T::from_str(line.trim()))
+}
+
+/// Parse a string into a stream of `T`, one record per cluster of lines.
+///
+/// This mirrors [`parse_newline_sep`] for in-memory data, except that every record's result is
+/// yielded instead of printing the first error and stopping.
+pub fn parse_newline_sep_str<'a, T>(
+    s: &'a str,
+) -> impl 'a + Iterator<Item = Result<T, <T as FromStr>::Err>>
+where
+    T: 'a + FromStr,
+{
+    s.split("\n\n")
+        .filter(|record| !record.is_empty())
+        .map(T::from_str)
+}
+
 /// adaptor which plugs into parse, splitting comma-separated items from the line
 ///
 /// This can be flattened or consumed by line, as required
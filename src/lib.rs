@@ -3,6 +3,7 @@ pub mod day;
 pub mod geometry;
 pub mod input;
 pub mod numbers;
+pub mod priority_queue;
 pub mod website;
 
 pub use input::{parse, CommaSep};
@@ -11,22 +11,38 @@
 //!
 //! This module is adapted from the example in [Rosetta Code](https://rosettacode.org/wiki/Chinese_remainder_theorem#Rust)
 
-use num::{integer::Integer, traits::Signed};
-use std::{iter::Product, ops::AddAssign};
+use num::{
+    integer::Integer,
+    traits::{CheckedMul, Signed},
+};
 
-fn egcd<N: Integer + Copy + Signed>(a: N, b: N) -> (N, N, N) {
-    if a.is_zero() {
-        (b, N::zero(), N::one())
+/// The extended Euclidean algorithm.
+///
+/// Returns `(g, x, y)` such that `g == gcd(a, b)` and `a * x + b * y == g`.
+fn egcd<N: Integer + Signed + Clone>(a: N, b: N) -> (N, N, N) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (N::one(), N::zero());
+    let (mut old_y, mut y) = (N::zero(), N::one());
+    while !r.is_zero() {
+        let q = old_r.div_floor(&r);
+        let next_r = old_r - q.clone() * r.clone();
+        old_r = std::mem::replace(&mut r, next_r);
+        let next_x = old_x - q.clone() * x.clone();
+        old_x = std::mem::replace(&mut x, next_x);
+        let next_y = old_y - q * y.clone();
+        old_y = std::mem::replace(&mut y, next_y);
+    }
+    if old_r.is_negative() {
+        (-old_r, -old_x, -old_y)
     } else {
-        let (g, x, y) = egcd(b % a, a);
-        (g, y - (b / a) * x, x)
+        (old_r, old_x, old_y)
     }
 }
 
-fn mod_inv<N: Integer + Copy + Signed>(x: N, n: N) -> Option<N> {
-    let (g, x, _) = egcd(x, n);
+pub(crate) fn mod_inv<N: Integer + Signed + Clone>(x: N, n: N) -> Option<N> {
+    let (g, x, _) = egcd(x, n.clone());
     if g.is_one() {
-        Some((x % n + n) % n)
+        Some(x.mod_floor(&n))
     } else {
         None
     }
@@ -54,13 +70,155 @@ impl<N> Constraint<N> {
 
 impl<N> Constraint<N>
 where
-    N: Copy + Integer,
+    N: Clone + Integer,
 {
     /// This formulation is useful when what's available is the "inverted remainder":
     /// `invert_remainder == modulus - remainder`.
     pub fn new_invert_remainder(modulus: N, invert_remainder: N) -> Constraint<N> {
-        Constraint::new(modulus, (modulus - invert_remainder) % modulus)
+        Constraint::new(
+            modulus.clone(),
+            (modulus.clone() - invert_remainder) % modulus,
+        )
+    }
+
+    /// Does `n` satisfy this constraint?
+    pub fn is_satisfied_by(&self, n: &N) -> bool {
+        n.mod_floor(&self.modulus) == self.remainder.mod_floor(&self.modulus)
+    }
+}
+
+/// Why a system of constraints could not be solved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("constraint {0} has a non-positive modulus")]
+    Modulus(usize),
+    #[error("constraint {second} contradicts constraint {first}")]
+    Conflict { first: usize, second: usize },
+    #[error("the solution including constraint {0} overflows")]
+    Overflow(usize),
+}
+
+/// Why two solution classes could not be merged.
+enum MergeError {
+    Conflict,
+    Overflow,
+}
+
+/// Combine two solution classes, with remainders in range, into the class satisfying both.
+fn merge<N>(a: &Constraint<N>, b: &Constraint<N>) -> Result<Constraint<N>, MergeError>
+where
+    N: Integer + Signed + Clone + CheckedMul,
+{
+    let (g, p, _) = egcd(a.modulus.clone(), b.modulus.clone());
+    let difference = b.remainder.clone() - a.remainder.clone();
+    if !difference.is_multiple_of(&g) {
+        return Err(MergeError::Conflict);
+    }
+    // step from `a.remainder` in multiples of `a.modulus` until `b` is satisfied too;
+    // reducing mod `b.modulus / g` first keeps the intermediate products small
+    let step_modulus = b.modulus.clone() / g.clone();
+    let steps = (difference / g)
+        .mod_floor(&step_modulus)
+        .checked_mul(&p.mod_floor(&step_modulus))
+        .ok_or(MergeError::Overflow)?;
+    let modulus = a
+        .modulus
+        .checked_mul(&step_modulus)
+        .ok_or(MergeError::Overflow)?;
+    // both terms are below `modulus`, which fits, so neither this product nor the sum overflows
+    let remainder = (a.remainder.clone() + a.modulus.clone() * steps.mod_floor(&step_modulus))
+        .mod_floor(&modulus);
+    Ok(Constraint { modulus, remainder })
+}
+
+/// Find every number satisfying all the supplied constraints.
+///
+/// Unlike [`chinese_remainder`], the moduli need not be coprime. The solutions form a single
+/// class modulo the least common multiple of the moduli, which is returned as a constraint with
+/// a remainder in `0..modulus`. With no constraints, every number is a solution: `0 mod 1`.
+///
+/// When the constraints are inconsistent, the error names a pair of them which cannot both hold:
+/// a system of congruences is solvable exactly when every pair of them is.
+///
+/// Intermediate values reach the square of the largest modulus, as well as the solution
+/// modulus. If either overflows `N`, the error names the constraint at which it happened; for
+/// large systems use `i128` or [`num::BigInt`].
+///
+/// ```
+/// # use aoc2020::numbers::chinese_remainder::{solve, Constraint, Error};
+/// let constraints = [Constraint::new(6, 5), Constraint::new(4, 3), Constraint::new(9, 2)];
+/// assert_eq!(solve(&constraints), Ok(Constraint::new(36, 11)));
+///
+/// let constraints = [Constraint::new(6, 5), Constraint::new(9, 2), Constraint::new(4, 2)];
+/// assert_eq!(solve(&constraints), Err(Error::Conflict { first: 0, second: 2 }));
+/// ```
+pub fn solve<N>(constraints: &[Constraint<N>]) -> Result<Constraint<N>, Error>
+where
+    N: Integer + Signed + Clone + CheckedMul,
+{
+    let mut solution = Constraint::new(N::one(), N::zero());
+    for (index, constraint) in constraints.iter().enumerate() {
+        if !constraint.modulus.is_positive() {
+            return Err(Error::Modulus(index));
+        }
+        let constraint = Constraint::new(
+            constraint.modulus.clone(),
+            constraint.remainder.mod_floor(&constraint.modulus),
+        );
+        solution = match merge(&solution, &constraint) {
+            Ok(merged) => merged,
+            Err(MergeError::Overflow) => return Err(Error::Overflow(index)),
+            Err(MergeError::Conflict) => {
+                // everything before `index` was consistent, so some earlier constraint
+                // must contradict this one on its own; conflicts are found before any
+                // arithmetic which could overflow
+                let first = constraints[..index]
+                    .iter()
+                    .map(|earlier| {
+                        Constraint::new(
+                            earlier.modulus.clone(),
+                            earlier.remainder.mod_floor(&earlier.modulus),
+                        )
+                    })
+                    .position(|earlier| {
+                        matches!(merge(&earlier, &constraint), Err(MergeError::Conflict))
+                    })
+                    .expect("an inconsistent system has an inconsistent pair");
+                return Err(Error::Conflict {
+                    first,
+                    second: index,
+                });
+            }
+        };
+    }
+    Ok(solution)
+}
+
+/// Solve the linear congruence `a * x ≡ b (mod modulus)`.
+///
+/// The solutions, if any, form a single class modulo `modulus / gcd(a, modulus)`.
+///
+/// ```
+/// # use aoc2020::numbers::chinese_remainder::{solve_linear, Constraint};
+/// assert_eq!(solve_linear(6, 4, 10), Some(Constraint::new(5, 4)));
+/// assert_eq!(solve_linear(6, 3, 10), None);
+/// ```
+pub fn solve_linear<N>(a: N, b: N, modulus: N) -> Option<Constraint<N>>
+where
+    N: Integer + Signed + Clone,
+{
+    if !modulus.is_positive() {
+        return None;
     }
+    let a = a.mod_floor(&modulus);
+    let (g, _, _) = egcd(a.clone(), modulus.clone());
+    if !b.is_multiple_of(&g) {
+        return None;
+    }
+    let modulus = modulus / g.clone();
+    let inverse = mod_inv(a / g.clone(), modulus.clone())?;
+    let remainder = ((b / g).mod_floor(&modulus) * inverse).mod_floor(&modulus);
+    Some(Constraint { modulus, remainder })
 }
 
 /// Find a number `n` which follows the supplied constraints.
@@ -74,24 +232,13 @@ where
 /// n % constraints[k].modulus == constraints[k].remainder
 /// ```
 ///
-/// Returns `None` if the constraint moduli are not all coprime.
+/// Returns the least non-negative such `n`, or `None` if the constraints are inconsistent.
+/// See [`solve`] to learn why.
 pub fn chinese_remainder<N>(constraints: &[Constraint<N>]) -> Option<N>
 where
-    N: Integer + Copy + Product + AddAssign + Signed,
+    N: Integer + Signed + Clone + CheckedMul,
 {
-    let product = constraints
-        .iter()
-        .map(|&constraint| constraint.modulus)
-        .product::<N>();
-
-    let mut sum = N::zero();
-
-    for Constraint { modulus, remainder } in constraints {
-        let p = product / *modulus;
-        sum += *remainder * mod_inv(p, *modulus)? * p;
-    }
-
-    Some(sum % product)
+    solve(constraints).ok().map(|solution| solution.remainder)
 }
 
 #[cfg(test)]
@@ -196,4 +343,94 @@ mod test {
         }
         assert_eq!(n, 3417);
     }
+
+    #[test]
+    fn test_non_coprime() {
+        // every pair shares a factor, but the system is consistent
+        let constraints = [
+            Constraint::new(12, 7),
+            Constraint::new(18, 13),
+            Constraint::new(8, 3),
+        ];
+        let solution = solve(&constraints).unwrap();
+        assert_eq!(solution.modulus, 72);
+        for constraint in &constraints {
+            assert!(constraint.is_satisfied_by(&solution.remainder));
+            assert!(constraint.is_satisfied_by(&(solution.remainder + 5 * solution.modulus)));
+        }
+        assert_eq!(chinese_remainder(&constraints), Some(solution.remainder));
+
+        assert_eq!(
+            solve(&[
+                Constraint::new(3, 1),
+                Constraint::new(5, 2),
+                Constraint::new(0, 2)
+            ]),
+            Err(Error::Modulus(2))
+        );
+        assert_eq!(
+            solve(&[
+                Constraint::new(12, 7),
+                Constraint::new(5, 2),
+                Constraint::new(8, 4)
+            ]),
+            Err(Error::Conflict {
+                first: 0,
+                second: 2
+            })
+        );
+        assert_eq!(solve::<i32>(&[]), Ok(Constraint::new(1, 0)));
+    }
+
+    #[test]
+    fn test_large_moduli() {
+        use num::BigInt;
+
+        // primes near 2^40: their product overflows i64, and the merge needs i128
+        let primes: [i128; 3] = [1_099_511_627_791, 1_099_511_627_803, 1_099_511_627_831];
+        let expect: i128 = 123_456_789_012_345_678_901_234_567;
+        let constraints: Vec<_> = primes
+            .iter()
+            .map(|&prime| Constraint::new(prime, expect % prime))
+            .collect();
+        let product: i128 = primes.iter().product();
+        assert_eq!(
+            chinese_remainder(&constraints),
+            Some(expect.mod_floor(&product))
+        );
+
+        let narrow: Vec<_> = constraints
+            .iter()
+            .map(|c| Constraint::new(c.modulus as i64, c.remainder as i64))
+            .collect();
+        assert_eq!(solve(&narrow), Err(Error::Overflow(1)));
+
+        let big: Vec<_> = constraints
+            .iter()
+            .map(|c| Constraint::new(BigInt::from(c.modulus), BigInt::from(c.remainder)))
+            .collect();
+        let solution = solve(&big).unwrap();
+        assert_eq!(solution.modulus, BigInt::from(product));
+        assert_eq!(solution.remainder, BigInt::from(expect % product));
+    }
+
+    #[test]
+    fn test_linear() {
+        for modulus in 1..30_i64 {
+            for a in -30..30 {
+                for b in -30..30 {
+                    let expect: Vec<_> = (0..modulus)
+                        .filter(|x| (a * x - b).is_multiple_of(&modulus))
+                        .collect();
+                    let found: Vec<_> = match solve_linear(a, b, modulus) {
+                        Some(solution) => (0..modulus)
+                            .filter(|x| solution.is_satisfied_by(x))
+                            .collect(),
+                        None => Vec::new(),
+                    };
+                    assert_eq!(found, expect, "{} * x = {} mod {}", a, b, modulus);
+                }
+            }
+        }
+    }
 }
@@ -1 +1,3 @@
 pub mod chinese_remainder;
+pub mod modular;
+pub mod number_theory;
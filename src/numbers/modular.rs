@@ -0,0 +1,323 @@
+//! Arithmetic modulo a `u64`, including discrete logarithms.
+//!
+//! Products are computed in `u128`, so any modulus up to `u64::MAX` is safe.
+
+use crate::numbers::{
+    chinese_remainder::{self, mod_inv, Constraint},
+    number_theory::factorize,
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// `a * b % modulus`, without overflow.
+pub fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    (a as u128 * b as u128 % modulus as u128) as u64
+}
+
+/// `base.pow(exp) % modulus`, by repeated squaring.
+pub fn pow_mod(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut base = base % modulus;
+    let mut result = 1 % modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exp >>= 1;
+    }
+    result
+}
+
+/// The `x` in `0..modulus` such that `a * x % modulus == 1`, if `a` and `modulus` are coprime.
+pub fn inverse(a: u64, modulus: u64) -> Option<u64> {
+    mod_inv(a as i128, modulus as i128).map(|x| x as u64)
+}
+
+/// An integer modulo some runtime modulus.
+///
+/// Arithmetic between values with different moduli is a logic error; it panics in debug builds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModInt {
+    value: u64,
+    modulus: u64,
+}
+
+impl ModInt {
+    pub fn new(value: u64, modulus: u64) -> ModInt {
+        assert!(modulus > 0, "modulus must be positive");
+        ModInt {
+            value: value % modulus,
+            modulus,
+        }
+    }
+
+    /// The canonical representative, in `0..modulus`.
+    pub fn value(self) -> u64 {
+        self.value
+    }
+
+    pub fn modulus(self) -> u64 {
+        self.modulus
+    }
+
+    pub fn pow(self, exp: u64) -> ModInt {
+        ModInt {
+            value: pow_mod(self.value, exp, self.modulus),
+            ..self
+        }
+    }
+
+    /// The multiplicative inverse of this value, if it is coprime to the modulus.
+    pub fn inverse(self) -> Option<ModInt> {
+        inverse(self.value, self.modulus).map(|value| ModInt { value, ..self })
+    }
+
+    fn with(self, value: u64) -> ModInt {
+        ModInt { value, ..self }
+    }
+
+    fn check(self, other: ModInt) {
+        debug_assert_eq!(self.modulus, other.modulus, "moduli must match");
+    }
+}
+
+impl fmt::Display for ModInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (mod {})", self.value, self.modulus)
+    }
+}
+
+impl Add for ModInt {
+    type Output = ModInt;
+
+    fn add(self, other: ModInt) -> ModInt {
+        self.check(other);
+        let sum = self.value as u128 + other.value as u128;
+        self.with((sum % self.modulus as u128) as u64)
+    }
+}
+
+impl Sub for ModInt {
+    type Output = ModInt;
+
+    fn sub(self, other: ModInt) -> ModInt {
+        self + -other
+    }
+}
+
+impl Mul for ModInt {
+    type Output = ModInt;
+
+    fn mul(self, other: ModInt) -> ModInt {
+        self.check(other);
+        self.with(mul_mod(self.value, other.value, self.modulus))
+    }
+}
+
+impl Neg for ModInt {
+    type Output = ModInt;
+
+    fn neg(self) -> ModInt {
+        if self.value == 0 {
+            self
+        } else {
+            self.with(self.modulus - self.value)
+        }
+    }
+}
+
+impl AddAssign for ModInt {
+    fn add_assign(&mut self, other: ModInt) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for ModInt {
+    fn sub_assign(&mut self, other: ModInt) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign for ModInt {
+    fn mul_assign(&mut self, other: ModInt) {
+        *self = *self * other;
+    }
+}
+
+/// The smallest `x` in `0..order` such that `base.pow(x) == target`, by baby-step giant-step.
+///
+/// `base` must be invertible, and `order` should be a multiple of its multiplicative order;
+/// this takes time and space proportional to `sqrt(order)`.
+pub fn baby_step_giant_step(base: ModInt, target: ModInt, order: u64) -> Option<u64> {
+    let steps = (order as f64).sqrt().ceil() as u64;
+    let steps = steps.max(1);
+
+    // remember the smallest exponent reaching each baby step
+    let mut baby = HashMap::with_capacity(steps as usize);
+    let mut power = base.with(1 % base.modulus);
+    for j in 0..steps {
+        baby.entry(power.value).or_insert(j);
+        power *= base;
+    }
+
+    let giant = base.inverse()?.pow(steps);
+    let mut gamma = target;
+    for i in 0..steps {
+        if let Some(&j) = baby.get(&gamma.value) {
+            let x = i * steps + j;
+            if x < order {
+                return Some(x);
+            }
+        }
+        gamma *= giant;
+    }
+    None
+}
+
+/// The multiplicative order of `a` modulo the prime `p`: the smallest `k > 0` with `a^k == 1`.
+///
+/// Returns `None` when `a` is divisible by `p`.
+pub fn multiplicative_order(a: u64, p: u64) -> Option<u64> {
+    if a.is_multiple_of(p) {
+        return None;
+    }
+    let mut order = p - 1;
+    for (prime, _) in factorize(p - 1) {
+        while order.is_multiple_of(prime) && pow_mod(a, order / prime, p) == 1 {
+            order /= prime;
+        }
+    }
+    Some(order)
+}
+
+/// Is `g` a generator of the multiplicative group modulo the prime `p`?
+pub fn is_primitive_root(g: u64, p: u64) -> bool {
+    multiplicative_order(g, p) == Some(p - 1)
+}
+
+/// The smallest primitive root modulo the prime `p`.
+pub fn primitive_root(p: u64) -> Option<u64> {
+    if p < 2 {
+        return None;
+    }
+    let factors = factorize(p - 1);
+    (1..p).find(|&g| {
+        factors
+            .iter()
+            .all(|&(prime, _)| pow_mod(g, (p - 1) / prime, p) != 1)
+    })
+}
+
+/// The smallest `x >= 0` such that `base.pow(x) % p == target % p`, for a prime `p`.
+///
+/// This is the Pohlig–Hellman algorithm: the problem is solved within the subgroup for each
+/// prime power dividing the order of `base`, and the results are combined with the Chinese
+/// Remainder Theorem. It is fast whenever the order of `base` has only small prime factors,
+/// and no slower than [`baby_step_giant_step`] otherwise.
+///
+/// ```
+/// # use aoc2020::numbers::modular::{discrete_log, pow_mod};
+/// let p = 20201227;
+/// assert_eq!(discrete_log(7, 5764801, p), Some(8));
+/// assert_eq!(pow_mod(7, 8, p), 5764801);
+/// // 4 is a quadratic residue, so it never generates 3
+/// assert_eq!(discrete_log(4, 3, 7), None);
+/// ```
+pub fn discrete_log(base: u64, target: u64, p: u64) -> Option<u64> {
+    let order = multiplicative_order(base, p)?;
+    let (base, target) = (ModInt::new(base, p), ModInt::new(target, p));
+
+    let mut constraints = Vec::new();
+    for (prime, exponent) in factorize(order) {
+        let prime_power = prime.pow(exponent);
+        // project into the subgroup of order `prime_power`
+        let cofactor = order / prime_power;
+        let (g, h) = (base.pow(cofactor), target.pow(cofactor));
+        // an element of order `prime`, against which each base-`prime` digit is found
+        let gamma = g.pow(prime_power / prime);
+        let g_inverse = g.inverse()?;
+
+        let mut x = 0;
+        let mut place = 1;
+        for k in 0..exponent {
+            let shifted = (g_inverse.pow(x) * h).pow(prime.pow(exponent - 1 - k));
+            let digit = baby_step_giant_step(gamma, shifted, prime)?;
+            x += digit * place;
+            place *= prime;
+        }
+        constraints.push(Constraint::new(prime_power as i128, x as i128));
+    }
+
+    let x = chinese_remainder::solve(&constraints).ok()?.remainder as u64;
+    // if `target` is outside the subgroup generated by `base`, the digits are meaningless
+    if base.pow(x) == target {
+        Some(x)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_mod_int() {
+        let m = u64::MAX - 58; // the largest prime below 2^64
+        let a = ModInt::new(u64::MAX - 100, m);
+        let b = ModInt::new(12345, m);
+        assert_eq!((a + b).value(), 12345 - 42);
+        assert_eq!((a - b) + b, a);
+        assert_eq!(a * a.inverse().unwrap(), ModInt::new(1, m));
+        assert_eq!(a.pow(m - 1), ModInt::new(1, m));
+        assert_eq!(-ModInt::new(0, m), ModInt::new(0, m));
+
+        assert_eq!(inverse(3, 10), Some(7));
+        assert_eq!(inverse(4, 10), None);
+        assert_eq!(pow_mod(5, 0, 1), 0);
+        assert_eq!(pow_mod(2, 62, u64::MAX), 1 << 62);
+    }
+
+    #[test]
+    fn test_primitive_roots() {
+        assert_eq!(primitive_root(2), Some(1));
+        assert_eq!(primitive_root(7), Some(3));
+        assert_eq!(primitive_root(20201227), Some(7));
+        assert!(is_primitive_root(7, 20201227));
+        assert_eq!(multiplicative_order(2, 7), Some(3));
+        assert_eq!(multiplicative_order(14, 7), None);
+    }
+
+    #[test]
+    fn test_discrete_log_matches_brute_force() {
+        for &p in &[2, 3, 13, 101, 1009] {
+            for base in 1..p.min(40) {
+                let mut power = 1;
+                let mut first_seen = HashMap::new();
+                for x in 0..p {
+                    first_seen.entry(power).or_insert(x);
+                    power = power * base % p;
+                }
+                for target in 0..p {
+                    let expect = first_seen.get(&target).copied();
+                    assert_eq!(discrete_log(base, target, p), expect);
+                    let bsgs =
+                        baby_step_giant_step(ModInt::new(base, p), ModInt::new(target, p), p);
+                    assert_eq!(bsgs, expect, "{}^x = {} mod {}", base, target, p);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_discrete_log_large() {
+        // 2^61 - 1 is prime, and the order of its group has only moderate factors
+        let p = (1 << 61) - 1;
+        let g = primitive_root(p).unwrap();
+        let x = 1_234_567_890_123_456_789;
+        assert_eq!(discrete_log(g, pow_mod(g, x, p), p), Some(x));
+    }
+}
@@ -0,0 +1,298 @@
+//! Primes, factorization, and the functions built on them.
+
+use crate::numbers::modular::{mul_mod, pow_mod};
+use num::integer::{gcd, Integer};
+
+/// The primes up to some limit, by the sieve of Eratosthenes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sieve {
+    composite: Vec<bool>,
+}
+
+impl Sieve {
+    /// Sieve every number up to and including `limit`.
+    pub fn new(limit: usize) -> Sieve {
+        let mut composite = vec![false; limit + 1];
+        for n in composite.iter_mut().take(2) {
+            *n = true;
+        }
+        let mut p = 2;
+        while p * p <= limit {
+            if !composite[p] {
+                for multiple in (p * p..=limit).step_by(p) {
+                    composite[multiple] = true;
+                }
+            }
+            p += 1;
+        }
+        Sieve { composite }
+    }
+
+    /// The largest number this sieve knows about.
+    pub fn limit(&self) -> usize {
+        self.composite.len() - 1
+    }
+
+    /// Is `n` prime?
+    ///
+    /// Panics if `n` exceeds the limit of this sieve.
+    pub fn is_prime(&self, n: usize) -> bool {
+        assert!(n <= self.limit(), "{} is beyond the sieve's limit", n);
+        !self.composite[n]
+    }
+
+    /// Iterate over the primes up to the limit, in ascending order.
+    pub fn primes(&self) -> impl '_ + Iterator<Item = usize> {
+        self.composite
+            .iter()
+            .enumerate()
+            .filter_map(|(n, &composite)| if composite { None } else { Some(n) })
+    }
+}
+
+const SMALL_PRIMES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Is `n` prime?
+///
+/// This is the Miller–Rabin test. Using the first twelve primes as witnesses makes it
+/// deterministic for every `u64`.
+pub fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for &p in &SMALL_PRIMES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    // n - 1 == d * 2^s, with d odd
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    SMALL_PRIMES.iter().all(|&witness| {
+        let mut x = pow_mod(witness, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// Find a nontrivial factor of the odd composite `n`, by Pollard's rho with Brent's cycle finding.
+fn find_factor(n: u64) -> u64 {
+    // gcds are expensive, so multiply this many differences together between them
+    const BATCH: u64 = 128;
+
+    for c in 1_u64.. {
+        let step = |x: u64| ((x as u128 * x as u128 + c as u128) % n as u128) as u64;
+        let (mut x, mut y, mut saved) = (2, 2, 2);
+        let (mut r, mut q, mut g) = (1, 1, 1);
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = step(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                saved = y;
+                for _ in 0..BATCH.min(r - k) {
+                    y = step(y);
+                    q = mul_mod(q, x.abs_diff(y), n);
+                }
+                g = gcd(q, n);
+                k += BATCH;
+            }
+            r *= 2;
+        }
+        if g == n {
+            // the batch overshot the factor; retrace it one step at a time
+            loop {
+                saved = step(saved);
+                g = gcd(x.abs_diff(saved), n);
+                if g > 1 {
+                    break;
+                }
+            }
+        }
+        if g != n {
+            return g;
+        }
+        // this sequence cycled without separating the factors; try another
+    }
+    unreachable!("every odd composite has a factor")
+}
+
+/// The prime factors of `n` with their multiplicities, in ascending order.
+///
+/// Small factors are found by trial division, and the rest by Pollard's rho.
+///
+/// ```
+/// # use aoc2020::numbers::number_theory::factorize;
+/// assert_eq!(factorize(20201226), vec![(2, 1), (3, 1), (29, 1), (116099, 1)]);
+/// assert_eq!(factorize(1), vec![]);
+/// ```
+pub fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+    let mut primes = Vec::new();
+    if n == 0 {
+        return Vec::new();
+    }
+    for &p in &SMALL_PRIMES {
+        while n.is_multiple_of(p) {
+            primes.push(p);
+            n /= p;
+        }
+    }
+
+    let mut pending = vec![n];
+    while let Some(n) = pending.pop() {
+        if n == 1 {
+            continue;
+        }
+        if is_prime(n) {
+            primes.push(n);
+        } else {
+            let factor = find_factor(n);
+            pending.push(factor);
+            pending.push(n / factor);
+        }
+    }
+
+    primes.sort_unstable();
+    let mut factors: Vec<(u64, u32)> = Vec::new();
+    for p in primes {
+        match factors.last_mut() {
+            Some((prime, exponent)) if *prime == p => *exponent += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+    factors
+}
+
+/// Every positive divisor of `n`, in ascending order.
+pub fn divisors(n: u64) -> Vec<u64> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut divisors = vec![1];
+    for (prime, exponent) in factorize(n) {
+        let smaller = divisors.len();
+        let mut power = 1;
+        for _ in 0..exponent {
+            power *= prime;
+            for idx in 0..smaller {
+                divisors.push(divisors[idx] * power);
+            }
+        }
+    }
+    divisors.sort_unstable();
+    divisors
+}
+
+/// Euler's totient: how many numbers in `1..=n` are coprime to `n`.
+pub fn totient(n: u64) -> u64 {
+    factorize(n)
+        .into_iter()
+        .fold(n, |totient, (prime, _)| totient / prime * (prime - 1))
+}
+
+/// The greatest common divisor of every item; zero if there are none.
+pub fn gcd_all<N: Integer>(items: impl IntoIterator<Item = N>) -> N {
+    items.into_iter().fold(N::zero(), |a, b| a.gcd(&b))
+}
+
+/// The least common multiple of every item; one if there are none.
+pub fn lcm_all<N: Integer>(items: impl IntoIterator<Item = N>) -> N {
+    items.into_iter().fold(N::one(), |a, b| a.lcm(&b))
+}
+
+/// Find the first pair of items, by index, which share a factor; `None` if they are all coprime.
+pub fn shared_factor<N: Integer>(items: &[N]) -> Option<(usize, usize)> {
+    (0..items.len()).find_map(|second| {
+        (0..second)
+            .find(|&first| !items[first].gcd(&items[second]).is_one())
+            .map(|first| (first, second))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sieve_agrees_with_miller_rabin() {
+        let sieve = Sieve::new(10_000);
+        assert_eq!(sieve.limit(), 10_000);
+        assert_eq!(sieve.primes().count(), 1229);
+        assert_eq!(
+            sieve.primes().take(5).collect::<Vec<_>>(),
+            vec![2, 3, 5, 7, 11]
+        );
+        for n in 0..=10_000 {
+            assert_eq!(sieve.is_prime(n), is_prime(n as u64), "{}", n);
+        }
+        assert!(!Sieve::new(1).primes().any(|_| true));
+    }
+
+    #[test]
+    fn test_large_primes() {
+        assert!(is_prime(20201227));
+        assert!(is_prime((1 << 61) - 1));
+        assert!(is_prime(u64::MAX - 58));
+        // strong pseudoprimes to many small bases
+        assert!(!is_prime(3_215_031_751));
+        assert!(!is_prime(3_825_123_056_546_413_051));
+        assert!(!is_prime(4_294_967_297)); // 641 * 6700417
+    }
+
+    #[test]
+    fn test_factorize() {
+        for n in 1..2000_u64 {
+            let factors = factorize(n);
+            assert_eq!(factors.iter().map(|&(p, e)| p.pow(e)).product::<u64>(), n);
+            assert!(factors.iter().all(|&(p, _)| is_prime(p)));
+        }
+        // a semiprime whose factors are both beyond trial division
+        let (p, q) = (4_294_967_291, 4_294_967_279);
+        assert_eq!(factorize(p * q), vec![(q, 1), (p, 1)]);
+        assert_eq!(
+            factorize(u64::MAX),
+            vec![
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65537, 1),
+                (6700417, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_divisor_functions() {
+        assert_eq!(divisors(36), vec![1, 2, 3, 4, 6, 9, 12, 18, 36]);
+        assert_eq!(divisors(1), vec![1]);
+        assert!(divisors(0).is_empty());
+        for n in 1..500_u64 {
+            let coprime = (1..=n).filter(|&k| gcd(k, n) == 1).count() as u64;
+            assert_eq!(totient(n), coprime, "totient({})", n);
+            assert_eq!(
+                divisors(n),
+                (1..=n).filter(|k| n % k == 0).collect::<Vec<_>>()
+            );
+        }
+
+        assert_eq!(gcd_all(vec![12, 18, 30]), 6);
+        assert_eq!(gcd_all(Vec::<i64>::new()), 0);
+        assert_eq!(lcm_all(vec![4, 6, 10]), 60);
+        assert_eq!(lcm_all(Vec::<i64>::new()), 1);
+        assert_eq!(shared_factor(&[7, 13, 59, 31, 19]), None);
+        assert_eq!(shared_factor(&[7, 12, 59, 18]), Some((1, 3)));
+    }
+}
@@ -0,0 +1,180 @@
+//! A min-priority queue over a dense range of indices, supporting decrease-key.
+
+/// Sentinel position for indices which are not in the heap.
+const ABSENT: usize = usize::MAX;
+
+/// A binary min-heap of the indices `0..capacity`, each with a priority.
+///
+/// Unlike [`std::collections::BinaryHeap`], each index is present at most once, membership can be
+/// checked in constant time, and an index's priority can be lowered in place. This makes it
+/// suitable as the open set of Dijkstra's algorithm or A*, where the indices identify nodes.
+///
+/// Ties between equal priorities are broken arbitrarily.
+#[derive(Clone, Debug)]
+pub struct IndexedPriorityQueue<P> {
+    /// `(priority, index)` pairs in heap order
+    heap: Vec<(P, usize)>,
+    /// `positions[index]` is the position of `index` within `heap`, or `ABSENT`
+    positions: Vec<usize>,
+}
+
+impl<P: Ord + Copy> IndexedPriorityQueue<P> {
+    /// Create an empty queue which accepts the indices `0..capacity`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        IndexedPriorityQueue {
+            heap: Vec::new(),
+            positions: vec![ABSENT; capacity],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Is this index currently queued?
+    ///
+    /// Panics if `index` is not less than the capacity.
+    pub fn contains(&self, index: usize) -> bool {
+        self.positions[index] != ABSENT
+    }
+
+    /// The priority of a queued index.
+    pub fn priority(&self, index: usize) -> Option<P> {
+        match self.positions.get(index) {
+            Some(&position) if position != ABSENT => Some(self.heap[position].0),
+            _ => None,
+        }
+    }
+
+    /// Queue `index` with the given priority, or lower its priority if it is already queued.
+    ///
+    /// Returns `false` and changes nothing if the index was already queued with a priority no
+    /// greater than `priority`.
+    ///
+    /// Panics if `index` is not less than the capacity.
+    pub fn push_or_decrease(&mut self, index: usize, priority: P) -> bool {
+        let position = match self.positions[index] {
+            ABSENT => {
+                self.heap.push((priority, index));
+                self.positions[index] = self.heap.len() - 1;
+                self.heap.len() - 1
+            }
+            position if priority < self.heap[position].0 => {
+                self.heap[position].0 = priority;
+                position
+            }
+            _ => return false,
+        };
+        self.sift_up(position);
+        true
+    }
+
+    /// The index with the least priority, without removing it.
+    pub fn peek(&self) -> Option<(usize, P)> {
+        self.heap
+            .first()
+            .map(|&(priority, index)| (index, priority))
+    }
+
+    /// Remove and return the index with the least priority.
+    pub fn pop(&mut self) -> Option<(usize, P)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        let last = self.heap.len() - 1;
+        self.swap(0, last);
+        let (priority, index) = self.heap.pop().expect("heap is not empty; qed");
+        self.positions[index] = ABSENT;
+        if !self.heap.is_empty() {
+            self.sift_down(0);
+        }
+        Some((index, priority))
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.positions[self.heap[a].1] = a;
+        self.positions[self.heap[b].1] = b;
+    }
+
+    fn sift_up(&mut self, mut position: usize) {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if self.heap[position].0 >= self.heap[parent].0 {
+                break;
+            }
+            self.swap(position, parent);
+            position = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut position: usize) {
+        loop {
+            let left = 2 * position + 1;
+            let right = left + 1;
+            let mut least = position;
+            if left < self.heap.len() && self.heap[left].0 < self.heap[least].0 {
+                least = left;
+            }
+            if right < self.heap.len() && self.heap[right].0 < self.heap[least].0 {
+                least = right;
+            }
+            if least == position {
+                break;
+            }
+            self.swap(position, least);
+            position = least;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pops_in_priority_order() {
+        let priorities = [7, 3, 9, 1, 4, 8, 2, 6, 0, 5];
+        let mut queue = IndexedPriorityQueue::with_capacity(priorities.len());
+        for (index, &priority) in priorities.iter().enumerate() {
+            assert!(queue.push_or_decrease(index, priority));
+        }
+        assert_eq!(queue.len(), priorities.len());
+
+        let popped: Vec<_> = std::iter::from_fn(|| queue.pop())
+            .map(|(_, priority)| priority)
+            .collect();
+        assert_eq!(popped, (0..10).collect::<Vec<_>>());
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_decrease_key() {
+        let mut queue = IndexedPriorityQueue::with_capacity(4);
+        queue.push_or_decrease(0, 10);
+        queue.push_or_decrease(1, 20);
+        queue.push_or_decrease(2, 30);
+
+        assert!(!queue.push_or_decrease(0, 15));
+        assert_eq!(queue.priority(0), Some(10));
+        assert!(queue.push_or_decrease(2, 5));
+        assert_eq!(queue.peek(), Some((2, 5)));
+        assert_eq!(queue.len(), 3);
+
+        assert_eq!(queue.pop(), Some((2, 5)));
+        assert!(!queue.contains(2));
+        assert!(!queue.contains(3));
+        assert_eq!(queue.priority(3), None);
+        assert_eq!(queue.pop(), Some((0, 10)));
+        assert_eq!(queue.pop(), Some((1, 20)));
+        assert_eq!(queue.pop(), None);
+
+        // popped indices may be queued again
+        assert!(queue.push_or_decrease(2, 1));
+        assert_eq!(queue.pop(), Some((2, 1)));
+    }
+}